@@ -0,0 +1,45 @@
+# Loaded through `bash --rcfile`. Reads the user's startup files the way bash itself would,
+# then reports the working directory (OSC 7) and prompt marks (OSC 133).
+if [ -n "$NLK_SHELL_LOGIN" ]; then
+    unset NLK_SHELL_LOGIN
+    [ -r /etc/profile ] && . /etc/profile
+    if [ -r "$HOME/.bash_profile" ]; then
+        . "$HOME/.bash_profile"
+    elif [ -r "$HOME/.bash_login" ]; then
+        . "$HOME/.bash_login"
+    elif [ -r "$HOME/.profile" ]; then
+        . "$HOME/.profile"
+    fi
+else
+    [ -r "$HOME/.bashrc" ] && . "$HOME/.bashrc"
+fi
+
+if [ -z "$__nlk_integration" ]; then
+    __nlk_integration=1
+    __nlk_command_running=
+
+    __nlk_preexec() {
+        [ -n "$COMP_LINE" ] && return
+        [ -n "$__nlk_command_running" ] && return
+        [ "$BASH_COMMAND" = "__nlk_precmd" ] && return
+        __nlk_command_running=1
+        local command
+        command=$(HISTTIMEFORMAT= builtin history 1)
+        command="${command#*[0-9]  }"
+        printf '\033]133;C;cmdline=%s\007' "$command"
+    }
+
+    __nlk_precmd() {
+        local status=$?
+        if [ -n "$__nlk_command_running" ]; then
+            printf '\033]133;D;%s\007' "$status"
+            __nlk_command_running=
+        fi
+        printf '\033]7;file://%s%s\007' "${HOSTNAME:-localhost}" "$PWD"
+        printf '\033]133;A\007'
+        return $status
+    }
+
+    trap '__nlk_preexec' DEBUG
+    PROMPT_COMMAND="__nlk_precmd${PROMPT_COMMAND:+;$PROMPT_COMMAND}"
+fi
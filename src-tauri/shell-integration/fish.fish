@@ -0,0 +1,14 @@
+# Loaded through `fish --init-command`. Reports the working directory (OSC 7) and
+# prompt marks (OSC 133).
+function __nlk_preexec --on-event fish_preexec
+    printf '\033]133;C;cmdline=%s\007' "$argv"
+end
+
+function __nlk_postexec --on-event fish_postexec
+    printf '\033]133;D;%s\007' $status
+end
+
+function __nlk_prompt --on-event fish_prompt
+    printf '\033]7;file://%s%s\007' (prompt_hostname) "$PWD"
+    printf '\033]133;A\007'
+end
@@ -0,0 +1,261 @@
+use crate::{broadcast, detach, dropdown, error::AppError, git, layout, notifications};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, path::PathBuf, sync::Mutex};
+use tauri::{Emitter, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutEvent, ShortcutState};
+
+/// Actions the backend knows about. The ids are stable, since bindings are saved under them.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Action {
+    #[serde(rename = "terminal.newTab")]
+    NewTab,
+    #[serde(rename = "tasks.run")]
+    RunTask,
+    #[serde(rename = "git.refresh")]
+    GitRefresh,
+    #[serde(rename = "broadcast.toggle")]
+    ToggleBroadcast,
+    #[serde(rename = "window.toggleDropdown")]
+    ToggleDropdown,
+}
+
+const ACTIONS: [Action; 5] = [
+    Action::NewTab,
+    Action::RunTask,
+    Action::GitRefresh,
+    Action::ToggleBroadcast,
+    Action::ToggleDropdown,
+];
+
+impl Action {
+    fn title(self) -> &'static str {
+        match self {
+            Action::NewTab => "New Tab",
+            Action::RunTask => "Run Task",
+            Action::GitRefresh => "Refresh Git Status",
+            Action::ToggleBroadcast => "Toggle Broadcast Input",
+            Action::ToggleDropdown => "Toggle Dropdown Terminal",
+        }
+    }
+
+    fn default_binding(self) -> Option<KeyBinding> {
+        let (accelerator, global) = match self {
+            Action::NewTab => ("CmdOrCtrl+Shift+T", false),
+            Action::RunTask => ("CmdOrCtrl+Shift+R", false),
+            Action::GitRefresh => ("CmdOrCtrl+Shift+G", false),
+            Action::ToggleBroadcast => ("CmdOrCtrl+Shift+B", false),
+            Action::ToggleDropdown => ("Alt+Backquote", true),
+        };
+        Some(KeyBinding {
+            accelerator: accelerator.to_string(),
+            global,
+        })
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyBinding {
+    /// Accelerator such as `CmdOrCtrl+Shift+T`.
+    accelerator: String,
+    /// Register system-wide, so the action fires even when no window is focused.
+    #[serde(default)]
+    global: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionInfo {
+    id: Action,
+    title: &'static str,
+    binding: Option<KeyBinding>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ActionTriggeredEvent {
+    action: Action,
+    args: Option<serde_json::Value>,
+    /// Tab the action applies to, if any.
+    tab_id: Option<String>,
+}
+
+/// Global shortcuts currently registered, by shortcut id.
+#[derive(Default)]
+pub struct ActionState {
+    shortcuts: Mutex<HashMap<u32, Action>>,
+}
+
+/// User overrides by action. A `None` entry unbinds the action.
+type Bindings = HashMap<Action, Option<KeyBinding>>;
+
+fn bindings_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_config_dir()
+        .map_err(|error| format!("failed to resolve config dir: {error}"))?;
+    Ok(dir.join("keybindings.json"))
+}
+
+fn load_bindings(app: &tauri::AppHandle) -> Result<Bindings, String> {
+    let path = bindings_path(app)?;
+    if !path.exists() {
+        return Ok(Bindings::new());
+    }
+
+    let raw = std::fs::read_to_string(&path).map_err(|error| format!("failed to read keybindings: {error}"))?;
+    serde_json::from_str(&raw).map_err(|error| format!("failed to parse keybindings: {error}"))
+}
+
+fn save_bindings(app: &tauri::AppHandle, bindings: &Bindings) -> Result<(), String> {
+    let path = bindings_path(app)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|error| format!("failed to create config dir: {error}"))?;
+    }
+
+    let raw =
+        serde_json::to_string_pretty(bindings).map_err(|error| format!("failed to encode keybindings: {error}"))?;
+    std::fs::write(&path, raw).map_err(|error| format!("failed to write keybindings: {error}"))
+}
+
+fn effective_binding(bindings: &Bindings, action: Action) -> Option<KeyBinding> {
+    match bindings.get(&action) {
+        Some(binding) => binding.clone(),
+        None => action.default_binding(),
+    }
+}
+
+/// Replaces the registered global shortcuts with the current `global` bindings. Bindings that
+/// fail to parse or are taken by another application are skipped and returned as errors.
+pub fn register_global_shortcuts(app: &tauri::AppHandle) -> Vec<String> {
+    let bindings = match load_bindings(app) {
+        Ok(bindings) => bindings,
+        Err(error) => return vec![error],
+    };
+
+    let state = app.state::<ActionState>();
+    let Ok(mut shortcuts) = state.shortcuts.lock() else {
+        return vec!["failed to lock action shortcuts".to_string()];
+    };
+    let global_shortcut = app.global_shortcut();
+    let _ = global_shortcut.unregister_all();
+    shortcuts.clear();
+
+    let mut errors = Vec::new();
+    for action in ACTIONS {
+        let Some(binding) = effective_binding(&bindings, action).filter(|binding| binding.global) else {
+            continue;
+        };
+        let shortcut = match binding.accelerator.parse::<Shortcut>() {
+            Ok(shortcut) => shortcut,
+            Err(error) => {
+                errors.push(format!("invalid shortcut {}: {error}", binding.accelerator));
+                continue;
+            }
+        };
+        match global_shortcut.register(shortcut) {
+            Ok(()) => {
+                shortcuts.insert(shortcut.id(), action);
+            }
+            Err(error) => errors.push(format!("failed to register {}: {error}", binding.accelerator)),
+        }
+    }
+    errors
+}
+
+/// Handler for the global-shortcut plugin.
+pub fn handle_shortcut(app: &tauri::AppHandle, shortcut: &Shortcut, event: ShortcutEvent) {
+    if event.state() != ShortcutState::Pressed {
+        return;
+    }
+
+    let action = app
+        .state::<ActionState>()
+        .shortcuts
+        .lock()
+        .ok()
+        .and_then(|shortcuts| shortcuts.get(&shortcut.id()).copied());
+    if let Some(action) = action {
+        let _ = dispatch(app, action, None);
+    }
+}
+
+/// Runs `action`. Actions that need the frontend, like opening a tab, bring a window up and are
+/// carried out by it in response to the `action-triggered` event, which is emitted for every action.
+fn dispatch(app: &tauri::AppHandle, action: Action, args: Option<serde_json::Value>) -> Result<(), AppError> {
+    let tab_id = notifications::active_tab(app);
+    match action {
+        Action::NewTab | Action::RunTask => detach::reopen(app),
+        Action::GitRefresh => git::watcher::refresh_all(app)?,
+        Action::ToggleBroadcast => {
+            let group_id = args
+                .as_ref()
+                .and_then(|args| args.get("groupId"))
+                .and_then(|group_id| group_id.as_str())
+                .map(str::to_string)
+                .or_else(|| {
+                    tab_id
+                        .as_deref()
+                        .and_then(|tab_id| layout::pane_group(app, tab_id))
+                        .map(|(group_id, _)| group_id)
+                })
+                .ok_or_else(|| AppError::invalid("no pane group to toggle broadcast for"))?;
+            broadcast::toggle_group(app, group_id, None)?;
+        }
+        Action::ToggleDropdown => {
+            dropdown::toggle(app)?;
+        }
+    }
+
+    let _ = app.emit("action-triggered", ActionTriggeredEvent { action, args, tab_id });
+    Ok(())
+}
+
+#[tauri::command]
+pub fn list_actions(app: tauri::AppHandle) -> Result<Vec<ActionInfo>, AppError> {
+    let bindings = load_bindings(&app)?;
+    Ok(ACTIONS
+        .into_iter()
+        .map(|action| ActionInfo {
+            id: action,
+            title: action.title(),
+            binding: effective_binding(&bindings, action),
+        })
+        .collect())
+}
+
+// Async because toggling the dropdown may create a window, which deadlocks on the main thread on Windows.
+#[tauri::command]
+pub async fn dispatch_action(
+    action: Action,
+    args: Option<serde_json::Value>,
+    app: tauri::AppHandle,
+) -> Result<(), AppError> {
+    dispatch(&app, action, args)
+}
+
+/// Rebinds `action`; `None` unbinds it. Returns the global shortcuts that could not be registered.
+#[tauri::command]
+pub fn set_action_binding(
+    action: Action,
+    binding: Option<KeyBinding>,
+    app: tauri::AppHandle,
+) -> Result<Vec<String>, AppError> {
+    if let Some(binding) = &binding {
+        binding
+            .accelerator
+            .parse::<Shortcut>()
+            .map_err(|error| AppError::invalid(format!("invalid shortcut {}: {error}", binding.accelerator)))?;
+    }
+
+    let mut bindings = load_bindings(&app)?;
+    bindings.insert(action, binding);
+    save_bindings(&app, &bindings)?;
+    Ok(register_global_shortcuts(&app))
+}
+
+#[tauri::command]
+pub fn reset_action_bindings(app: tauri::AppHandle) -> Result<Vec<String>, AppError> {
+    save_bindings(&app, &Bindings::new())?;
+    Ok(register_global_shortcuts(&app))
+}
@@ -0,0 +1,124 @@
+use crate::{error::AppError, notifications, terminal::OutputObserver, windows};
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+use tauri::Manager;
+
+/// Silence after which a background tab that was producing output counts as idle again.
+const IDLE_AFTER: Duration = Duration::from_secs(3);
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Default)]
+struct TabActivity {
+    last_output: Option<Instant>,
+    /// Set between `terminal-activity` and the matching `terminal-idle`.
+    active: bool,
+    unread: bool,
+}
+
+/// Output activity of tabs that are not in the foreground, for activity badges.
+#[derive(Default)]
+pub struct ActivityState {
+    tabs: Mutex<HashMap<String, TabActivity>>,
+    watching: AtomicBool,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TerminalActivityEvent {
+    tab_id: String,
+}
+
+impl ActivityState {
+    fn record(&self, app: &tauri::AppHandle, tab_id: &str) {
+        let focused = notifications::is_active_tab(app, tab_id);
+        let Ok(mut tabs) = self.tabs.lock() else {
+            return;
+        };
+        let tab = tabs.entry(tab_id.to_string()).or_default();
+        tab.last_output = Some(Instant::now());
+        if focused || tab.active {
+            return;
+        }
+
+        tab.active = true;
+        tab.unread = true;
+        drop(tabs);
+        windows::emit(
+            app,
+            tab_id,
+            "terminal-activity",
+            TerminalActivityEvent {
+                tab_id: tab_id.to_string(),
+            },
+        );
+
+        if !self.watching.swap(true, Ordering::SeqCst) {
+            watch_idle(app.clone());
+        }
+    }
+}
+
+/// Emits `terminal-idle` for active tabs that have been quiet for `IDLE_AFTER`.
+fn watch_idle(app: tauri::AppHandle) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(IDLE_CHECK_INTERVAL);
+        let state = app.state::<ActivityState>();
+        let idle = match state.tabs.lock() {
+            Ok(mut tabs) => tabs
+                .iter_mut()
+                .filter(|(_, tab)| tab.active && tab.last_output.is_some_and(|last| last.elapsed() >= IDLE_AFTER))
+                .map(|(tab_id, tab)| {
+                    tab.active = false;
+                    tab_id.clone()
+                })
+                .collect::<Vec<_>>(),
+            Err(_) => continue,
+        };
+
+        for tab_id in idle {
+            let event = TerminalActivityEvent { tab_id: tab_id.clone() };
+            windows::emit(&app, &tab_id, "terminal-idle", event);
+        }
+    });
+}
+
+pub fn forget_tab(app: &tauri::AppHandle, tab_id: &str) {
+    if let Ok(mut tabs) = app.state::<ActivityState>().tabs.lock() {
+        tabs.remove(tab_id);
+    }
+}
+
+pub struct ActivityObserver;
+
+impl OutputObserver for ActivityObserver {
+    fn observe(&mut self, app: &tauri::AppHandle, tab_id: &str, _bytes: &[u8]) {
+        app.state::<ActivityState>().record(app, tab_id);
+    }
+}
+
+/// Clears the activity of the tab that just came to the foreground.
+pub fn mark_read(app: &tauri::AppHandle, tab_id: &str) {
+    if let Ok(mut tabs) = app.state::<ActivityState>().tabs.lock() {
+        if let Some(tab) = tabs.get_mut(tab_id) {
+            tab.active = false;
+            tab.unread = false;
+        }
+    }
+}
+
+/// Whether the tab printed anything since it was last focused.
+#[tauri::command]
+pub fn session_unread(tab_id: String, state: tauri::State<ActivityState>) -> Result<bool, AppError> {
+    let tabs = state
+        .tabs
+        .lock()
+        .map_err(|_| "failed to lock terminal activity".to_string())?;
+    Ok(tabs.get(&tab_id).is_some_and(|tab| tab.unread))
+}
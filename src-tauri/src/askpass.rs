@@ -0,0 +1,217 @@
+use crate::error::AppError;
+use serde::Serialize;
+use std::{
+    collections::{hash_map::RandomState, HashMap},
+    hash::{BuildHasher, Hasher},
+    io::{BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc, Arc, Mutex,
+    },
+    time::Duration,
+};
+use tauri::{Emitter, Manager};
+
+const PORT_ENV: &str = "NLK_ASKPASS_PORT";
+const TOKEN_ENV: &str = "NLK_ASKPASS_TOKEN";
+const PROMPT_TIMEOUT: Duration = Duration::from_secs(300);
+
+type PendingRequests = Arc<Mutex<HashMap<String, mpsc::Sender<Option<String>>>>>;
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CredentialRequestEvent {
+    id: String,
+    prompt: String,
+    secret: bool,
+}
+
+struct AskpassServer {
+    port: u16,
+    token: String,
+}
+
+#[derive(Default)]
+pub struct AskpassState {
+    server: Mutex<Option<AskpassServer>>,
+    pending: PendingRequests,
+    next_id: Arc<AtomicU64>,
+}
+
+fn random_token() -> String {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_nanos())
+        .unwrap_or(0);
+
+    (0..2)
+        .map(|round| {
+            let mut hasher = RandomState::new().build_hasher();
+            hasher.write_u128(nanos);
+            hasher.write_u32(std::process::id());
+            hasher.write_u8(round);
+            format!("{:016x}", hasher.finish())
+        })
+        .collect()
+}
+
+/// Answers one helper connection: `<token>\n<prompt>\n` in, the user's answer out. Closing the
+/// connection without an answer makes the helper exit non-zero, which git treats as cancel.
+fn handle_connection(
+    stream: TcpStream,
+    token: &str,
+    app: &tauri::AppHandle,
+    pending: &PendingRequests,
+    next_id: &AtomicU64,
+) -> Result<(), String> {
+    let mut reader = BufReader::new(stream.try_clone().map_err(|error| error.to_string())?);
+    let mut received_token = String::new();
+    let mut prompt = String::new();
+    reader.read_line(&mut received_token).map_err(|error| error.to_string())?;
+    reader.read_line(&mut prompt).map_err(|error| error.to_string())?;
+
+    if received_token.trim_end() != token {
+        return Err("invalid askpass token".to_string());
+    }
+
+    let prompt = prompt.trim_end().to_string();
+    let id = format!("credential-{}", next_id.fetch_add(1, Ordering::Relaxed));
+    let (sender, receiver) = mpsc::channel();
+    pending
+        .lock()
+        .map_err(|_| "failed to lock credential requests".to_string())?
+        .insert(id.clone(), sender);
+
+    let lowered = prompt.to_lowercase();
+    let _ = app.emit(
+        "git-credential-request",
+        CredentialRequestEvent {
+            id: id.clone(),
+            secret: lowered.contains("password") || lowered.contains("passphrase") || lowered.contains("token"),
+            prompt,
+        },
+    );
+
+    let answer = receiver.recv_timeout(PROMPT_TIMEOUT).ok().flatten();
+    if let Ok(mut pending) = pending.lock() {
+        pending.remove(&id);
+    }
+
+    if let Some(answer) = answer {
+        let mut stream = stream;
+        stream
+            .write_all(format!("{answer}\n").as_bytes())
+            .map_err(|error| error.to_string())?;
+    }
+
+    Ok(())
+}
+
+fn start_server(app: &tauri::AppHandle, state: &AskpassState) -> Result<AskpassServer, String> {
+    let listener =
+        TcpListener::bind("127.0.0.1:0").map_err(|error| format!("failed to start askpass listener: {error}"))?;
+    let port = listener
+        .local_addr()
+        .map_err(|error| format!("failed to read askpass address: {error}"))?
+        .port();
+    let token = random_token();
+
+    let app_handle = app.clone();
+    let pending = Arc::clone(&state.pending);
+    let next_id = Arc::clone(&state.next_id);
+    let server_token = token.clone();
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let app_handle = app_handle.clone();
+            let pending = Arc::clone(&pending);
+            let next_id = Arc::clone(&next_id);
+            let token = server_token.clone();
+            std::thread::spawn(move || {
+                let _ = handle_connection(stream, &token, &app_handle, &pending, &next_id);
+            });
+        }
+    });
+
+    Ok(AskpassServer { port, token })
+}
+
+/// Environment for git network commands so credential prompts are routed to the frontend.
+pub fn git_env(app: &tauri::AppHandle) -> Result<Vec<(String, String)>, AppError> {
+    let state = app.state::<AskpassState>();
+    let mut server = state
+        .server
+        .lock()
+        .map_err(|_| "failed to lock askpass server".to_string())?;
+
+    if server.is_none() {
+        *server = Some(start_server(app, &state)?);
+    }
+    let Some(server) = server.as_ref() else {
+        return Err(AppError::from("askpass server unavailable"));
+    };
+
+    let helper = std::env::current_exe().map_err(|error| format!("failed to resolve askpass helper: {error}"))?;
+    let helper = helper.to_string_lossy().to_string();
+
+    Ok(vec![
+        ("GIT_ASKPASS".to_string(), helper.clone()),
+        ("SSH_ASKPASS".to_string(), helper),
+        ("SSH_ASKPASS_REQUIRE".to_string(), "force".to_string()),
+        ("GIT_TERMINAL_PROMPT".to_string(), "0".to_string()),
+        (PORT_ENV.to_string(), server.port.to_string()),
+        (TOKEN_ENV.to_string(), server.token.clone()),
+    ])
+}
+
+/// Asks the running app for an answer to `prompt` over the askpass bridge. Only works in helper
+/// processes started with the environment from [`git_env`].
+pub fn request(prompt: &str) -> Option<String> {
+    let port = std::env::var(PORT_ENV).ok()?;
+    let token = std::env::var(TOKEN_ENV).unwrap_or_default();
+
+    let mut stream = TcpStream::connect(("127.0.0.1", port.parse::<u16>().unwrap_or(0))).ok()?;
+    stream
+        .write_all(format!("{token}\n{}\n", prompt.replace('\n', " ")).as_bytes())
+        .ok()?;
+
+    let mut answer = String::new();
+    if stream.read_to_string(&mut answer).is_err() || answer.is_empty() {
+        return None;
+    }
+    Some(answer)
+}
+
+/// Entry point when the app binary is launched by git or ssh as an askpass helper.
+/// Returns `None` for normal app launches.
+pub fn run_client() -> Option<i32> {
+    std::env::var(PORT_ENV).ok()?;
+    let prompt = std::env::args().nth(1).unwrap_or_else(|| "Password:".to_string());
+
+    let Some(answer) = request(&prompt) else {
+        return Some(1);
+    };
+
+    print!("{answer}");
+    let _ = std::io::stdout().flush();
+    Some(0)
+}
+
+#[tauri::command]
+pub fn git_credential_respond(
+    id: String,
+    value: Option<String>,
+    state: tauri::State<AskpassState>,
+) -> Result<(), AppError> {
+    let pending = state
+        .pending
+        .lock()
+        .map_err(|_| "failed to lock credential requests".to_string())?;
+
+    let sender = pending
+        .get(&id)
+        .ok_or_else(|| AppError::invalid(format!("credential request not found: {id}")))?;
+    let _ = sender.send(value);
+    Ok(())
+}
@@ -0,0 +1,140 @@
+use crate::{
+    error::AppError,
+    layout,
+    terminal::{self, TerminalState},
+    windows,
+};
+use serde::Serialize;
+use std::{collections::HashSet, sync::Mutex};
+use tauri::Manager;
+
+/// Pane groups whose panes all receive what is typed into any one of them.
+#[derive(Default)]
+pub struct BroadcastState {
+    groups: Mutex<HashSet<String>>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BroadcastFailure {
+    tab_id: String,
+    error: String,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BroadcastFailedEvent {
+    source_tab_id: String,
+    failures: Vec<BroadcastFailure>,
+}
+
+fn broadcast(state: &TerminalState, tab_ids: &[String], data: &[u8]) -> Vec<BroadcastFailure> {
+    tab_ids
+        .iter()
+        .filter_map(|tab_id| {
+            terminal::write_session(state, tab_id, data)
+                .err()
+                .map(|error| BroadcastFailure {
+                    tab_id: tab_id.clone(),
+                    error: error.into(),
+                })
+        })
+        .collect()
+}
+
+/// Returns the other panes to mirror input from `tab_id` to, if its group is broadcasting.
+fn broadcast_targets(app: &tauri::AppHandle, tab_id: &str) -> Vec<String> {
+    let state = app.state::<BroadcastState>();
+    let any_enabled = state.groups.lock().map(|groups| !groups.is_empty()).unwrap_or(false);
+    if !any_enabled {
+        return Vec::new();
+    }
+
+    let Some((group_id, members)) = layout::pane_group(app, tab_id) else {
+        return Vec::new();
+    };
+    let enabled = state
+        .groups
+        .lock()
+        .map(|groups| groups.contains(&group_id))
+        .unwrap_or(false);
+    if !enabled {
+        return Vec::new();
+    }
+    members.into_iter().filter(|member| member != tab_id).collect()
+}
+
+/// Writes input typed into `tab_id`, mirroring it to the rest of the pane group while broadcast
+/// is on. Failures of the mirrored writes are reported with a `terminal-broadcast-failed` event.
+pub fn write_with_broadcast(
+    app: &tauri::AppHandle,
+    state: &TerminalState,
+    tab_id: &str,
+    data: &[u8],
+) -> Result<(), AppError> {
+    terminal::write_session(state, tab_id, data)?;
+
+    let targets = broadcast_targets(app, tab_id);
+    if targets.is_empty() {
+        return Ok(());
+    }
+    let failures = broadcast(state, &targets, data);
+    if !failures.is_empty() {
+        windows::emit(
+            app,
+            tab_id,
+            "terminal-broadcast-failed",
+            BroadcastFailedEvent {
+                source_tab_id: tab_id.to_string(),
+                failures,
+            },
+        );
+    }
+    Ok(())
+}
+
+/// Sends the same input to several sessions and reports the ones that could not be written.
+#[tauri::command]
+pub fn write_terminal_broadcast(
+    tab_ids: Vec<String>,
+    data: String,
+    state: tauri::State<TerminalState>,
+) -> Result<Vec<BroadcastFailure>, AppError> {
+    Ok(broadcast(&state, &tab_ids, data.as_bytes()))
+}
+
+/// Turns broadcast for a pane group on or off, flipping it when `enabled` is omitted. Returns
+/// the new state.
+pub fn toggle_group(app: &tauri::AppHandle, group_id: String, enabled: Option<bool>) -> Result<bool, String> {
+    let state = app.state::<BroadcastState>();
+    let mut groups = state
+        .groups
+        .lock()
+        .map_err(|_| "failed to lock broadcast groups".to_string())?;
+
+    let enabled = enabled.unwrap_or(!groups.contains(&group_id));
+    if enabled {
+        groups.insert(group_id);
+    } else {
+        groups.remove(&group_id);
+    }
+    Ok(enabled)
+}
+
+#[tauri::command]
+pub fn toggle_broadcast_group(
+    group_id: String,
+    enabled: Option<bool>,
+    app: tauri::AppHandle,
+) -> Result<bool, AppError> {
+    Ok(toggle_group(&app, group_id, enabled)?)
+}
+
+#[tauri::command]
+pub fn broadcast_groups(state: tauri::State<BroadcastState>) -> Result<Vec<String>, AppError> {
+    let groups = state
+        .groups
+        .lock()
+        .map_err(|_| "failed to lock broadcast groups".to_string())?;
+    Ok(groups.iter().cloned().collect())
+}
@@ -0,0 +1,279 @@
+use crate::{dropdown, error::AppError, terminal::TerminalState, windows};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashSet, path::PathBuf, sync::Mutex};
+use tauri::{
+    menu::{Menu, MenuItem},
+    tray::TrayIconBuilder,
+    Manager,
+};
+
+const TRAY_ID: &str = "detached-sessions";
+/// Scrollback lines handed back per session on reattach unless the caller asks otherwise.
+const DEFAULT_REPLAY_LINES: usize = 1000;
+
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DetachSettings {
+    /// Keep sessions running in the background when the last window closes, instead of quitting.
+    #[serde(default)]
+    keep_sessions_on_close: bool,
+}
+
+/// Tabs whose sessions are running without a window showing them.
+#[derive(Default)]
+pub struct DetachState {
+    detached: Mutex<HashSet<String>>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DetachedSession {
+    tab_id: String,
+    shell: String,
+    title: Option<String>,
+    cwd: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReattachedSession {
+    tab_id: String,
+    shell: String,
+    title: Option<String>,
+    cwd: Option<String>,
+    scrollback_tail: Vec<String>,
+    /// Escape sequences that redraw the current screen, written after the scrollback.
+    screen: String,
+}
+
+fn settings_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_config_dir()
+        .map_err(|error| format!("failed to resolve config dir: {error}"))?;
+    Ok(dir.join("detach.json"))
+}
+
+fn load_settings(app: &tauri::AppHandle) -> Result<DetachSettings, String> {
+    let path = settings_path(app)?;
+    if !path.exists() {
+        return Ok(DetachSettings::default());
+    }
+
+    let raw = std::fs::read_to_string(&path).map_err(|error| format!("failed to read detach settings: {error}"))?;
+    serde_json::from_str(&raw).map_err(|error| format!("failed to parse detach settings: {error}"))
+}
+
+/// Marks `tab_ids` (or every session) as detached and returns the marked tabs.
+fn mark_detached(app: &tauri::AppHandle, tab_ids: Option<Vec<String>>) -> Result<Vec<String>, String> {
+    let terminals = app.state::<TerminalState>();
+    let sessions = terminals
+        .sessions
+        .lock()
+        .map_err(|_| "failed to lock terminal sessions".to_string())?;
+    let marked = match tab_ids {
+        Some(tab_ids) => tab_ids
+            .into_iter()
+            .filter(|tab_id| sessions.contains_key(tab_id))
+            .collect::<Vec<_>>(),
+        None => sessions.keys().cloned().collect(),
+    };
+    drop(sessions);
+
+    app.state::<DetachState>()
+        .detached
+        .lock()
+        .map_err(|_| "failed to lock detached sessions".to_string())?
+        .extend(marked.iter().cloned());
+    Ok(marked)
+}
+
+/// Shows the main window again, rebuilding it from the app config after it was closed.
+fn show_window(app: &tauri::AppHandle) -> Result<(), String> {
+    let _ = app.remove_tray_by_id(TRAY_ID);
+    let main = app
+        .webview_windows()
+        .into_values()
+        .find(|window| window.label() != dropdown::LABEL);
+    if let Some(window) = main {
+        let _ = window.show();
+        let _ = window.set_focus();
+        return Ok(());
+    }
+
+    let config = app
+        .config()
+        .app
+        .windows
+        .first()
+        .cloned()
+        .ok_or_else(|| "no window configured".to_string())?;
+    tauri::WebviewWindowBuilder::from_config(app, &config)
+        .and_then(|builder| builder.build())
+        .map(|_| ())
+        .map_err(|error| format!("failed to open window: {error}"))
+}
+
+/// Puts up a tray icon to reopen the window or quit while sessions run without one.
+fn show_tray(app: &tauri::AppHandle) -> Result<(), String> {
+    if app.tray_by_id(TRAY_ID).is_some() {
+        return Ok(());
+    }
+
+    let show = MenuItem::with_id(app, "show", "Show Terminal", true, None::<&str>)
+        .map_err(|error| format!("failed to build tray menu: {error}"))?;
+    let quit = MenuItem::with_id(app, "quit", "Quit and End Sessions", true, None::<&str>)
+        .map_err(|error| format!("failed to build tray menu: {error}"))?;
+    let menu = Menu::with_items(app, &[&show, &quit]).map_err(|error| format!("failed to build tray menu: {error}"))?;
+
+    let mut tray = TrayIconBuilder::with_id(TRAY_ID)
+        .tooltip("Terminal sessions running")
+        .menu(&menu)
+        .on_menu_event(|app, event| match event.id().as_ref() {
+            "show" => {
+                let _ = show_window(app);
+            }
+            "quit" => app.exit(0),
+            _ => {}
+        });
+    if let Some(icon) = app.default_window_icon() {
+        tray = tray.icon(icon.clone());
+    }
+    tray.build(app)
+        .map(|_| ())
+        .map_err(|error| format!("failed to create tray icon: {error}"))
+}
+
+/// Called when the last window closes. Returns true when the app should keep running with its
+/// sessions detached rather than exit.
+pub fn detach_on_close(app: &tauri::AppHandle) -> bool {
+    if !load_settings(app).is_ok_and(|settings| settings.keep_sessions_on_close) {
+        return false;
+    }
+    match mark_detached(app, None) {
+        Ok(marked) if !marked.is_empty() => show_tray(app).is_ok(),
+        _ => false,
+    }
+}
+
+/// Brings the window back when the app is reactivated with no window open.
+pub fn reopen(app: &tauri::AppHandle) {
+    let _ = show_window(app);
+}
+
+#[tauri::command]
+pub fn get_detach_settings(app: tauri::AppHandle) -> Result<DetachSettings, AppError> {
+    Ok(load_settings(&app)?)
+}
+
+#[tauri::command]
+pub fn set_detach_settings(settings: DetachSettings, app: tauri::AppHandle) -> Result<(), AppError> {
+    let path = settings_path(&app)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|error| format!("failed to create config dir: {error}"))?;
+    }
+
+    let raw = serde_json::to_string_pretty(&settings)
+        .map_err(|error| format!("failed to encode detach settings: {error}"))?;
+    std::fs::write(&path, raw).map_err(|error| format!("failed to write detach settings: {error}"))?;
+    Ok(())
+}
+
+/// Leaves sessions running without a view, e.g. before the frontend reloads. `None` detaches all.
+#[tauri::command]
+pub fn detach_sessions(tab_ids: Option<Vec<String>>, app: tauri::AppHandle) -> Result<Vec<String>, AppError> {
+    Ok(mark_detached(&app, tab_ids)?)
+}
+
+#[tauri::command]
+pub fn list_detached_sessions(
+    state: tauri::State<DetachState>,
+    terminals: tauri::State<TerminalState>,
+) -> Result<Vec<DetachedSession>, AppError> {
+    let detached = state
+        .detached
+        .lock()
+        .map_err(|_| "failed to lock detached sessions".to_string())?;
+    let sessions = terminals
+        .sessions
+        .lock()
+        .map_err(|_| "failed to lock terminal sessions".to_string())?;
+
+    Ok(detached
+        .iter()
+        .filter_map(|tab_id| {
+            let session = sessions.get(tab_id)?;
+            let reported = session.reported.lock().ok()?;
+            Some(DetachedSession {
+                tab_id: tab_id.clone(),
+                shell: session.shell.clone(),
+                title: reported.title(),
+                cwd: reported.cwd.clone(),
+            })
+        })
+        .collect())
+}
+
+/// Reconnects detached sessions (`None` for all of them) to the calling window, returning what
+/// they printed meanwhile: the scrollback tail plus a redraw of the current screen.
+#[tauri::command]
+pub fn reattach_sessions(
+    tab_ids: Option<Vec<String>>,
+    scrollback_lines: Option<usize>,
+    app: tauri::AppHandle,
+    webview_window: tauri::WebviewWindow,
+    state: tauri::State<DetachState>,
+    terminals: tauri::State<TerminalState>,
+) -> Result<Vec<ReattachedSession>, AppError> {
+    let mut detached = state
+        .detached
+        .lock()
+        .map_err(|_| "failed to lock detached sessions".to_string())?;
+    let sessions = terminals
+        .sessions
+        .lock()
+        .map_err(|_| "failed to lock terminal sessions".to_string())?;
+
+    // Sessions that ended while detached are simply dropped.
+    detached.retain(|tab_id| sessions.contains_key(tab_id));
+    let tab_ids = match tab_ids {
+        Some(tab_ids) => tab_ids.into_iter().filter(|tab_id| detached.contains(tab_id)).collect(),
+        None => detached.iter().cloned().collect::<Vec<_>>(),
+    };
+
+    let lines = scrollback_lines.unwrap_or(DEFAULT_REPLAY_LINES);
+    let mut reattached = Vec::new();
+    for tab_id in tab_ids {
+        detached.remove(&tab_id);
+        let Some(session) = sessions.get(&tab_id) else {
+            continue;
+        };
+        windows::claim(&app, &tab_id, webview_window.label());
+        let (title, cwd) = session
+            .reported
+            .lock()
+            .map(|reported| (reported.title(), reported.cwd.clone()))
+            .unwrap_or_default();
+        let scrollback_tail = session
+            .scrollback
+            .lock()
+            .map(|scrollback| scrollback.tail(lines))
+            .unwrap_or_default();
+        let screen = session
+            .screen
+            .lock()
+            .map(|parser| String::from_utf8_lossy(&parser.screen().contents_formatted()).to_string())
+            .unwrap_or_default();
+
+        reattached.push(ReattachedSession {
+            tab_id,
+            shell: session.shell.clone(),
+            title,
+            cwd,
+            scrollback_tail,
+            screen,
+        });
+    }
+
+    Ok(reattached)
+}
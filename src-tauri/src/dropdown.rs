@@ -0,0 +1,240 @@
+use crate::error::AppError;
+use serde::{Deserialize, Serialize};
+use std::{
+    path::PathBuf,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+use tauri::{Emitter, Manager, PhysicalPosition, PhysicalSize, WebviewUrl, WebviewWindow, WebviewWindowBuilder};
+
+pub const LABEL: &str = "dropdown";
+/// The session shown in the dropdown window. The frontend opens it with `open_terminal`, which
+/// reuses it on every later show.
+pub const TAB_ID: &str = "dropdown";
+const FRAME: Duration = Duration::from_millis(16);
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DropdownSettings {
+    /// Share of the monitor height the window covers, between 0.1 and 1.
+    height_fraction: f64,
+    /// Slide duration; 0 shows and hides instantly.
+    animation_ms: u64,
+}
+
+impl Default for DropdownSettings {
+    fn default() -> Self {
+        Self {
+            height_fraction: 0.4,
+            animation_ms: 150,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DropdownPhase {
+    #[default]
+    Hidden,
+    Showing,
+    Shown,
+    Hiding,
+}
+
+#[derive(Default)]
+struct Animation {
+    phase: DropdownPhase,
+    /// Bumped by every toggle so a running slide notices it was superseded.
+    generation: u64,
+}
+
+#[derive(Default)]
+pub struct DropdownState {
+    animation: Mutex<Animation>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DropdownStatus {
+    phase: DropdownPhase,
+    tab_id: &'static str,
+}
+
+fn settings_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_config_dir()
+        .map_err(|error| format!("failed to resolve config dir: {error}"))?;
+    Ok(dir.join("dropdown.json"))
+}
+
+fn load_settings(app: &tauri::AppHandle) -> Result<DropdownSettings, String> {
+    let path = settings_path(app)?;
+    if !path.exists() {
+        return Ok(DropdownSettings::default());
+    }
+
+    let raw = std::fs::read_to_string(&path).map_err(|error| format!("failed to read dropdown settings: {error}"))?;
+    serde_json::from_str(&raw).map_err(|error| format!("failed to parse dropdown settings: {error}"))
+}
+
+fn dropdown_window(app: &tauri::AppHandle) -> Result<WebviewWindow, String> {
+    if let Some(window) = app.get_webview_window(LABEL) {
+        return Ok(window);
+    }
+
+    WebviewWindowBuilder::new(app, LABEL, WebviewUrl::default())
+        .title("Terminal")
+        .decorations(false)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .resizable(false)
+        .visible(false)
+        .build()
+        .map_err(|error| format!("failed to create dropdown window: {error}"))
+}
+
+/// Sizes the window to the top edge of the monitor under the cursor. Returns the shown and
+/// hidden y positions.
+fn place(app: &tauri::AppHandle, window: &WebviewWindow, settings: &DropdownSettings) -> Result<(i32, i32), String> {
+    let monitor = app
+        .cursor_position()
+        .ok()
+        .and_then(|cursor| app.monitor_from_point(cursor.x, cursor.y).ok().flatten())
+        .or_else(|| app.primary_monitor().ok().flatten())
+        .ok_or_else(|| "no monitor to show the dropdown on".to_string())?;
+
+    let origin = monitor.position();
+    let size = monitor.size();
+    let height = (f64::from(size.height) * settings.height_fraction.clamp(0.1, 1.0)).round() as u32;
+    window
+        .set_size(PhysicalSize::new(size.width, height))
+        .map_err(|error| format!("failed to size dropdown window: {error}"))?;
+
+    let shown = origin.y;
+    let hidden = origin.y - height as i32;
+    if !window.is_visible().unwrap_or(false) {
+        let _ = window.set_position(PhysicalPosition::new(origin.x, hidden));
+    }
+    Ok((shown, hidden))
+}
+
+fn set_phase(app: &tauri::AppHandle, generation: u64, phase: DropdownPhase) -> bool {
+    let state = app.state::<DropdownState>();
+    let Ok(mut animation) = state.animation.lock() else {
+        return false;
+    };
+    if animation.generation != generation {
+        return false;
+    }
+    animation.phase = phase;
+    let _ = app.emit("dropdown-changed", DropdownStatus { phase, tab_id: TAB_ID });
+    true
+}
+
+/// Slides the window between `from` and `to`, easing out, unless a newer toggle takes over.
+fn slide(app: tauri::AppHandle, window: WebviewWindow, generation: u64, from: i32, to: i32, duration: Duration) {
+    let x = window.outer_position().map(|position| position.x).unwrap_or_default();
+    let start = Instant::now();
+    loop {
+        let progress = if duration.is_zero() {
+            1.0
+        } else {
+            (start.elapsed().as_secs_f64() / duration.as_secs_f64()).min(1.0)
+        };
+        let eased = 1.0 - (1.0 - progress).powi(3);
+        let y = from + (f64::from(to - from) * eased).round() as i32;
+
+        let current = app
+            .state::<DropdownState>()
+            .animation
+            .lock()
+            .map(|animation| animation.generation == generation)
+            .unwrap_or(false);
+        if !current {
+            return;
+        }
+        let _ = window.set_position(PhysicalPosition::new(x, y));
+        if progress >= 1.0 {
+            break;
+        }
+        std::thread::sleep(FRAME);
+    }
+
+    let hiding = app
+        .state::<DropdownState>()
+        .animation
+        .lock()
+        .map(|animation| animation.phase == DropdownPhase::Hiding)
+        .unwrap_or(false);
+    if hiding {
+        let _ = window.hide();
+        set_phase(&app, generation, DropdownPhase::Hidden);
+    } else {
+        set_phase(&app, generation, DropdownPhase::Shown);
+    }
+}
+
+/// Shows the dropdown window when hidden or hiding, and hides it otherwise.
+pub fn toggle(app: &tauri::AppHandle) -> Result<DropdownStatus, String> {
+    let settings = load_settings(app)?;
+    let window = dropdown_window(app)?;
+    let state = app.state::<DropdownState>();
+
+    let (phase, generation) = {
+        let mut animation = state
+            .animation
+            .lock()
+            .map_err(|_| "failed to lock dropdown state".to_string())?;
+        animation.generation += 1;
+        animation.phase = match animation.phase {
+            DropdownPhase::Hidden | DropdownPhase::Hiding => DropdownPhase::Showing,
+            DropdownPhase::Showing | DropdownPhase::Shown => DropdownPhase::Hiding,
+        };
+        (animation.phase, animation.generation)
+    };
+
+    let (shown, hidden) = place(app, &window, &settings)?;
+    let from = window.outer_position().map(|position| position.y).unwrap_or(hidden);
+    let to = if phase == DropdownPhase::Showing {
+        window
+            .show()
+            .map_err(|error| format!("failed to show dropdown window: {error}"))?;
+        let _ = window.set_focus();
+        shown
+    } else {
+        hidden
+    };
+
+    let status = DropdownStatus { phase, tab_id: TAB_ID };
+    let _ = app.emit("dropdown-changed", status.clone());
+
+    let thread_app = app.clone();
+    let duration = Duration::from_millis(settings.animation_ms);
+    std::thread::spawn(move || slide(thread_app, window, generation, from, to, duration));
+    Ok(status)
+}
+
+// Async so the window is not created on the main thread, which deadlocks on Windows.
+#[tauri::command]
+pub async fn toggle_dropdown(app: tauri::AppHandle) -> Result<DropdownStatus, AppError> {
+    Ok(toggle(&app)?)
+}
+
+#[tauri::command]
+pub fn get_dropdown_settings(app: tauri::AppHandle) -> Result<DropdownSettings, AppError> {
+    Ok(load_settings(&app)?)
+}
+
+#[tauri::command]
+pub fn set_dropdown_settings(settings: DropdownSettings, app: tauri::AppHandle) -> Result<(), AppError> {
+    let path = settings_path(&app)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|error| format!("failed to create config dir: {error}"))?;
+    }
+
+    let raw = serde_json::to_string_pretty(&settings)
+        .map_err(|error| format!("failed to encode dropdown settings: {error}"))?;
+    std::fs::write(&path, raw).map_err(|error| format!("failed to write dropdown settings: {error}"))?;
+    Ok(())
+}
@@ -0,0 +1,180 @@
+use crate::{
+    error::AppError,
+    terminal::{self, TerminalState},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+use tauri::Manager;
+
+/// Editors that need a terminal; these are handed back to the frontend to run in a tab.
+const TERMINAL_EDITORS: &[&str] = &["vi", "vim", "nvim", "nano", "emacs", "hx", "helix", "micro", "kak", "joe"];
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EditorSettings {
+    /// Editor command line, e.g. `code` or `subl -n`. Falls back to `$VISUAL`, `$EDITOR`, then `code`.
+    #[serde(default)]
+    command: Option<String>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EditorLaunch {
+    /// False for terminal editors, which the frontend should run in a tab instead.
+    launched: bool,
+    program: String,
+    args: Vec<String>,
+}
+
+fn settings_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_config_dir()
+        .map_err(|error| format!("failed to resolve config dir: {error}"))?;
+    Ok(dir.join("editor.json"))
+}
+
+fn load_settings(app: &tauri::AppHandle) -> Result<EditorSettings, String> {
+    let path = settings_path(app)?;
+    if !path.exists() {
+        return Ok(EditorSettings::default());
+    }
+
+    let raw = std::fs::read_to_string(&path).map_err(|error| format!("failed to read editor settings: {error}"))?;
+    serde_json::from_str(&raw).map_err(|error| format!("failed to parse editor settings: {error}"))
+}
+
+fn editor_command(settings: &EditorSettings) -> String {
+    settings
+        .command
+        .clone()
+        .or_else(|| std::env::var("VISUAL").ok())
+        .or_else(|| std::env::var("EDITOR").ok())
+        .filter(|command| !command.trim().is_empty())
+        .unwrap_or_else(|| "code".to_string())
+}
+
+/// Builds the arguments that make `program` open `path` at the given position.
+fn location_args(program: &str, path: &str, line: Option<u32>, column: Option<u32>) -> Vec<String> {
+    let name = Path::new(program)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let Some(line) = line else {
+        return vec![path.to_string()];
+    };
+    let column = column.unwrap_or(1);
+
+    match name.as_str() {
+        "code" | "code-insiders" | "codium" | "cursor" | "windsurf" => {
+            vec!["--goto".to_string(), format!("{path}:{line}:{column}")]
+        }
+        "subl" | "sublime_text" | "zed" | "hx" | "helix" | "micro" => vec![format!("{path}:{line}:{column}")],
+        "idea" | "idea64" | "webstorm" | "pycharm" | "clion" | "goland" | "rustrover" => vec![
+            "--line".to_string(),
+            line.to_string(),
+            "--column".to_string(),
+            column.to_string(),
+            path.to_string(),
+        ],
+        "vi" | "vim" | "nvim" | "nano" | "emacs" | "kak" | "joe" => vec![format!("+{line}"), path.to_string()],
+        _ => vec![path.to_string()],
+    }
+}
+
+/// Resolves `path` against the cwd of `tab_id`'s shell when it is relative.
+fn resolve_path(path: &str, tab_id: Option<&str>, state: &TerminalState) -> String {
+    let expanded = match path.strip_prefix("~/") {
+        Some(rest) => std::env::var("HOME")
+            .map(|home| Path::new(&home).join(rest).to_string_lossy().to_string())
+            .unwrap_or_else(|_| path.to_string()),
+        None => path.to_string(),
+    };
+    if Path::new(&expanded).is_absolute() {
+        return expanded;
+    }
+
+    let cwd = tab_id.and_then(|tab_id| {
+        let sessions = state.sessions.lock().ok()?;
+        let session = sessions.get(tab_id)?;
+        terminal::session_cwd(session)
+            .ok()
+            .flatten()
+            .or_else(|| session.reported.lock().ok()?.cwd.clone())
+    });
+    match cwd {
+        Some(cwd) => Path::new(&cwd).join(&expanded).to_string_lossy().to_string(),
+        None => expanded,
+    }
+}
+
+/// Opens a file, optionally at a line and column, in the configured editor. Relative paths such
+/// as compiler output are resolved against the cwd of `tab_id`.
+#[tauri::command]
+pub fn open_in_editor(
+    path: String,
+    line: Option<u32>,
+    column: Option<u32>,
+    tab_id: Option<String>,
+    app: tauri::AppHandle,
+    state: tauri::State<TerminalState>,
+) -> Result<EditorLaunch, AppError> {
+    let path = resolve_path(&path, tab_id.as_deref(), &state);
+    if !Path::new(&path).exists() {
+        return Err(AppError::invalid(format!("file not found: {path}")));
+    }
+
+    let command = editor_command(&load_settings(&app)?);
+    let mut parts = command.split_whitespace().map(ToOwned::to_owned);
+    let program = parts.next().ok_or_else(|| AppError::invalid("editor command is empty"))?;
+    let mut args = parts.collect::<Vec<String>>();
+    args.extend(location_args(&program, &path, line, column));
+
+    let name = Path::new(&program)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    if TERMINAL_EDITORS.contains(&name.as_str()) {
+        return Ok(EditorLaunch {
+            launched: false,
+            program,
+            args,
+        });
+    }
+
+    let mut child = Command::new(&program)
+        .args(&args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|error| format!("failed to launch {program}: {error}"))?;
+    std::thread::spawn(move || child.wait());
+
+    Ok(EditorLaunch {
+        launched: true,
+        program,
+        args,
+    })
+}
+
+#[tauri::command]
+pub fn get_editor_settings(app: tauri::AppHandle) -> Result<EditorSettings, AppError> {
+    Ok(load_settings(&app)?)
+}
+
+#[tauri::command]
+pub fn set_editor_settings(settings: EditorSettings, app: tauri::AppHandle) -> Result<(), AppError> {
+    let path = settings_path(&app)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|error| format!("failed to create config dir: {error}"))?;
+    }
+
+    let raw =
+        serde_json::to_string_pretty(&settings).map_err(|error| format!("failed to encode editor settings: {error}"))?;
+    std::fs::write(&path, raw).map_err(|error| format!("failed to write editor settings: {error}"))?;
+    Ok(())
+}
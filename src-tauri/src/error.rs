@@ -0,0 +1,142 @@
+use serde::Serialize;
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ErrorKind {
+    SessionNotFound,
+    SessionExists,
+    /// Spawning, reading or resizing the pseudo terminal failed.
+    PtyFailure,
+    InvalidInput,
+    GitFailed,
+    GitNotRepository,
+    /// The remote rejected or asked for credentials.
+    GitAuthRequired,
+    GitConflict,
+    /// A hook such as `pre-commit` or `commit-msg` exited with an error.
+    GitHookRejected,
+    Cancelled,
+    Internal,
+}
+
+/// Error returned by commands, so the frontend can branch on `kind` instead of matching text.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppError {
+    pub kind: ErrorKind,
+    pub message: String,
+    /// Raw output behind the error, such as git's stderr, when it says more than `message`.
+    pub detail: Option<String>,
+    /// Whether retrying, possibly after user input like credentials, can succeed.
+    pub recoverable: bool,
+    /// Exit code of the git process that failed.
+    pub exit_code: Option<i32>,
+    /// The hook that rejected the operation, for `GitHookRejected`.
+    pub hook: Option<HookFailure>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HookFailure {
+    pub name: String,
+    /// What the hook printed on stdout and stderr.
+    pub output: String,
+}
+
+impl AppError {
+    pub fn new(kind: ErrorKind, message: impl Into<String>) -> Self {
+        let recoverable = matches!(
+            kind,
+            ErrorKind::GitAuthRequired | ErrorKind::GitConflict | ErrorKind::GitHookRejected | ErrorKind::Cancelled
+        );
+        Self {
+            kind,
+            message: message.into(),
+            detail: None,
+            recoverable,
+            exit_code: None,
+            hook: None,
+        }
+    }
+
+    pub fn session_not_found(tab_id: &str) -> Self {
+        Self::new(ErrorKind::SessionNotFound, format!("terminal session not found: {tab_id}"))
+    }
+
+    pub fn pty(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::PtyFailure, message)
+    }
+
+    pub fn invalid(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::InvalidInput, message)
+    }
+
+    /// A failed git invocation, classified from what it printed.
+    pub fn git(stderr: String, exit_code: Option<i32>) -> Self {
+        let kind = git_error_kind(&stderr);
+        let message = stderr
+            .lines()
+            .find(|line| !line.trim().is_empty())
+            .map(|line| line.trim().to_string())
+            .unwrap_or_else(|| "git command failed".to_string());
+        Self {
+            detail: (stderr.trim() != message).then_some(stderr),
+            exit_code,
+            ..Self::new(kind, message)
+        }
+    }
+}
+
+fn git_error_kind(stderr: &str) -> ErrorKind {
+    let lower = stderr.to_lowercase();
+    if lower.contains("authentication failed")
+        || lower.contains("could not read username")
+        || lower.contains("could not read password")
+        || lower.contains("permission denied (publickey")
+        || lower.contains("terminal prompts disabled")
+        || lower.contains("invalid username or password")
+    {
+        ErrorKind::GitAuthRequired
+    } else if lower.contains("not a git repository") {
+        ErrorKind::GitNotRepository
+    } else if lower.contains("conflict") || lower.contains("needs merge") || lower.contains("unmerged") {
+        ErrorKind::GitConflict
+    } else if lower.contains("operation cancelled") {
+        ErrorKind::Cancelled
+    } else {
+        ErrorKind::GitFailed
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for AppError {}
+
+/// Helpers that still report plain messages surface as `Internal` errors.
+impl From<String> for AppError {
+    fn from(message: String) -> Self {
+        Self::new(ErrorKind::Internal, message)
+    }
+}
+
+impl From<&str> for AppError {
+    fn from(message: &str) -> Self {
+        Self::from(message.to_string())
+    }
+}
+
+/// Lets helpers that return plain messages use `?` on calls that return `AppError`. The detail
+/// follows the message so raw output like git's stderr still says what failed.
+impl From<AppError> for String {
+    fn from(error: AppError) -> Self {
+        match error.detail {
+            Some(detail) => format!("{}: {detail}", error.message),
+            None => error.message,
+        }
+    }
+}
@@ -0,0 +1,173 @@
+use crate::error::AppError;
+use serde::{Deserialize, Serialize};
+use std::{
+    sync::{Arc, Condvar, Mutex},
+    time::{Duration, Instant},
+};
+
+/// How long the emitter collects PTY reads before sending one `terminal-data` event.
+pub const FLUSH_INTERVAL: Duration = Duration::from_millis(6);
+/// Upper bound for the raw bytes carried by a single `terminal-data` event.
+pub const MAX_EVENT_BYTES: usize = 64 * 1024;
+/// Reads queued between the PTY reader and the emitter. Once full the reader blocks, the kernel
+/// buffer fills and the process writing to the terminal is stalled.
+pub const QUEUE_CHUNKS: usize = 64;
+/// Flush interval while a flooding session is summarized.
+pub const SUMMARY_INTERVAL: Duration = Duration::from_millis(100);
+/// Bytes kept from each summarized batch; the rest only reaches the scrollback.
+pub const SUMMARY_TAIL_BYTES: usize = 16 * 1024;
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FloodSettings {
+    /// Output rate above which a session counts as flooding.
+    threshold_bytes_per_sec: u64,
+    /// Forward only the tail of each batch while flooding.
+    summarize: bool,
+}
+
+impl Default for FloodSettings {
+    fn default() -> Self {
+        Self {
+            threshold_bytes_per_sec: 2 * 1024 * 1024,
+            summarize: false,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct FlowState {
+    flood: Mutex<FloodSettings>,
+}
+
+impl FlowState {
+    pub fn flood_settings(&self) -> FloodSettings {
+        self.flood.lock().map(|settings| *settings).unwrap_or_default()
+    }
+}
+
+/// Measures a session's output rate over one second windows.
+pub struct FloodDetector {
+    window_start: Instant,
+    window_bytes: u64,
+    flooding: bool,
+}
+
+impl Default for FloodDetector {
+    fn default() -> Self {
+        Self {
+            window_start: Instant::now(),
+            window_bytes: 0,
+            flooding: false,
+        }
+    }
+}
+
+impl FloodDetector {
+    /// Counts `bytes` and, when a window closes with the flooding state changed, returns the new
+    /// state and the measured rate.
+    pub fn record(&mut self, bytes: usize, settings: FloodSettings) -> Option<(bool, u64)> {
+        self.window_bytes += bytes as u64;
+        let elapsed = self.window_start.elapsed();
+        if elapsed < Duration::from_secs(1) {
+            return None;
+        }
+
+        let rate = (self.window_bytes as f64 / elapsed.as_secs_f64()) as u64;
+        self.window_start = Instant::now();
+        self.window_bytes = 0;
+
+        let flooding = rate > settings.threshold_bytes_per_sec;
+        if flooding == self.flooding {
+            return None;
+        }
+        self.flooding = flooding;
+        Some((flooding, rate))
+    }
+
+    pub fn summarizing(&self, settings: FloodSettings) -> bool {
+        self.flooding && settings.summarize
+    }
+}
+
+/// Keeps the last `SUMMARY_TAIL_BYTES` of a batch, starting after a newline where possible.
+/// Returns the kept bytes and how many were dropped.
+pub fn summarize(batch: &[u8]) -> (&[u8], usize) {
+    if batch.len() <= SUMMARY_TAIL_BYTES {
+        return (batch, 0);
+    }
+
+    let tail_start = batch.len() - SUMMARY_TAIL_BYTES;
+    let start = batch[tail_start..]
+        .iter()
+        .position(|byte| *byte == b'\n')
+        .map(|offset| tail_start + offset + 1)
+        .unwrap_or(tail_start);
+    (&batch[start..], start)
+}
+
+#[derive(Default)]
+struct FlowFlags {
+    paused: bool,
+    closed: bool,
+}
+
+/// Pause switch the frontend flips when it falls behind rendering output.
+#[derive(Default)]
+pub struct FlowControl {
+    flags: Mutex<FlowFlags>,
+    changed: Condvar,
+}
+
+impl FlowControl {
+    pub fn set_paused(&self, paused: bool) {
+        if let Ok(mut flags) = self.flags.lock() {
+            flags.paused = paused;
+        }
+        self.changed.notify_all();
+    }
+
+    /// Blocks while output is paused. Returns immediately once the session is closed.
+    pub fn wait_while_paused(&self) {
+        let Ok(flags) = self.flags.lock() else {
+            return;
+        };
+        drop(self.changed.wait_while(flags, |flags| flags.paused && !flags.closed));
+    }
+
+    fn close(&self) {
+        if let Ok(mut flags) = self.flags.lock() {
+            flags.closed = true;
+        }
+        self.changed.notify_all();
+    }
+}
+
+/// Session-owned handle that releases a paused emitter when the session goes away, so the
+/// reader and emitter threads can drain and exit.
+pub struct FlowHandle(pub Arc<FlowControl>);
+
+impl Drop for FlowHandle {
+    fn drop(&mut self) {
+        self.0.close();
+    }
+}
+
+#[tauri::command]
+pub fn get_flood_settings(state: tauri::State<FlowState>) -> Result<FloodSettings, AppError> {
+    let settings = state
+        .flood
+        .lock()
+        .map_err(|_| "failed to lock flood settings".to_string())?;
+    Ok(*settings)
+}
+
+#[tauri::command]
+pub fn set_flood_settings(settings: FloodSettings, state: tauri::State<FlowState>) -> Result<(), AppError> {
+    let mut current = state
+        .flood
+        .lock()
+        .map_err(|_| "failed to lock flood settings".to_string())?;
+    *current = settings;
+    Ok(())
+}
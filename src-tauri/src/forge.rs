@@ -0,0 +1,673 @@
+use crate::{
+    error::AppError,
+    git::{self, operation::GitOperations, remote},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+use tauri::Manager;
+use tauri_plugin_opener::OpenerExt;
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ForgeKind {
+    Github,
+    Gitlab,
+}
+
+/// URL layouts of the web interfaces `git_web_url` knows about.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum WebFlavor {
+    Github,
+    Gitlab,
+    Bitbucket,
+    Gitea,
+}
+
+#[derive(Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WebUrlKind {
+    Repo,
+    Commit,
+    Branch,
+    File,
+}
+
+/// Inclusive 1-based line range of a file link.
+#[derive(Clone, Copy, Deserialize)]
+pub struct WebLineRange {
+    start: usize,
+    end: usize,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ForgeAccount {
+    host: String,
+    kind: ForgeKind,
+    token: String,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForgeAccountInfo {
+    host: String,
+    kind: ForgeKind,
+}
+
+/// Host and repository path parsed from a remote URL.
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteRepo {
+    pub host: String,
+    /// Everything before the repository name; GitLab groups may be nested.
+    pub owner: String,
+    pub name: String,
+}
+
+impl RemoteRepo {
+    pub fn path(&self) -> String {
+        format!("{}/{}", self.owner, self.name)
+    }
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForgeRepo {
+    remote: String,
+    kind: ForgeKind,
+    #[serde(flatten)]
+    repo: RemoteRepo,
+    authenticated: bool,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PullRequest {
+    number: u64,
+    title: String,
+    author: String,
+    state: String,
+    draft: bool,
+    source_branch: String,
+    target_branch: String,
+    url: String,
+    updated_at: String,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PullRequestDetails {
+    #[serde(flatten)]
+    pull_request: PullRequest,
+    body: String,
+    labels: Vec<String>,
+    mergeable: Option<bool>,
+    additions: Option<u64>,
+    deletions: Option<u64>,
+    changed_files: Option<u64>,
+}
+
+/// Parses `git@host:owner/name.git`, `ssh://git@host:22/owner/name.git` and
+/// `https://host/owner/name.git` style remote URLs.
+pub fn parse_remote_url(url: &str) -> Option<RemoteRepo> {
+    let url = url.trim();
+    let (host, path) = if let Some((_, rest)) = url.split_once("://") {
+        let (authority, path) = rest.split_once('/')?;
+        let host = authority.rsplit('@').next()?;
+        let host = host.split(':').next()?;
+        (host, path)
+    } else {
+        // scp-like syntax: [user@]host:path
+        let (authority, path) = url.split_once(':')?;
+        (authority.rsplit('@').next()?, path)
+    };
+
+    let path = path.trim_matches('/').trim_end_matches(".git");
+    let (owner, name) = path.rsplit_once('/')?;
+    if host.is_empty() || owner.is_empty() || name.is_empty() {
+        return None;
+    }
+
+    Some(RemoteRepo {
+        host: host.to_lowercase(),
+        owner: owner.to_string(),
+        name: name.to_string(),
+    })
+}
+
+fn accounts_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_config_dir()
+        .map_err(|error| format!("failed to resolve config dir: {error}"))?;
+    Ok(dir.join("forge_accounts.json"))
+}
+
+fn load_accounts(app: &tauri::AppHandle) -> Result<Vec<ForgeAccount>, String> {
+    let path = accounts_path(app)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let raw = std::fs::read_to_string(&path).map_err(|error| format!("failed to read forge accounts: {error}"))?;
+    serde_json::from_str(&raw).map_err(|error| format!("failed to parse forge accounts: {error}"))
+}
+
+fn store_accounts(app: &tauri::AppHandle, accounts: &[ForgeAccount]) -> Result<(), String> {
+    let path = accounts_path(app)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|error| format!("failed to create config dir: {error}"))?;
+    }
+
+    let raw =
+        serde_json::to_string_pretty(accounts).map_err(|error| format!("failed to encode forge accounts: {error}"))?;
+    std::fs::write(&path, raw).map_err(|error| format!("failed to write forge accounts: {error}"))?;
+
+    // Tokens are stored in plain JSON, so keep the file private to the user.
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600));
+    }
+    Ok(())
+}
+
+fn guess_kind(host: &str) -> Option<ForgeKind> {
+    if host.contains("github") {
+        Some(ForgeKind::Github)
+    } else if host.contains("gitlab") {
+        Some(ForgeKind::Gitlab)
+    } else {
+        None
+    }
+}
+
+struct Forge {
+    kind: ForgeKind,
+    repo: RemoteRepo,
+    token: Option<String>,
+}
+
+impl Forge {
+    fn detect(app: &tauri::AppHandle, repo: &Path, remote: Option<&str>) -> Result<(String, Self), String> {
+        let (remote, url) = remote::remote_url(repo, remote)?;
+        let parsed = parse_remote_url(&url).ok_or_else(|| format!("unrecognized remote url: {url}"))?;
+        let account = load_accounts(app)?
+            .into_iter()
+            .find(|account| account.host == parsed.host);
+
+        let kind = account
+            .as_ref()
+            .map(|account| account.kind)
+            .or_else(|| guess_kind(&parsed.host))
+            .ok_or_else(|| format!("unknown forge for {}; add an account for this host", parsed.host))?;
+
+        Ok((
+            remote,
+            Self {
+                kind,
+                repo: parsed,
+                token: account.map(|account| account.token),
+            },
+        ))
+    }
+
+    fn api_base(&self) -> String {
+        match self.kind {
+            ForgeKind::Github if self.repo.host == "github.com" => "https://api.github.com".to_string(),
+            ForgeKind::Github => format!("https://{}/api/v3", self.repo.host),
+            ForgeKind::Gitlab => format!("https://{}/api/v4", self.repo.host),
+        }
+    }
+
+    /// API path of the repository itself.
+    fn repo_endpoint(&self) -> String {
+        match self.kind {
+            ForgeKind::Github => format!("/repos/{}", self.repo.path()),
+            ForgeKind::Gitlab => format!("/projects/{}", self.repo.path().replace('/', "%2F")),
+        }
+    }
+
+    fn pulls_endpoint(&self) -> String {
+        match self.kind {
+            ForgeKind::Github => format!("{}/pulls", self.repo_endpoint()),
+            ForgeKind::Gitlab => format!("{}/merge_requests", self.repo_endpoint()),
+        }
+    }
+
+    fn request(&self, method: &str, endpoint: &str, body: Option<Value>) -> Result<Value, String> {
+        let mut request = ureq::request(method, &format!("{}{endpoint}", self.api_base()))
+            .set("User-Agent", "nlk-term")
+            .set("Accept", "application/json");
+        if let Some(token) = self.token.as_deref() {
+            request = match self.kind {
+                ForgeKind::Github => request.set("Authorization", &format!("Bearer {token}")),
+                ForgeKind::Gitlab => request.set("PRIVATE-TOKEN", token),
+            };
+        }
+
+        let response = match body {
+            Some(body) => request.send_json(body),
+            None => request.call(),
+        };
+
+        match response {
+            Ok(response) => response
+                .into_json::<Value>()
+                .map_err(|error| format!("failed to parse forge response: {error}")),
+            Err(ureq::Error::Status(code, response)) => {
+                let detail = response
+                    .into_json::<Value>()
+                    .ok()
+                    .and_then(|body| body.get("message").and_then(Value::as_str).map(ToOwned::to_owned))
+                    .unwrap_or_default();
+                Err(format!("forge request failed ({code}): {detail}").trim_end_matches(": ").to_string())
+            }
+            Err(error) => Err(format!("forge request failed: {error}")),
+        }
+    }
+
+    fn default_branch(&self) -> Result<String, String> {
+        self.request("GET", &self.repo_endpoint(), None)?
+            .get("default_branch")
+            .and_then(Value::as_str)
+            .map(ToOwned::to_owned)
+            .ok_or_else(|| "repository has no default branch".to_string())
+    }
+
+    fn pull_request(&self, value: &Value) -> PullRequest {
+        let text = |pointer: &str| value.pointer(pointer).and_then(Value::as_str).unwrap_or_default().to_string();
+        match self.kind {
+            ForgeKind::Github => PullRequest {
+                number: value.get("number").and_then(Value::as_u64).unwrap_or(0),
+                title: text("/title"),
+                author: text("/user/login"),
+                state: if value.get("merged_at").is_some_and(|merged| !merged.is_null()) {
+                    "merged".to_string()
+                } else {
+                    text("/state")
+                },
+                draft: value.get("draft").and_then(Value::as_bool).unwrap_or(false),
+                source_branch: text("/head/ref"),
+                target_branch: text("/base/ref"),
+                url: text("/html_url"),
+                updated_at: text("/updated_at"),
+            },
+            ForgeKind::Gitlab => PullRequest {
+                number: value.get("iid").and_then(Value::as_u64).unwrap_or(0),
+                title: text("/title"),
+                author: text("/author/username"),
+                state: text("/state"),
+                draft: value
+                    .get("draft")
+                    .or_else(|| value.get("work_in_progress"))
+                    .and_then(Value::as_bool)
+                    .unwrap_or(false),
+                source_branch: text("/source_branch"),
+                target_branch: text("/target_branch"),
+                url: text("/web_url"),
+                updated_at: text("/updated_at"),
+            },
+        }
+    }
+
+    fn pull_request_details(&self, value: &Value) -> PullRequestDetails {
+        let number = |key: &str| value.get(key).and_then(Value::as_u64);
+        let labels = value
+            .get("labels")
+            .and_then(Value::as_array)
+            .map(|labels| {
+                labels
+                    .iter()
+                    .filter_map(|label| label.get("name").and_then(Value::as_str).or_else(|| label.as_str()))
+                    .map(ToOwned::to_owned)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        match self.kind {
+            ForgeKind::Github => PullRequestDetails {
+                pull_request: self.pull_request(value),
+                body: value.get("body").and_then(Value::as_str).unwrap_or_default().to_string(),
+                labels,
+                mergeable: value.get("mergeable").and_then(Value::as_bool),
+                additions: number("additions"),
+                deletions: number("deletions"),
+                changed_files: number("changed_files"),
+            },
+            ForgeKind::Gitlab => PullRequestDetails {
+                pull_request: self.pull_request(value),
+                body: value.get("description").and_then(Value::as_str).unwrap_or_default().to_string(),
+                labels,
+                mergeable: value
+                    .get("merge_status")
+                    .and_then(Value::as_str)
+                    .map(|status| status == "can_be_merged"),
+                additions: None,
+                deletions: None,
+                changed_files: value
+                    .get("changes_count")
+                    .and_then(Value::as_str)
+                    .and_then(|count| count.trim_end_matches('+').parse().ok()),
+            },
+        }
+    }
+
+    /// Ref the forge publishes for a pull request's head commit.
+    fn head_ref(&self, number: u64) -> String {
+        match self.kind {
+            ForgeKind::Github => format!("pull/{number}/head"),
+            ForgeKind::Gitlab => format!("merge-requests/{number}/head"),
+        }
+    }
+}
+
+#[tauri::command]
+pub fn forge_accounts(app: tauri::AppHandle) -> Result<Vec<ForgeAccountInfo>, AppError> {
+    Ok(load_accounts(&app)?
+        .into_iter()
+        .map(|account| ForgeAccountInfo {
+            host: account.host,
+            kind: account.kind,
+        })
+        .collect())
+}
+
+#[tauri::command]
+pub fn forge_set_token(
+    host: String,
+    kind: Option<ForgeKind>,
+    token: String,
+    app: tauri::AppHandle,
+) -> Result<(), AppError> {
+    let host = host.trim().to_lowercase();
+    let token = token.trim().to_string();
+    if host.is_empty() {
+        return Err(AppError::invalid("forge host is empty"));
+    }
+    if token.is_empty() {
+        return Err(AppError::invalid("forge token is empty"));
+    }
+    let kind = kind
+        .or_else(|| guess_kind(&host))
+        .ok_or_else(|| AppError::invalid(format!("cannot tell which forge runs on {host}")))?;
+
+    let mut accounts = load_accounts(&app)?;
+    accounts.retain(|account| account.host != host);
+    accounts.push(ForgeAccount { host, kind, token });
+    Ok(store_accounts(&app, &accounts)?)
+}
+
+#[tauri::command]
+pub fn forge_remove_token(host: String, app: tauri::AppHandle) -> Result<(), AppError> {
+    let host = host.trim().to_lowercase();
+    let mut accounts = load_accounts(&app)?;
+    accounts.retain(|account| account.host != host);
+    Ok(store_accounts(&app, &accounts)?)
+}
+
+#[tauri::command]
+pub fn forge_detect(repo_path: String, remote: Option<String>, app: tauri::AppHandle) -> Result<ForgeRepo, AppError> {
+    let repo = PathBuf::from(repo_path);
+    let (remote, forge) = Forge::detect(&app, &repo, remote.as_deref())?;
+    Ok(ForgeRepo {
+        remote,
+        kind: forge.kind,
+        authenticated: forge.token.is_some(),
+        repo: forge.repo,
+    })
+}
+
+/// Lists pull (GitHub) or merge (GitLab) requests. `state` is `open`, `closed` or `all`.
+#[tauri::command]
+pub async fn forge_list_prs(
+    repo_path: String,
+    remote: Option<String>,
+    state: Option<String>,
+    app: tauri::AppHandle,
+) -> Result<Vec<PullRequest>, AppError> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let repo = PathBuf::from(repo_path);
+        let (_, forge) = Forge::detect(&app, &repo, remote.as_deref())?;
+        let state = match (forge.kind, state.as_deref().unwrap_or("open")) {
+            (ForgeKind::Gitlab, "open") => "opened",
+            (_, "open") => "open",
+            (_, "closed") => "closed",
+            (_, "all") => "all",
+            (_, other) => return Err(AppError::invalid(format!("invalid pull request state: {other}"))),
+        };
+
+        let endpoint = format!("{}?state={state}&per_page=50", forge.pulls_endpoint());
+        let items = forge.request("GET", &endpoint, None)?;
+        Ok(items
+            .as_array()
+            .map(|items| items.iter().map(|item| forge.pull_request(item)).collect())
+            .unwrap_or_default())
+    })
+    .await
+    .map_err(|error| format!("forge request failed: {error}"))?
+}
+
+#[tauri::command]
+pub async fn forge_pr_details(
+    repo_path: String,
+    remote: Option<String>,
+    number: u64,
+    app: tauri::AppHandle,
+) -> Result<PullRequestDetails, AppError> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let repo = PathBuf::from(repo_path);
+        let (_, forge) = Forge::detect(&app, &repo, remote.as_deref())?;
+        let item = forge.request("GET", &format!("{}/{number}", forge.pulls_endpoint()), None)?;
+        Ok(forge.pull_request_details(&item))
+    })
+    .await
+    .map_err(|error| format!("forge request failed: {error}"))?
+}
+
+/// Opens a pull request from `source_branch` (the current branch by default) into
+/// `target_branch` (the repository default branch by default). The branch must be pushed.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn forge_create_pr(
+    repo_path: String,
+    remote: Option<String>,
+    title: String,
+    body: Option<String>,
+    source_branch: Option<String>,
+    target_branch: Option<String>,
+    draft: Option<bool>,
+    app: tauri::AppHandle,
+) -> Result<PullRequest, AppError> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let repo = PathBuf::from(repo_path);
+        let title = title.trim().to_string();
+        if title.is_empty() {
+            return Err(AppError::invalid("pull request title is empty"));
+        }
+
+        let (_, forge) = Forge::detect(&app, &repo, remote.as_deref())?;
+        if forge.token.is_none() {
+            return Err(AppError::invalid(format!("no token stored for {}", forge.repo.host)));
+        }
+
+        let source = match source_branch {
+            Some(branch) => branch,
+            None => git::current_branch(&repo)?,
+        };
+        let target = match target_branch {
+            Some(branch) => branch,
+            None => forge.default_branch()?,
+        };
+        let draft = draft.unwrap_or(false);
+        let body = body.unwrap_or_default();
+
+        let payload = match forge.kind {
+            ForgeKind::Github => json!({
+                "title": title,
+                "body": body,
+                "head": source,
+                "base": target,
+                "draft": draft,
+            }),
+            ForgeKind::Gitlab => json!({
+                "title": if draft { format!("Draft: {title}") } else { title },
+                "description": body,
+                "source_branch": source,
+                "target_branch": target,
+            }),
+        };
+
+        let item = forge.request("POST", &forge.pulls_endpoint(), Some(payload))?;
+        Ok(forge.pull_request(&item))
+    })
+    .await
+    .map_err(|error| format!("forge request failed: {error}"))?
+}
+
+/// Fetches the head of a pull request into a local `pr-<number>` branch and checks it out.
+#[tauri::command]
+pub async fn forge_checkout_pr(
+    repo_path: String,
+    remote: Option<String>,
+    number: u64,
+    app: tauri::AppHandle,
+) -> Result<String, AppError> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let repo = PathBuf::from(&repo_path);
+        let (remote, forge) = Forge::detect(&app, &repo, remote.as_deref())?;
+        let branch = format!("pr-{number}");
+        let refspec = format!("+{}:{branch}", forge.head_ref(number));
+
+        let operation_id = app.state::<GitOperations>().operation_id(None);
+        git::operation::run_network_git(
+            &app,
+            &repo,
+            &["fetch", "--progress", remote.as_str(), refspec.as_str()],
+            &operation_id,
+        )?;
+        git::git_checkout(repo_path, branch)
+    })
+    .await
+    .map_err(|error| format!("forge request failed: {error}"))?
+}
+
+fn web_flavor(app: &tauri::AppHandle, host: &str) -> Result<WebFlavor, String> {
+    if host.contains("bitbucket") {
+        return Ok(WebFlavor::Bitbucket);
+    }
+    if host.contains("gitea") || host.contains("codeberg") || host.contains("forgejo") {
+        return Ok(WebFlavor::Gitea);
+    }
+
+    let kind = load_accounts(app)?
+        .into_iter()
+        .find(|account| account.host == host)
+        .map(|account| account.kind)
+        .or_else(|| guess_kind(host));
+    match kind {
+        Some(ForgeKind::Github) => Ok(WebFlavor::Github),
+        Some(ForgeKind::Gitlab) => Ok(WebFlavor::Gitlab),
+        None => Err(format!("unknown web interface for {host}")),
+    }
+}
+
+/// Percent-encodes each `/`-separated segment of a branch name or file path, keeping the slashes.
+fn encode_path(path: &str) -> String {
+    let mut encoded = String::with_capacity(path.len());
+    for byte in path.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~' | b'/') {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    encoded
+}
+
+fn line_anchor(flavor: WebFlavor, range: WebLineRange) -> String {
+    let (start, end) = (range.start.min(range.end), range.start.max(range.end));
+    match (flavor, start == end) {
+        (WebFlavor::Bitbucket, true) => format!("#lines-{start}"),
+        (WebFlavor::Bitbucket, false) => format!("#lines-{start}:{end}"),
+        (WebFlavor::Gitlab, false) => format!("#L{start}-{end}"),
+        (_, true) => format!("#L{start}"),
+        (_, false) => format!("#L{start}-L{end}"),
+    }
+}
+
+/// Builds the web URL of the repository, a commit, a branch or a file (optionally at a line
+/// range) on the remote's hosting service, and opens it in the browser when `open` is set.
+/// File links default to the HEAD commit so they stay valid after the branch moves on.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn git_web_url(
+    repo_path: String,
+    kind: WebUrlKind,
+    target: Option<String>,
+    rev: Option<String>,
+    line_range: Option<WebLineRange>,
+    remote: Option<String>,
+    open: Option<bool>,
+    app: tauri::AppHandle,
+) -> Result<String, AppError> {
+    let repo = PathBuf::from(repo_path);
+    let (_, url) = remote::remote_url(&repo, remote.as_deref())?;
+    let parsed = parse_remote_url(&url).ok_or_else(|| AppError::invalid(format!("unrecognized remote url: {url}")))?;
+    let flavor = web_flavor(&app, &parsed.host)?;
+    let base = format!("https://{}/{}", parsed.host, parsed.path());
+
+    let resolve = |revision: &str| {
+        git::rev_parse(&repo, &format!("{revision}^{{commit}}"))
+            .ok_or_else(|| AppError::invalid(format!("unknown revision: {revision}")))
+    };
+    let required = |value: Option<String>, what: &str| {
+        value
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+            .ok_or_else(|| AppError::invalid(format!("{what} is required")))
+    };
+
+    let web_url = match kind {
+        WebUrlKind::Repo => base,
+        WebUrlKind::Commit => {
+            let commit = resolve(&required(target, "commit")?)?;
+            match flavor {
+                WebFlavor::Github | WebFlavor::Gitea => format!("{base}/commit/{commit}"),
+                WebFlavor::Gitlab => format!("{base}/-/commit/{commit}"),
+                WebFlavor::Bitbucket => format!("{base}/commits/{commit}"),
+            }
+        }
+        WebUrlKind::Branch => {
+            let branch = encode_path(&required(target, "branch")?);
+            match flavor {
+                WebFlavor::Github => format!("{base}/tree/{branch}"),
+                WebFlavor::Gitlab => format!("{base}/-/tree/{branch}"),
+                WebFlavor::Bitbucket => format!("{base}/branch/{branch}"),
+                WebFlavor::Gitea => format!("{base}/src/branch/{branch}"),
+            }
+        }
+        WebUrlKind::File => {
+            let path = encode_path(&required(target, "file path")?.replace('\\', "/"));
+            let commit = resolve(rev.as_deref().unwrap_or("HEAD"))?;
+            let mut file_url = match flavor {
+                WebFlavor::Github => format!("{base}/blob/{commit}/{path}"),
+                WebFlavor::Gitlab => format!("{base}/-/blob/{commit}/{path}"),
+                WebFlavor::Bitbucket => format!("{base}/src/{commit}/{path}"),
+                WebFlavor::Gitea => format!("{base}/src/commit/{commit}/{path}"),
+            };
+            if let Some(range) = line_range {
+                file_url.push_str(&line_anchor(flavor, range));
+            }
+            file_url
+        }
+    };
+
+    if open.unwrap_or(false) {
+        app.opener()
+            .open_url(web_url.as_str(), None::<&str>)
+            .map_err(|error| format!("failed to open {web_url}: {error}"))?;
+    }
+    Ok(web_url)
+}
@@ -1,17 +1,74 @@
-use serde::Serialize;
+pub mod archive;
+pub mod bisect;
+pub mod blame;
+pub mod config;
+pub mod conventional;
+pub mod diff;
+pub mod discard;
+pub mod hooks;
+pub mod identity;
+pub mod init;
+#[cfg(feature = "libgit2")]
+mod libgit;
+pub mod log;
+pub mod merge;
+pub mod operation;
+pub mod patch;
+pub mod rebase;
+pub mod reflog;
+pub mod remote;
+pub mod show;
+pub mod signing;
+pub mod stash;
+pub mod stats;
+pub mod status_cache;
+pub mod submodule;
+pub mod watcher;
+pub mod word_diff;
+pub mod worktree;
+
+use crate::{
+    askpass,
+    error::{AppError, ErrorKind},
+};
+use operation::{run_network_command, run_network_git, GitOperations, NetworkOutput};
+use serde::{Deserialize, Serialize};
 use std::{
+    io::Write,
     path::{Path, PathBuf},
-    process::Command,
+    process::{Command, Output, Stdio},
 };
+use tauri::Manager;
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum GitChangeKind {
+    Ordinary,
+    Renamed,
+    Copied,
+    Conflicted,
+    Untracked,
+}
 
 #[derive(Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GitChange {
     path: String,
+    /// Two-letter porcelain v1 style code, e.g. `M `, ` D`, `R ` or `??`.
     status: String,
+    kind: GitChangeKind,
     staged: bool,
     unstaged: bool,
     untracked: bool,
+    /// Source path of a rename or copy.
+    orig_path: Option<String>,
+    /// Similarity percentage of a rename or copy.
+    similarity: Option<u8>,
+    /// An untracked directory listed as a whole, with a trailing `/`, because nothing in it is
+    /// tracked.
+    directory: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    submodule: Option<submodule::SubmoduleChange>,
 }
 
 #[derive(Clone, Serialize)]
@@ -19,9 +76,19 @@ pub struct GitChange {
 pub struct GitStatusResponse {
     repo_path: String,
     branch: String,
+    /// Tracking branch such as `origin/main`, or `None` when the branch was never published.
+    upstream: Option<String>,
+    /// The upstream is configured but its remote branch no longer exists.
+    upstream_gone: bool,
     ahead: usize,
     behind: usize,
     changes: Vec<GitChange>,
+    /// Ignored files, with wholly ignored directories collapsed like untracked ones. Only
+    /// listed when asked for.
+    ignored: Vec<String>,
+    /// Set when the repository's author differs from the identity assigned to repositories
+    /// like it.
+    identity_mismatch: Option<identity::IdentityMismatch>,
 }
 
 #[derive(Clone, Serialize)]
@@ -29,44 +96,174 @@ pub struct GitStatusResponse {
 pub struct GitBranchesResponse {
     current: String,
     branches: Vec<String>,
+    local: Vec<GitBranchInfo>,
+    remote: Vec<GitBranchInfo>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitBranchInfo {
+    name: String,
+    upstream: Option<String>,
+    upstream_gone: bool,
+    ahead: usize,
+    behind: usize,
+    last_commit: String,
+    last_commit_subject: String,
+    last_commit_date: String,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum GitPullStrategy {
+    Merge,
+    Rebase,
+    FfOnly,
+}
+
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum GitPullOutcome {
+    UpToDate,
+    FastForward,
+    MergeCommit,
+    Rebased,
+    RebaseInProgress,
+    Conflicts,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitPullResponse {
+    outcome: GitPullOutcome,
+    conflicts: Vec<String>,
+    output: String,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitPushRef {
+    local_ref: String,
+    remote_ref: String,
+    summary: String,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitPushResponse {
+    success: bool,
+    remote: Option<String>,
+    branch: Option<String>,
+    new_upstream: Option<String>,
+    updated: Vec<GitPushRef>,
+    rejected: Vec<GitPushRef>,
+    output: String,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitBranchDeleteResponse {
+    deleted: bool,
+    unmerged: bool,
+    message: String,
+}
+
+fn run_git(repo_path: &Path, args: &[&str]) -> Result<String, AppError> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo_path)
+        .args(args)
+        .output()
+        .map_err(|error| format!("failed to run git: {error}"))?;
+
+    git_output(output)
+}
+
+/// Runs a git command that changes the index, worktree or refs, and drops the cached status.
+fn run_git_mutating(repo_path: &Path, args: &[&str]) -> Result<String, AppError> {
+    let result = run_git(repo_path, args);
+    status_cache::invalidate(repo_path);
+    result
 }
 
-fn run_git(repo_path: &Path, args: &[&str]) -> Result<String, String> {
+fn run_git_with_env(repo_path: &Path, args: &[&str], envs: &[(&str, &str)]) -> Result<String, AppError> {
     let output = Command::new("git")
         .arg("-C")
         .arg(repo_path)
         .args(args)
+        .envs(envs.iter().copied())
         .output()
         .map_err(|error| format!("failed to run git: {error}"))?;
 
+    git_output(output)
+}
+
+fn run_git_with_input(repo_path: &Path, args: &[&str], input: &str) -> Result<String, AppError> {
+    let mut child = Command::new("git")
+        .arg("-C")
+        .arg(repo_path)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|error| format!("failed to run git: {error}"))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(input.as_bytes())
+            .map_err(|error| format!("failed to write to git: {error}"))?;
+    }
+
+    let output = child
+        .wait_with_output()
+        .map_err(|error| format!("failed to run git: {error}"))?;
+
+    git_output(output)
+}
+
+fn git_output(output: Output) -> Result<String, AppError> {
     if output.status.success() {
         return Ok(String::from_utf8_lossy(&output.stdout).to_string());
     }
 
     let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
-    Err(if stderr.is_empty() {
-        "git command failed".to_string()
-    } else {
-        stderr
-    })
+    Err(AppError::git(stderr, output.status.code()))
+}
+
+/// Resolves a path inside the git directory, e.g. `rebase-merge` or `MERGE_HEAD`.
+fn git_path(repo: &Path, name: &str) -> Result<PathBuf, AppError> {
+    let output = run_git(repo, &["rev-parse", "--git-path", name])?;
+    let path = PathBuf::from(output.trim());
+    Ok(if path.is_absolute() { path } else { repo.join(path) })
 }
 
-fn resolve_git_root(path: &Path) -> Result<PathBuf, String> {
+fn conflicted_paths(repo: &Path) -> Result<Vec<String>, AppError> {
+    let raw = run_git(repo, &["diff", "--name-only", "--diff-filter=U"])?;
+    Ok(raw
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(ToOwned::to_owned)
+        .collect())
+}
+
+fn resolve_git_root(path: &Path) -> Result<PathBuf, AppError> {
     let output = run_git(path, &["rev-parse", "--show-toplevel"])?;
     let root = output.trim();
     if root.is_empty() {
-        return Err("failed to detect git root".to_string());
+        return Err(AppError::new(ErrorKind::GitNotRepository, "failed to detect git root"));
     }
     Ok(PathBuf::from(root))
 }
 
-fn detect_repo_root(explicit_path: Option<String>) -> Result<PathBuf, String> {
+fn detect_repo_root(explicit_path: Option<String>) -> Result<PathBuf, AppError> {
     if let Some(path) = explicit_path {
         let candidate = PathBuf::from(path);
         if candidate.exists() {
             return resolve_git_root(&candidate);
         }
-        return Err("repo path does not exist".to_string());
+        return Err(AppError::invalid("repo path does not exist"));
     }
 
     let mut candidate = std::env::current_dir().map_err(|error| format!("cwd error: {error}"))?;
@@ -81,84 +278,224 @@ fn detect_repo_root(explicit_path: Option<String>) -> Result<PathBuf, String> {
         }
     }
 
-    Err("git repository not found".to_string())
+    Err(AppError::new(ErrorKind::GitNotRepository, "git repository not found"))
+}
+
+/// How much of the untracked files `git_status` looks for, as in `--untracked-files`. `No`
+/// skips scanning the work tree for new files, which is most of the cost in large repositories.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum GitUntrackedMode {
+    No,
+    /// Wholly untracked directories are listed as one entry.
+    #[default]
+    Normal,
+    All,
 }
 
+impl GitUntrackedMode {
+    fn as_arg(self) -> &'static str {
+        match self {
+            GitUntrackedMode::No => "--untracked-files=no",
+            GitUntrackedMode::Normal => "--untracked-files=normal",
+            GitUntrackedMode::All => "--untracked-files=all",
+        }
+    }
+}
+
+#[derive(Clone, Default, PartialEq, Eq)]
+pub(crate) struct GitStatusOptions {
+    pub include_ignored: bool,
+    pub untracked: GitUntrackedMode,
+    /// Limits the status to a subdirectory or pathspec, relative to the repository root.
+    pub pathspec: Option<String>,
+}
+
+/// Status of the repository at `repo_path`. Results for an explicit path and default options are
+/// cached briefly, see `status_cache`.
 #[tauri::command]
-pub fn git_status(repo_path: Option<String>) -> Result<GitStatusResponse, String> {
+pub fn git_status(
+    repo_path: Option<String>,
+    include_ignored: Option<bool>,
+    untracked: Option<GitUntrackedMode>,
+    pathspec: Option<String>,
+    app: tauri::AppHandle,
+) -> Result<GitStatusResponse, AppError> {
+    let options = GitStatusOptions {
+        include_ignored: include_ignored.unwrap_or(false),
+        untracked: untracked.unwrap_or_default(),
+        pathspec: pathspec
+            .map(|pathspec| pathspec.trim().to_string())
+            .filter(|pathspec| !pathspec.is_empty()),
+    };
+    let Some(path) = repo_path.as_deref().map(Path::new) else {
+        return load_status(&app, None, &options);
+    };
+    if options != GitStatusOptions::default() {
+        return load_status(&app, repo_path.clone(), &options);
+    }
+    if let Some(status) = status_cache::get(path) {
+        return Ok(status);
+    }
+
+    let status = load_status(&app, repo_path.clone(), &options)?;
+    status_cache::store(path, &status);
+    Ok(status)
+}
+
+fn load_status(
+    app: &tauri::AppHandle,
+    repo_path: Option<String>,
+    options: &GitStatusOptions,
+) -> Result<GitStatusResponse, AppError> {
+    #[cfg(feature = "libgit2")]
+    if let Ok(mut status) = libgit::status(repo_path.as_deref(), options) {
+        submodule::annotate_changes(Path::new(&status.repo_path), &mut status.changes);
+        status.identity_mismatch = identity::check_mismatch(app, Path::new(&status.repo_path));
+        return Ok(status);
+    }
+
     let repo = detect_repo_root(repo_path)?;
-    let raw = run_git(&repo, &["status", "--porcelain=v1", "--branch"])?;
+    let mut args = vec![
+        "--no-optional-locks",
+        "status",
+        "--porcelain=v2",
+        "--branch",
+        "-z",
+        options.untracked.as_arg(),
+    ];
+    if options.include_ignored {
+        args.push("--ignored");
+    }
+    if let Some(pathspec) = options.pathspec.as_deref() {
+        args.extend(["--", pathspec]);
+    }
+    let raw = run_git(&repo, &args)?;
+    let mut status = parse_status_v2(&repo, &raw);
+    status.identity_mismatch = identity::check_mismatch(app, &repo);
+    Ok(status)
+}
 
-    let mut branch = "unknown".to_string();
-    let mut ahead: usize = 0;
-    let mut behind: usize = 0;
-    let mut changes = Vec::new();
+fn status_change(xy: &str, kind: GitChangeKind, sub: &str, path: &str) -> GitChange {
+    // v2 marks an unchanged side with `.`; v1 used a space.
+    let status = xy.replace('.', " ");
+    let x = status.chars().next().unwrap_or(' ');
+    let y = status.chars().nth(1).unwrap_or(' ');
+    let conflicted = kind == GitChangeKind::Conflicted;
 
-    for line in raw.lines() {
-        if let Some(rest) = line.strip_prefix("## ") {
-            let mut head = rest.trim();
-            let mut tracking_meta: Option<&str> = None;
+    GitChange {
+        path: path.to_string(),
+        kind,
+        staged: !conflicted && x != ' ',
+        unstaged: conflicted || y != ' ',
+        untracked: false,
+        orig_path: None,
+        similarity: None,
+        directory: false,
+        submodule: submodule::from_status_flags(sub),
+        status,
+    }
+}
 
-            if let Some((prefix, suffix)) = rest.split_once(" [") {
-                head = prefix.trim();
-                tracking_meta = Some(suffix.trim_end_matches(']').trim());
-            }
+/// Parses `git status --porcelain=v2 --branch -z`. Records are NUL separated; rename and copy
+/// records are followed by an extra record holding the original path.
+fn parse_status_v2(repo: &Path, raw: &str) -> GitStatusResponse {
+    let mut branch = "unknown".to_string();
+    let mut upstream = None;
+    let mut has_tracking = false;
+    let mut ahead = 0;
+    let mut behind = 0;
+    let mut changes = Vec::new();
+    let mut ignored = Vec::new();
+    let mut records = raw.split('\0');
 
-            branch = head
-                .split_once("...")
-                .map(|(left, _)| left)
-                .unwrap_or(head)
-                .trim()
-                .to_string();
-
-            if let Some(meta) = tracking_meta {
-                for part in meta.split(',') {
-                    let chunk = part.trim();
-                    if let Some(value) = chunk.strip_prefix("ahead ") {
-                        ahead = value.trim().parse::<usize>().unwrap_or(0);
-                    } else if let Some(value) = chunk.strip_prefix("behind ") {
-                        behind = value.trim().parse::<usize>().unwrap_or(0);
+    while let Some(record) = records.next() {
+        if let Some(header) = record.strip_prefix("# ") {
+            let (key, value) = header.split_once(' ').unwrap_or((header, ""));
+            match key {
+                "branch.head" if value == "(detached)" => branch = "HEAD (no branch)".to_string(),
+                "branch.head" => branch = value.to_string(),
+                "branch.upstream" => upstream = Some(value.to_string()),
+                // Missing when the upstream ref is gone.
+                "branch.ab" => {
+                    has_tracking = true;
+                    for part in value.split_whitespace() {
+                        if let Some(count) = part.strip_prefix('+') {
+                            ahead = count.parse().unwrap_or(0);
+                        } else if let Some(count) = part.strip_prefix('-') {
+                            behind = count.parse().unwrap_or(0);
+                        }
                     }
                 }
+                _ => {}
             }
             continue;
         }
 
-        if line.len() < 4 {
-            continue;
-        }
-
-        let status = &line[0..2];
-        let x = status.chars().next().unwrap_or(' ');
-        let y = status.chars().nth(1).unwrap_or(' ');
-        let mut path = line[3..].trim().to_string();
-
-        if let Some((_, to)) = path.split_once(" -> ") {
-            path = to.to_string();
+        let fields = record.splitn(11, ' ').collect::<Vec<&str>>();
+        match fields.first().copied() {
+            Some("1") if fields.len() >= 9 => {
+                let path = fields[8..].join(" ");
+                changes.push(status_change(fields[1], GitChangeKind::Ordinary, fields[2], &path));
+            }
+            Some("2") if fields.len() >= 10 => {
+                let score = fields[8];
+                let kind = if score.starts_with('C') {
+                    GitChangeKind::Copied
+                } else {
+                    GitChangeKind::Renamed
+                };
+                let path = fields[9..].join(" ");
+                let mut change = status_change(fields[1], kind, fields[2], &path);
+                change.similarity = score.get(1..).and_then(|value| value.parse().ok());
+                change.orig_path = records.next().map(ToOwned::to_owned);
+                changes.push(change);
+            }
+            Some("u") if fields.len() >= 11 => {
+                changes.push(status_change(fields[1], GitChangeKind::Conflicted, fields[2], fields[10]));
+            }
+            Some("?") => {
+                let path = record.get(2..).unwrap_or_default();
+                changes.push(GitChange {
+                    path: path.to_string(),
+                    status: "??".to_string(),
+                    kind: GitChangeKind::Untracked,
+                    staged: false,
+                    unstaged: true,
+                    untracked: true,
+                    orig_path: None,
+                    similarity: None,
+                    directory: path.ends_with('/'),
+                    submodule: None,
+                });
+            }
+            Some("!") => ignored.push(record.get(2..).unwrap_or_default().to_string()),
+            _ => {}
         }
-
-        changes.push(GitChange {
-            path,
-            status: status.to_string(),
-            staged: x != ' ' && x != '?',
-            unstaged: y != ' ',
-            untracked: x == '?' && y == '?',
-        });
     }
 
-    Ok(GitStatusResponse {
+    GitStatusResponse {
         repo_path: repo.to_string_lossy().to_string(),
+        upstream_gone: upstream.is_some() && !has_tracking,
         branch,
+        upstream,
         ahead,
         behind,
         changes,
-    })
+        ignored,
+        identity_mismatch: None,
+    }
 }
 
 #[tauri::command]
-pub fn git_diff(repo_path: String, path: String, staged: bool, untracked: bool) -> Result<String, String> {
+pub fn git_diff(repo_path: String, path: String, staged: bool, untracked: bool) -> Result<String, AppError> {
     let repo = PathBuf::from(repo_path);
 
+    #[cfg(feature = "libgit2")]
+    if let Ok(diff) = libgit::diff(&repo, Some(&path), staged, untracked) {
+        return Ok(diff);
+    }
+
     if untracked {
         let output = Command::new("git")
             .arg("-C")
@@ -173,11 +510,7 @@ pub fn git_diff(repo_path: String, path: String, staged: bool, untracked: bool)
         }
 
         let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
-        return Err(if stderr.is_empty() {
-            "failed to generate diff".to_string()
-        } else {
-            stderr
-        });
+        return Err(AppError::git(stderr, output.status.code()));
     }
 
     if staged {
@@ -188,105 +521,676 @@ pub fn git_diff(repo_path: String, path: String, staged: bool, untracked: bool)
 }
 
 #[tauri::command]
-pub fn git_stage(repo_path: String, path: String) -> Result<(), String> {
+pub fn git_stage(repo_path: String, path: String) -> Result<(), AppError> {
     let repo = PathBuf::from(repo_path);
-    run_git(&repo, &["add", "--", path.as_str()]).map(|_| ())
+    run_git_mutating(&repo, &["add", "--", path.as_str()]).map(|_| ())
 }
 
 #[tauri::command]
-pub fn git_stage_all(repo_path: String) -> Result<(), String> {
+pub fn git_stage_all(repo_path: String) -> Result<(), AppError> {
     let repo = PathBuf::from(repo_path);
-    run_git(&repo, &["add", "--all"]).map(|_| ())
+    run_git_mutating(&repo, &["add", "--all"]).map(|_| ())
 }
 
 #[tauri::command]
-pub fn git_unstage(repo_path: String, path: String) -> Result<(), String> {
+pub fn git_unstage(repo_path: String, path: String) -> Result<(), AppError> {
     let repo = PathBuf::from(repo_path);
 
-    if run_git(&repo, &["restore", "--staged", "--", path.as_str()]).is_ok() {
+    if run_git_mutating(&repo, &["restore", "--staged", "--", path.as_str()]).is_ok() {
         return Ok(());
     }
 
-    run_git(&repo, &["reset", "HEAD", "--", path.as_str()]).map(|_| ())
+    run_git_mutating(&repo, &["reset", "HEAD", "--", path.as_str()]).map(|_| ())
+}
+
+/// Runs a mutating command over `paths` in one git process. The paths go through stdin so large
+/// selections don't run into command line length limits.
+fn run_git_with_pathspecs(repo: &Path, args: &[&str], paths: &[String]) -> Result<String, AppError> {
+    if paths.is_empty() {
+        return Err(AppError::invalid("no paths selected"));
+    }
+
+    let mut args = args.to_vec();
+    args.extend(["--pathspec-from-file=-", "--pathspec-file-nul"]);
+    let result = run_git_with_input(repo, &args, &paths.join("\0"));
+    status_cache::invalidate(repo);
+    result
 }
 
 #[tauri::command]
-pub fn git_commit(repo_path: String, message: String, amend: bool) -> Result<String, String> {
+pub fn git_stage_paths(repo_path: String, paths: Vec<String>) -> Result<(), AppError> {
     let repo = PathBuf::from(repo_path);
-    let trimmed = message.trim();
+    run_git_with_pathspecs(&repo, &["add"], &paths).map(|_| ())
+}
+
+#[tauri::command]
+pub fn git_unstage_paths(repo_path: String, paths: Vec<String>) -> Result<(), AppError> {
+    let repo = PathBuf::from(repo_path);
+
+    if run_git_with_pathspecs(&repo, &["restore", "--staged"], &paths).is_ok() {
+        return Ok(());
+    }
+
+    run_git_with_pathspecs(&repo, &["reset", "HEAD"], &paths).map(|_| ())
+}
 
+/// Checks an author override is a full `Name <email>` ident. Anything else would make git
+/// search earlier commits for a matching author instead.
+fn validate_author(author: &str) -> Result<(), AppError> {
+    let valid = author
+        .strip_suffix('>')
+        .and_then(|rest| rest.split_once(" <"))
+        .is_some_and(|(name, email)| !name.trim().is_empty() && !email.is_empty() && !email.contains(['<', '>']));
+    if valid {
+        Ok(())
+    } else {
+        Err(AppError::invalid(format!("author must look like \"Name <email>\": {author}")))
+    }
+}
+
+/// Commits the staged changes. `sign` forces signing on or off; when omitted `commit.gpgsign`
+/// decides. A rejecting `pre-commit` or `commit-msg` hook comes back as `GitHookRejected` with
+/// the hook's output. `author` overrides the author as `Name <email>`.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn git_commit(
+    repo_path: String,
+    message: String,
+    amend: bool,
+    sign: Option<bool>,
+    no_verify: Option<bool>,
+    signoff: Option<bool>,
+    allow_empty: Option<bool>,
+    author: Option<String>,
+    app: tauri::AppHandle,
+) -> Result<String, AppError> {
+    let repo = PathBuf::from(repo_path);
+    let options = GitCommitOptions {
+        amend,
+        sign,
+        no_verify: no_verify.unwrap_or(false),
+        signoff: signoff.unwrap_or(false),
+        allow_empty: allow_empty.unwrap_or(false),
+        author: author.map(|author| author.trim().to_string()).filter(|author| !author.is_empty()),
+    };
+    commit(&app, &repo, &message, &options, &[])
+}
+
+/// Commits only `paths`, as they are in the worktree, leaving the rest of the index staged.
+#[tauri::command]
+pub fn git_commit_paths(
+    repo_path: String,
+    message: String,
+    paths: Vec<String>,
+    sign: Option<bool>,
+    app: tauri::AppHandle,
+) -> Result<String, AppError> {
+    if paths.is_empty() {
+        return Err(AppError::invalid("no paths selected"));
+    }
+
+    let repo = PathBuf::from(repo_path);
+    let options = GitCommitOptions {
+        sign,
+        ..GitCommitOptions::default()
+    };
+    commit(&app, &repo, &message, &options, &paths)
+}
+
+#[derive(Default)]
+struct GitCommitOptions {
+    amend: bool,
+    sign: Option<bool>,
+    no_verify: bool,
+    signoff: bool,
+    allow_empty: bool,
+    author: Option<String>,
+}
+
+/// Runs `git commit`, limited to `paths` with `--only` when any are given. The paths go through
+/// stdin like in `run_git_with_pathspecs`.
+fn commit(
+    app: &tauri::AppHandle,
+    repo: &Path,
+    message: &str,
+    options: &GitCommitOptions,
+    paths: &[String],
+) -> Result<String, AppError> {
+    let trimmed = message.trim();
     if trimmed.is_empty() {
-        return Err("commit message is empty".to_string());
+        return Err(AppError::invalid("commit message is empty"));
+    }
+    if let Some(author) = &options.author {
+        validate_author(author)?;
     }
 
+    let signing = options.sign.unwrap_or_else(|| signing::signing_enabled(repo));
     let mut command = Command::new("git");
-    command.arg("-C").arg(&repo).arg("commit").arg("-m").arg(trimmed);
-    if amend {
+    command.arg("-C").arg(repo);
+    if signing {
+        let args = signing::signing_args(repo)?;
+        command.args(args.config_args).envs(args.envs).envs(askpass::git_env(app)?);
+    }
+    command.arg("commit").arg("-m").arg(trimmed);
+    if options.amend {
         command.arg("--amend");
     }
+    if options.no_verify {
+        command.arg("--no-verify");
+    }
+    if options.signoff {
+        command.arg("--signoff");
+    }
+    if options.allow_empty {
+        command.arg("--allow-empty");
+    }
+    if let Some(author) = &options.author {
+        command.arg(format!("--author={author}"));
+    }
+    match options.sign {
+        Some(true) => {
+            command.arg("--gpg-sign");
+        }
+        Some(false) => {
+            command.arg("--no-gpg-sign");
+        }
+        None => {}
+    }
+    if !paths.is_empty() {
+        command.args(["--only", "--pathspec-from-file=-", "--pathspec-file-nul"]);
+    }
 
-    let output = command
-        .output()
+    let trace = hooks::HookTrace::new();
+    let (trace_env, trace_path) = trace.env();
+    command.env(trace_env, trace_path);
+
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
         .map_err(|error| format!("failed to run git commit: {error}"))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(paths.join("\0").as_bytes())
+            .map_err(|error| format!("failed to write to git: {error}"))?;
+    }
+    let output = child
+        .wait_with_output()
+        .map_err(|error| format!("failed to run git commit: {error}"))?;
+    status_cache::invalidate(repo);
 
     if output.status.success() {
         return Ok(String::from_utf8_lossy(&output.stdout).to_string());
     }
 
+    if let Some(hook) = trace.failed_hook() {
+        return Err(hooks::hook_error(
+            hook,
+            &String::from_utf8_lossy(&output.stdout),
+            &String::from_utf8_lossy(&output.stderr),
+            output.status.code(),
+        ));
+    }
+
     let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
-    Err(if stderr.is_empty() {
-        "git commit failed".to_string()
-    } else {
-        stderr
-    })
+    if signing {
+        if let Some(error) = signing::signing_error(&stderr) {
+            return Err(AppError {
+                detail: Some(stderr),
+                exit_code: output.status.code(),
+                ..AppError::new(ErrorKind::GitFailed, error)
+            });
+        }
+    }
+    Err(AppError::git(stderr, output.status.code()))
+}
+
+/// Runs blocking network git work off the command thread so progress events can stream.
+async fn run_operation<T, F>(app: tauri::AppHandle, operation_id: Option<String>, task: F) -> Result<T, AppError>
+where
+    T: Send + 'static,
+    F: FnOnce(&tauri::AppHandle, &str) -> Result<T, AppError> + Send + 'static,
+{
+    let operation_id = app.state::<GitOperations>().operation_id(operation_id);
+    tauri::async_runtime::spawn_blocking(move || task(&app, &operation_id))
+        .await
+        .map_err(|error| AppError::from(format!("git operation failed: {error}")))?
 }
 
 #[tauri::command]
-pub fn git_fetch(repo_path: String) -> Result<String, String> {
+pub async fn git_fetch(
+    repo_path: String,
+    operation_id: Option<String>,
+    app: tauri::AppHandle,
+) -> Result<String, AppError> {
     let repo = PathBuf::from(repo_path);
-    run_git(&repo, &["fetch", "--prune"])
+    run_operation(app, operation_id, move |app, operation_id| {
+        run_network_git(app, &repo, &["fetch", "--prune", "--progress"], operation_id)
+    })
+    .await
+}
+
+pub(crate) fn rev_parse(repo: &Path, revision: &str) -> Option<String> {
+    run_git(repo, &["rev-parse", "--verify", "--quiet", revision])
+        .ok()
+        .map(|output| output.trim().to_string())
+        .filter(|hash| !hash.is_empty())
+}
+
+fn rebase_in_progress(repo: &Path) -> bool {
+    ["rebase-merge", "rebase-apply"]
+        .iter()
+        .any(|name| git_path(repo, name).map(|path| path.is_dir()).unwrap_or(false))
 }
 
 #[tauri::command]
-pub fn git_pull(repo_path: String) -> Result<String, String> {
+pub async fn git_pull(
+    repo_path: String,
+    strategy: Option<GitPullStrategy>,
+    operation_id: Option<String>,
+    app: tauri::AppHandle,
+) -> Result<GitPullResponse, AppError> {
     let repo = PathBuf::from(repo_path);
-    run_git(&repo, &["pull"])
+    run_operation(app, operation_id, move |app, operation_id| {
+        pull(app, &repo, strategy, operation_id)
+    })
+    .await
+}
+
+fn pull(
+    app: &tauri::AppHandle,
+    repo: &Path,
+    strategy: Option<GitPullStrategy>,
+    operation_id: &str,
+) -> Result<GitPullResponse, AppError> {
+    let repo = repo.to_path_buf();
+    let before = rev_parse(&repo, "HEAD");
+
+    let mut args = vec!["pull", "--no-edit", "--progress"];
+    match strategy {
+        Some(GitPullStrategy::Merge) => args.push("--no-rebase"),
+        Some(GitPullStrategy::Rebase) => args.push("--rebase"),
+        Some(GitPullStrategy::FfOnly) => args.push("--ff-only"),
+        None => {}
+    }
+
+    let output = match run_network_git(app, &repo, &args, operation_id) {
+        Ok(output) => output,
+        Err(error) => {
+            let conflicts = conflicted_paths(&repo)?;
+            let outcome = if rebase_in_progress(&repo) {
+                GitPullOutcome::RebaseInProgress
+            } else if !conflicts.is_empty() {
+                GitPullOutcome::Conflicts
+            } else {
+                return Err(error);
+            };
+
+            return Ok(GitPullResponse {
+                outcome,
+                conflicts,
+                output: error.into(),
+            });
+        }
+    };
+
+    let after = rev_parse(&repo, "HEAD");
+    let outcome = if before == after {
+        GitPullOutcome::UpToDate
+    } else if rev_parse(&repo, "HEAD^2").is_some() && rev_parse(&repo, "HEAD^1") == before {
+        GitPullOutcome::MergeCommit
+    } else if output.contains("Fast-forward") || strategy != Some(GitPullStrategy::Rebase) {
+        GitPullOutcome::FastForward
+    } else {
+        GitPullOutcome::Rebased
+    };
+
+    Ok(GitPullResponse {
+        outcome,
+        conflicts: Vec::new(),
+        output: output.trim().to_string(),
+    })
+}
+
+/// Repository around a directory and what it has checked out.
+#[derive(Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepoHead {
+    pub repo_root: String,
+    /// `None` when HEAD is detached.
+    pub branch: Option<String>,
+    /// Short hash of HEAD, absent on a branch without commits.
+    pub commit: Option<String>,
+}
+
+/// `None` when `path` isn't inside a work tree.
+pub(crate) fn repo_head(path: &Path) -> Option<RepoHead> {
+    let root = resolve_git_root(path).ok()?;
+    let branch = run_git(&root, &["branch", "--show-current"])
+        .ok()
+        .map(|branch| branch.trim().to_string())
+        .filter(|branch| !branch.is_empty());
+    let commit = run_git(&root, &["rev-parse", "--verify", "--quiet", "--short", "HEAD"])
+        .ok()
+        .map(|hash| hash.trim().to_string())
+        .filter(|hash| !hash.is_empty());
+
+    Some(RepoHead {
+        repo_root: root.to_string_lossy().to_string(),
+        branch,
+        commit,
+    })
+}
+
+pub(crate) fn current_branch(repo: &Path) -> Result<String, AppError> {
+    let branch = run_git(repo, &["branch", "--show-current"])?.trim().to_string();
+    if branch.is_empty() {
+        return Err(AppError::new(ErrorKind::GitFailed, "HEAD is detached"));
+    }
+    Ok(branch)
+}
+
+fn default_remote(repo: &Path) -> Result<String, AppError> {
+    let raw = run_git(repo, &["remote"])?;
+    let remotes = raw.lines().map(str::trim).filter(|name| !name.is_empty()).collect::<Vec<&str>>();
+
+    if remotes.contains(&"origin") {
+        return Ok("origin".to_string());
+    }
+    remotes
+        .first()
+        .map(|name| name.to_string())
+        .ok_or_else(|| AppError::new(ErrorKind::GitFailed, "no remotes configured"))
+}
+
+/// Parses `git push --porcelain` ref lines: `<flag>\t<from>:<to>\t<summary>`.
+fn parse_push_refs(raw: &str) -> (Vec<GitPushRef>, Vec<GitPushRef>) {
+    let mut updated = Vec::new();
+    let mut rejected = Vec::new();
+
+    for line in raw.lines() {
+        let mut fields = line.split('\t');
+        let (Some(flag), Some(refspec)) = (fields.next(), fields.next()) else {
+            continue;
+        };
+        let summary = fields.next().unwrap_or_default().to_string();
+        let (from, to) = refspec.split_once(':').unwrap_or((refspec, refspec));
+        let entry = GitPushRef {
+            local_ref: from.to_string(),
+            remote_ref: to.to_string(),
+            summary,
+        };
+
+        match flag {
+            "!" => rejected.push(entry),
+            " " | "+" | "-" | "*" => updated.push(entry),
+            _ => {}
+        }
+    }
+
+    (updated, rejected)
 }
 
 #[tauri::command]
-pub fn git_push(repo_path: String) -> Result<String, String> {
+#[allow(clippy::too_many_arguments)]
+pub async fn git_push(
+    repo_path: String,
+    remote: Option<String>,
+    branch: Option<String>,
+    set_upstream: Option<bool>,
+    force_with_lease: Option<bool>,
+    push_tags: Option<bool>,
+    operation_id: Option<String>,
+    app: tauri::AppHandle,
+) -> Result<GitPushResponse, AppError> {
     let repo = PathBuf::from(repo_path);
-    run_git(&repo, &["push"])
+    let options = GitPushOptions {
+        remote,
+        branch,
+        set_upstream: set_upstream.unwrap_or(false),
+        force_with_lease: force_with_lease.unwrap_or(false),
+        push_tags: push_tags.unwrap_or(false),
+    };
+    run_operation(app, operation_id, move |app, operation_id| {
+        push(app, &repo, options, operation_id)
+    })
+    .await
+}
+
+struct GitPushOptions {
+    remote: Option<String>,
+    branch: Option<String>,
+    set_upstream: bool,
+    force_with_lease: bool,
+    push_tags: bool,
+}
+
+fn push(
+    app: &tauri::AppHandle,
+    repo: &Path,
+    options: GitPushOptions,
+    operation_id: &str,
+) -> Result<GitPushResponse, AppError> {
+    let repo = repo.to_path_buf();
+    let GitPushOptions {
+        remote,
+        branch,
+        set_upstream,
+        force_with_lease,
+        push_tags,
+    } = options;
+
+    let branch = match branch.map(|branch| branch.trim().to_string()).filter(|branch| !branch.is_empty()) {
+        Some(branch) => Some(validate_branch_name(&repo, &branch)?),
+        None if set_upstream => Some(current_branch(&repo)?),
+        None => None,
+    };
+    let remote = match remote {
+        Some(remote) => Some(remote::validate_remote_name(&remote)?.to_string()),
+        None if branch.is_some() => Some(default_remote(&repo)?),
+        None => None,
+    };
+
+    let mut args = vec!["push", "--porcelain", "--progress"];
+    if set_upstream {
+        args.push("--set-upstream");
+    }
+    if force_with_lease {
+        args.push("--force-with-lease");
+    }
+    if push_tags {
+        args.push("--tags");
+    }
+    if let Some(remote) = remote.as_deref() {
+        args.push(remote);
+    }
+    if let Some(branch) = branch.as_deref() {
+        args.push(branch);
+    }
+
+    let NetworkOutput {
+        success,
+        exit_code,
+        stdout,
+        stderr,
+    } = run_network_command(app, &repo, &args, operation_id)?;
+    let (updated, rejected) = parse_push_refs(&stdout);
+
+    if !success && rejected.is_empty() {
+        return Err(AppError::git(stderr, exit_code));
+    }
+
+    let new_upstream = match (set_upstream && success, &remote, &branch) {
+        (true, Some(remote), Some(branch)) => Some(format!("{remote}/{branch}")),
+        _ => None,
+    };
+
+    Ok(GitPushResponse {
+        success,
+        remote,
+        branch,
+        new_upstream,
+        updated,
+        rejected,
+        output: format!("{stdout}{stderr}").trim().to_string(),
+    })
+}
+
+/// Parses `%(upstream:track)` output such as `[ahead 1, behind 2]` or `[gone]`.
+fn parse_tracking(track: &str) -> (usize, usize, bool) {
+    let inner = track.trim().trim_start_matches('[').trim_end_matches(']');
+    let mut ahead = 0;
+    let mut behind = 0;
+
+    for part in inner.split(',') {
+        let chunk = part.trim();
+        if let Some(value) = chunk.strip_prefix("ahead ") {
+            ahead = value.trim().parse::<usize>().unwrap_or(0);
+        } else if let Some(value) = chunk.strip_prefix("behind ") {
+            behind = value.trim().parse::<usize>().unwrap_or(0);
+        }
+    }
+
+    (ahead, behind, inner == "gone")
 }
 
 #[tauri::command]
-pub fn git_branches(repo_path: String) -> Result<GitBranchesResponse, String> {
+pub fn git_branches(repo_path: String) -> Result<GitBranchesResponse, AppError> {
     let repo = PathBuf::from(repo_path);
     let current = run_git(&repo, &["branch", "--show-current"])?.trim().to_string();
-    let raw = run_git(&repo, &["for-each-ref", "--format=%(refname:short)", "refs/heads"])?;
+    let raw = run_git(
+        &repo,
+        &[
+            "for-each-ref",
+            "--format=%(refname)%1f%(refname:short)%1f%(upstream:short)%1f%(upstream:track)%1f%(objectname:short)%1f%(subject)%1f%(committerdate:iso-strict)",
+            "refs/heads",
+            "refs/remotes",
+        ],
+    )?;
 
-    let mut branches = raw
-        .lines()
-        .map(str::trim)
-        .filter(|line| !line.is_empty())
-        .map(ToOwned::to_owned)
-        .collect::<Vec<String>>();
-    branches.sort();
+    let mut local = Vec::new();
+    let mut remote = Vec::new();
+
+    for line in raw.lines() {
+        let fields = line.split('\u{1f}').collect::<Vec<&str>>();
+        if fields.len() < 7 || fields[0].ends_with("/HEAD") {
+            continue;
+        }
+
+        let (ahead, behind, upstream_gone) = parse_tracking(fields[3]);
+        let info = GitBranchInfo {
+            name: fields[1].to_string(),
+            upstream: Some(fields[2].to_string()).filter(|upstream| !upstream.is_empty()),
+            upstream_gone,
+            ahead,
+            behind,
+            last_commit: fields[4].to_string(),
+            last_commit_subject: fields[5].to_string(),
+            last_commit_date: fields[6].to_string(),
+        };
 
-    Ok(GitBranchesResponse { current, branches })
+        if fields[0].starts_with("refs/heads/") {
+            local.push(info);
+        } else {
+            remote.push(info);
+        }
+    }
+
+    local.sort_by(|left, right| left.name.cmp(&right.name));
+    remote.sort_by(|left, right| left.name.cmp(&right.name));
+
+    let branches = local.iter().map(|branch| branch.name.clone()).collect::<Vec<String>>();
+
+    Ok(GitBranchesResponse {
+        current,
+        branches,
+        local,
+        remote,
+    })
 }
 
 #[tauri::command]
-pub fn git_checkout(repo_path: String, branch: String) -> Result<String, String> {
+pub fn git_checkout(repo_path: String, branch: String) -> Result<String, AppError> {
     let repo = PathBuf::from(repo_path);
     let target = branch.trim().to_string();
     if target.is_empty() {
-        return Err("branch name is empty".to_string());
+        return Err(AppError::invalid("branch name is empty"));
     }
 
-    if run_git(&repo, &["switch", target.as_str()]).is_ok() {
+    if run_git_mutating(&repo, &["switch", target.as_str()]).is_ok() {
         return Ok(format!("Switched to branch '{target}'"));
     }
 
-    run_git(&repo, &["checkout", target.as_str()])
+    run_git_mutating(&repo, &["checkout", target.as_str()])
+}
+
+fn validate_branch_name(repo: &Path, name: &str) -> Result<String, AppError> {
+    let trimmed = name.trim();
+    if trimmed.is_empty() {
+        return Err(AppError::invalid("branch name is empty"));
+    }
+    if trimmed.starts_with('-') {
+        return Err(AppError::invalid(format!("invalid branch name: {trimmed}")));
+    }
+
+    run_git(repo, &["check-ref-format", "--branch", trimmed])
+        .map_err(|_| AppError::invalid(format!("invalid branch name: {trimmed}")))?;
+    Ok(trimmed.to_string())
+}
+
+#[tauri::command]
+pub fn git_branch_create(
+    repo_path: String,
+    name: String,
+    from: Option<String>,
+    checkout: bool,
+) -> Result<String, AppError> {
+    let repo = PathBuf::from(repo_path);
+    let name = validate_branch_name(&repo, &name)?;
+
+    let mut args = if checkout {
+        vec!["switch", "-c", name.as_str()]
+    } else {
+        vec!["branch", name.as_str()]
+    };
+
+    let from = from.map(|from| from.trim().to_string()).filter(|from| !from.is_empty());
+    if let Some(from) = from.as_deref() {
+        args.push(log::validate_revision(from)?);
+    }
+
+    run_git_mutating(&repo, &args)?;
+    Ok(format!("Created branch '{name}'"))
+}
+
+#[tauri::command]
+pub fn git_branch_delete(repo_path: String, name: String, force: bool) -> Result<GitBranchDeleteResponse, AppError> {
+    let repo = PathBuf::from(repo_path);
+    let name = validate_branch_name(&repo, &name)?;
+    let flag = if force { "-D" } else { "-d" };
+
+    match run_git_mutating(&repo, &["branch", flag, name.as_str()]) {
+        Ok(output) => Ok(GitBranchDeleteResponse {
+            deleted: true,
+            unmerged: false,
+            message: output.trim().to_string(),
+        }),
+        Err(error) if error.message.contains("not fully merged") => Ok(GitBranchDeleteResponse {
+            deleted: false,
+            unmerged: true,
+            message: error.into(),
+        }),
+        Err(error) => Err(error),
+    }
+}
+
+#[tauri::command]
+pub fn git_branch_rename(repo_path: String, old_name: String, new_name: String) -> Result<String, AppError> {
+    let repo = PathBuf::from(repo_path);
+    let old_name = validate_branch_name(&repo, &old_name)?;
+    let new_name = validate_branch_name(&repo, &new_name)?;
+
+    run_git_mutating(&repo, &["branch", "-m", old_name.as_str(), new_name.as_str()])?;
+    Ok(format!("Renamed branch '{old_name}' to '{new_name}'"))
 }
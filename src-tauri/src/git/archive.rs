@@ -0,0 +1,54 @@
+use super::{log::validate_revision, rev_parse, run_git};
+use crate::error::AppError;
+use serde::Deserialize;
+use std::path::PathBuf;
+
+#[derive(Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GitArchiveFormat {
+    Zip,
+    Tar,
+    /// Gzipped tar, `tar.gz` in git's terms.
+    Tgz,
+}
+
+impl GitArchiveFormat {
+    fn as_arg(self) -> &'static str {
+        match self {
+            GitArchiveFormat::Zip => "--format=zip",
+            GitArchiveFormat::Tar => "--format=tar",
+            GitArchiveFormat::Tgz => "--format=tar.gz",
+        }
+    }
+}
+
+/// Exports the tree of `rev` to `output_path` as an archive. Runs off the command thread since
+/// large trees take a while. Returns the written path.
+#[tauri::command]
+pub async fn git_archive(
+    repo_path: String,
+    rev: String,
+    format: GitArchiveFormat,
+    output_path: String,
+) -> Result<String, AppError> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let repo = PathBuf::from(repo_path);
+        let rev = validate_revision(&rev)?;
+        if rev_parse(&repo, &format!("{rev}^{{tree}}")).is_none() {
+            return Err(AppError::invalid(format!("unknown revision: {rev}")));
+        }
+
+        let output = PathBuf::from(&output_path);
+        if let Some(parent) = output.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            if !parent.is_dir() {
+                return Err(AppError::invalid(format!("folder does not exist: {}", parent.display())));
+            }
+        }
+
+        let output_arg = format!("--output={}", output.display());
+        run_git(&repo, &["archive", format.as_arg(), &output_arg, rev])?;
+        Ok(output_path)
+    })
+    .await
+    .map_err(|error| AppError::from(format!("git archive failed: {error}")))?
+}
@@ -0,0 +1,140 @@
+use super::{
+    git_path,
+    log::{parse_log, validate_revision, GitCommit, LOG_FORMAT},
+    run_git,
+    run_git_mutating,
+};
+use crate::error::AppError;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+#[derive(Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BisectMark {
+    Good,
+    Bad,
+    Skip,
+}
+
+impl BisectMark {
+    fn as_str(self) -> &'static str {
+        match self {
+            BisectMark::Good => "good",
+            BisectMark::Bad => "bad",
+            BisectMark::Skip => "skip",
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitBisectStatus {
+    active: bool,
+    bad: Option<String>,
+    good: Vec<String>,
+    skipped: Vec<String>,
+    /// The checked out commit waiting to be marked; `None` once the first bad commit is found.
+    candidate: Option<GitCommit>,
+    first_bad: Option<GitCommit>,
+    /// Commits still suspected, including the bad one.
+    remaining: usize,
+    /// Roughly how many more marks it takes to finish.
+    steps: usize,
+}
+
+fn commit(repo: &Path, revision: &str) -> Option<GitCommit> {
+    let raw = run_git(repo, &["log", "-1", LOG_FORMAT, revision, "--"]).ok()?;
+    parse_log(&raw).into_iter().next()
+}
+
+fn bisect_refs(repo: &Path, prefix: &str) -> Vec<String> {
+    run_git(repo, &["for-each-ref", "--format=%(objectname)", prefix])
+        .map(|raw| raw.lines().map(str::trim).filter(|hash| !hash.is_empty()).map(ToOwned::to_owned).collect())
+        .unwrap_or_default()
+}
+
+/// Reads the state git keeps under `refs/bisect`. What is left to test comes from
+/// `rev-list --bisect-vars` over the bad commit minus the good ones.
+fn bisect_status(repo: &Path) -> Result<GitBisectStatus, AppError> {
+    let mut status = GitBisectStatus {
+        active: git_path(repo, "BISECT_START")?.is_file(),
+        bad: None,
+        good: Vec::new(),
+        skipped: Vec::new(),
+        candidate: None,
+        first_bad: None,
+        remaining: 0,
+        steps: 0,
+    };
+    if !status.active {
+        return Ok(status);
+    }
+
+    status.bad = bisect_refs(repo, "refs/bisect/bad").into_iter().next();
+    status.good = bisect_refs(repo, "refs/bisect/good-*");
+    status.skipped = bisect_refs(repo, "refs/bisect/skip-*");
+    // Until both ends are marked there is nothing to narrow down yet.
+    let Some(bad) = status.bad.clone().filter(|_| !status.good.is_empty()) else {
+        status.candidate = commit(repo, "HEAD");
+        return Ok(status);
+    };
+
+    let mut args = vec!["rev-list", "--bisect-vars", bad.as_str(), "--not"];
+    args.extend(status.good.iter().map(String::as_str));
+    let vars = run_git(repo, &args)?;
+    for line in vars.lines() {
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = value.trim_matches('\'');
+        match key {
+            "bisect_all" => status.remaining = value.parse().unwrap_or(0),
+            "bisect_steps" => status.steps = value.parse().unwrap_or(0),
+            _ => {}
+        }
+    }
+
+    if status.remaining <= 1 {
+        status.first_bad = commit(repo, &bad);
+    } else {
+        status.candidate = commit(repo, "HEAD");
+    }
+    Ok(status)
+}
+
+/// Starts bisecting between a known bad and a known good revision and checks out the first
+/// commit to test.
+#[tauri::command]
+pub fn git_bisect_start(repo_path: String, bad: String, good: String) -> Result<GitBisectStatus, AppError> {
+    let repo = PathBuf::from(repo_path);
+    let bad = validate_revision(&bad)?;
+    let good = validate_revision(&good)?;
+
+    run_git_mutating(&repo, &["bisect", "start", bad, good, "--"])?;
+    bisect_status(&repo)
+}
+
+/// Marks the checked out commit, or `rev` when given, and moves on to the next candidate.
+#[tauri::command]
+pub fn git_bisect_mark(repo_path: String, mark: BisectMark, rev: Option<String>) -> Result<GitBisectStatus, AppError> {
+    let repo = PathBuf::from(repo_path);
+    let rev = rev.as_deref().map(validate_revision).transpose()?;
+
+    let mut args = vec!["bisect", mark.as_str()];
+    args.extend(rev);
+    run_git_mutating(&repo, &args)?;
+    bisect_status(&repo)
+}
+
+#[tauri::command]
+pub fn git_bisect_status(repo_path: String) -> Result<GitBisectStatus, AppError> {
+    bisect_status(&PathBuf::from(repo_path))
+}
+
+/// Ends the bisect and returns to the branch it started from.
+#[tauri::command]
+pub fn git_bisect_reset(repo_path: String) -> Result<GitBisectStatus, AppError> {
+    let repo = PathBuf::from(repo_path);
+    run_git_mutating(&repo, &["bisect", "reset"])?;
+    bisect_status(&repo)
+}
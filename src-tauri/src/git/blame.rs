@@ -0,0 +1,114 @@
+use super::{log::validate_revision, run_git};
+use crate::error::AppError;
+use serde::Serialize;
+use std::{collections::HashMap, path::PathBuf};
+
+#[derive(Clone, Default)]
+struct BlameCommit {
+    author: String,
+    email: String,
+    author_time: i64,
+    author_tz: String,
+    summary: String,
+    previous_path: Option<String>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitBlameLine {
+    pub(super) line_number: usize,
+    pub(super) original_line: usize,
+    pub(super) hash: String,
+    pub(super) author: String,
+    pub(super) email: String,
+    /// Unix timestamp of the author date.
+    pub(super) author_time: i64,
+    pub(super) author_tz: String,
+    pub(super) summary: String,
+    pub(super) previous_path: Option<String>,
+    pub(super) content: String,
+}
+
+/// Parses `git blame --porcelain` output. Commit headers are only printed the first time a
+/// commit appears, so they are cached by hash.
+fn parse_blame(raw: &str) -> Vec<GitBlameLine> {
+    let mut commits: HashMap<String, BlameCommit> = HashMap::new();
+    let mut lines = Vec::new();
+    let mut current: Option<(String, usize, usize)> = None;
+
+    for line in raw.lines() {
+        if let Some(content) = line.strip_prefix('\t') {
+            if let Some((hash, original_line, line_number)) = current.take() {
+                let commit = commits.get(&hash).cloned().unwrap_or_default();
+                lines.push(GitBlameLine {
+                    line_number,
+                    original_line,
+                    hash,
+                    author: commit.author,
+                    email: commit.email,
+                    author_time: commit.author_time,
+                    author_tz: commit.author_tz,
+                    summary: commit.summary,
+                    previous_path: commit.previous_path,
+                    content: content.to_string(),
+                });
+            }
+            continue;
+        }
+
+        if current.is_none() {
+            let mut parts = line.split_whitespace();
+            let hash = parts.next().unwrap_or_default();
+            if hash.len() >= 40 && hash.chars().all(|ch| ch.is_ascii_hexdigit()) {
+                let original_line = parts.next().and_then(|value| value.parse().ok()).unwrap_or(0);
+                let line_number = parts.next().and_then(|value| value.parse().ok()).unwrap_or(0);
+                commits.entry(hash.to_string()).or_default();
+                current = Some((hash.to_string(), original_line, line_number));
+            }
+            continue;
+        }
+
+        let Some((hash, _, _)) = current.as_ref() else {
+            continue;
+        };
+        let Some(commit) = commits.get_mut(hash) else {
+            continue;
+        };
+
+        let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+        match key {
+            "author" => commit.author = value.to_string(),
+            "author-mail" => commit.email = value.trim_start_matches('<').trim_end_matches('>').to_string(),
+            "author-time" => commit.author_time = value.parse().unwrap_or(0),
+            "author-tz" => commit.author_tz = value.to_string(),
+            "summary" => commit.summary = value.to_string(),
+            "previous" => {
+                commit.previous_path = value.split_once(' ').map(|(_, path)| path.to_string());
+            }
+            _ => {}
+        }
+    }
+
+    lines
+}
+
+#[tauri::command]
+pub fn git_blame(repo_path: String, path: String, rev: Option<String>) -> Result<Vec<GitBlameLine>, AppError> {
+    let repo = PathBuf::from(repo_path);
+    let rev = rev.as_deref().map(validate_revision).transpose()?;
+
+    #[cfg(feature = "libgit2")]
+    if let Ok(lines) = super::libgit::blame(&repo, &path, rev) {
+        return Ok(lines);
+    }
+
+    let mut args = vec!["blame", "--porcelain"];
+    if let Some(rev) = rev {
+        args.push(rev);
+    }
+    args.push("--");
+    args.push(path.as_str());
+
+    let raw = run_git(&repo, &args)?;
+    Ok(parse_blame(&raw))
+}
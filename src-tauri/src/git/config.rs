@@ -0,0 +1,118 @@
+use super::{run_git, status_cache};
+use crate::error::AppError;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GitConfigScope {
+    /// The repository's `.git/config`.
+    Local,
+    /// The user's `~/.gitconfig`.
+    Global,
+}
+
+impl GitConfigScope {
+    fn as_arg(self) -> &'static str {
+        match self {
+            GitConfigScope::Local => "--local",
+            GitConfigScope::Global => "--global",
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitConfigEntry {
+    key: String,
+    value: String,
+    scope: GitConfigScope,
+}
+
+fn validate_key(key: &str) -> Result<&str, AppError> {
+    let trimmed = key.trim();
+    if trimmed.starts_with('-') || !trimmed.contains('.') || trimmed.ends_with('.') {
+        return Err(AppError::invalid(format!("invalid config key: {trimmed}")));
+    }
+    Ok(trimmed)
+}
+
+/// Value of `key` in `scope`, or as git resolves it across all scopes when `scope` is `None`.
+pub(crate) fn config_get(repo: &Path, key: &str, scope: Option<GitConfigScope>) -> Result<Option<String>, AppError> {
+    let key = validate_key(key)?;
+    let mut args = vec!["config"];
+    args.extend(scope.map(GitConfigScope::as_arg));
+    args.extend(["--get", key]);
+
+    match run_git(repo, &args) {
+        Ok(value) => Ok(Some(value.trim_end_matches(['\n', '\r']).to_string())),
+        // Exit code 1 means the key isn't set.
+        Err(error) if error.exit_code == Some(1) => Ok(None),
+        Err(error) => Err(error),
+    }
+}
+
+/// Sets `key` in `scope`, or unsets it when `value` is `None`.
+pub(crate) fn config_set(repo: &Path, key: &str, value: Option<&str>, scope: GitConfigScope) -> Result<(), AppError> {
+    let key = validate_key(key)?;
+    let result = match value {
+        Some(value) => run_git(repo, &["config", scope.as_arg(), "--", key, value]).map(|_| ()),
+        None => match run_git(repo, &["config", scope.as_arg(), "--unset", key]) {
+            // Unsetting a key that isn't there (exit code 5) is already done.
+            Err(error) if error.exit_code == Some(5) => Ok(()),
+            result => result.map(|_| ()),
+        },
+    };
+    // Settings like `status.showUntrackedFiles` change what status reports.
+    status_cache::invalidate(repo);
+    result
+}
+
+#[tauri::command]
+pub fn git_config_get(
+    repo_path: String,
+    key: String,
+    scope: Option<GitConfigScope>,
+) -> Result<Option<String>, AppError> {
+    config_get(&PathBuf::from(repo_path), &key, scope)
+}
+
+#[tauri::command]
+pub fn git_config_set(
+    repo_path: String,
+    key: String,
+    value: Option<String>,
+    scope: GitConfigScope,
+) -> Result<(), AppError> {
+    config_set(&PathBuf::from(repo_path), &key, value.as_deref(), scope)
+}
+
+/// Entries of one scope, or of global and local config in that order when `scope` is `None`.
+/// Multi-valued keys appear once per value.
+#[tauri::command]
+pub fn git_config_list(repo_path: String, scope: Option<GitConfigScope>) -> Result<Vec<GitConfigEntry>, AppError> {
+    let repo = PathBuf::from(repo_path);
+    let scopes = match scope {
+        Some(scope) => vec![scope],
+        None => vec![GitConfigScope::Global, GitConfigScope::Local],
+    };
+
+    let mut entries = Vec::new();
+    for scope in scopes {
+        // Records are `key\nvalue\0`; a missing config file lists nothing.
+        let raw = match run_git(&repo, &["config", scope.as_arg(), "--list", "-z"]) {
+            Ok(raw) => raw,
+            Err(_) if scope == GitConfigScope::Global => continue,
+            Err(error) => return Err(error),
+        };
+        entries.extend(raw.split('\0').filter(|record| !record.is_empty()).map(|record| {
+            let (key, value) = record.split_once('\n').unwrap_or((record, ""));
+            GitConfigEntry {
+                key: key.to_string(),
+                value: value.to_string(),
+                scope,
+            }
+        }));
+    }
+    Ok(entries)
+}
@@ -0,0 +1,127 @@
+use super::{git_commit, run_git};
+use crate::error::AppError;
+use serde::Deserialize;
+use std::{collections::HashSet, path::PathBuf};
+
+const COMMIT_TYPES: &[&str] = &[
+    "feat", "fix", "docs", "style", "refactor", "perf", "test", "build", "ci", "chore", "revert",
+];
+const MAX_HEADER_LENGTH: usize = 100;
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConventionalCommit {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    scope: Option<String>,
+    subject: String,
+    #[serde(default)]
+    body: Option<String>,
+    #[serde(default)]
+    breaking: bool,
+    /// Description for the `BREAKING CHANGE:` footer. Implies `breaking`.
+    #[serde(default)]
+    breaking_change: Option<String>,
+    /// Extra footer lines such as `Refs: #123`.
+    #[serde(default)]
+    footer: Option<String>,
+}
+
+fn non_empty(value: Option<&str>) -> Option<&str> {
+    value.map(str::trim).filter(|value| !value.is_empty())
+}
+
+/// Validates the fields and assembles `type(scope)!: subject`, the body and the footers.
+fn build_message(commit: &ConventionalCommit) -> Result<String, AppError> {
+    let kind = commit.kind.trim();
+    if !COMMIT_TYPES.contains(&kind) {
+        return Err(AppError::invalid(format!(
+            "unknown commit type: {kind} (expected one of {})",
+            COMMIT_TYPES.join(", ")
+        )));
+    }
+
+    let scope = non_empty(commit.scope.as_deref());
+    if let Some(scope) = scope {
+        if scope.contains(['(', ')', ':', '\n']) {
+            return Err(AppError::invalid(format!("invalid commit scope: {scope}")));
+        }
+    }
+
+    let subject = commit.subject.trim();
+    if subject.is_empty() {
+        return Err(AppError::invalid("commit subject is empty"));
+    }
+    if subject.contains('\n') {
+        return Err(AppError::invalid("commit subject must be a single line"));
+    }
+
+    let breaking_change = non_empty(commit.breaking_change.as_deref());
+    let breaking = commit.breaking || breaking_change.is_some();
+
+    let mut header = kind.to_string();
+    if let Some(scope) = scope {
+        header.push_str(&format!("({scope})"));
+    }
+    if breaking {
+        header.push('!');
+    }
+    header.push_str(": ");
+    header.push_str(subject);
+
+    if header.chars().count() > MAX_HEADER_LENGTH {
+        return Err(AppError::invalid(format!(
+            "commit header is longer than {MAX_HEADER_LENGTH} characters"
+        )));
+    }
+
+    let mut sections = vec![header];
+    if let Some(body) = non_empty(commit.body.as_deref()) {
+        sections.push(body.to_string());
+    }
+
+    let mut footers = Vec::new();
+    if let Some(description) = breaking_change {
+        footers.push(format!("BREAKING CHANGE: {description}"));
+    }
+    if let Some(footer) = non_empty(commit.footer.as_deref()) {
+        footers.push(footer.to_string());
+    }
+    if !footers.is_empty() {
+        sections.push(footers.join("\n"));
+    }
+
+    Ok(sections.join("\n\n"))
+}
+
+#[tauri::command]
+pub fn git_commit_structured(
+    repo_path: String,
+    commit: ConventionalCommit,
+    amend: Option<bool>,
+    sign: Option<bool>,
+    app: tauri::AppHandle,
+) -> Result<String, AppError> {
+    let message = build_message(&commit)?;
+    git_commit(repo_path, message, amend.unwrap_or(false), sign, None, None, None, None, app)
+}
+
+/// Distinct recent commit subjects, newest first, for message autocomplete.
+#[tauri::command]
+pub fn git_recent_commit_subjects(repo_path: String, limit: Option<usize>) -> Result<Vec<String>, AppError> {
+    let repo = PathBuf::from(repo_path);
+    let limit = limit.unwrap_or(50).clamp(1, 500);
+    // Read extra commits so duplicates don't shrink the result below the limit.
+    let count = (limit * 4).to_string();
+    let raw = run_git(&repo, &["log", "-n", count.as_str(), "--no-merges", "--format=%s"])?;
+
+    let mut seen = HashSet::new();
+    Ok(raw
+        .lines()
+        .map(str::trim)
+        .filter(|subject| !subject.is_empty() && seen.insert(subject.to_string()))
+        .take(limit)
+        .map(ToOwned::to_owned)
+        .collect())
+}
@@ -0,0 +1,391 @@
+use super::{
+    git_diff,
+    patch::parse_hunk_header,
+    resolve_git_root,
+    run_git,
+    show::{blob_bytes, blob_spec},
+    word_diff,
+};
+use crate::{error::AppError, images};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::Serialize;
+use std::path::PathBuf;
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffLineKind {
+    Context,
+    Added,
+    Removed,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenRange {
+    pub start: usize,
+    pub end: usize,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffLine {
+    pub kind: DiffLineKind,
+    pub content: String,
+    pub old_line: Option<usize>,
+    pub new_line: Option<usize>,
+    /// Set when the line is followed by `\ No newline at end of file`.
+    pub no_newline: bool,
+    /// Intraline changes, only filled in when word diff is requested.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub changes: Vec<TokenRange>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffHunk {
+    pub header: String,
+    pub old_start: usize,
+    pub old_lines: usize,
+    pub new_start: usize,
+    pub new_lines: usize,
+    pub lines: Vec<DiffLine>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffFile {
+    pub old_path: Option<String>,
+    pub new_path: Option<String>,
+    pub status: String,
+    pub binary: bool,
+    pub additions: usize,
+    pub deletions: usize,
+    pub hunks: Vec<DiffHunk>,
+}
+
+impl DiffFile {
+    fn new() -> Self {
+        Self {
+            old_path: None,
+            new_path: None,
+            status: "modified".to_string(),
+            binary: false,
+            additions: 0,
+            deletions: 0,
+            hunks: Vec::new(),
+        }
+    }
+}
+
+fn unquote_path(path: &str) -> String {
+    path.trim().trim_matches('"').to_string()
+}
+
+fn strip_side_prefix(path: &str) -> Option<String> {
+    let path = unquote_path(path);
+    if path == "/dev/null" {
+        return None;
+    }
+
+    Some(
+        path.strip_prefix("a/")
+            .or_else(|| path.strip_prefix("b/"))
+            .unwrap_or(&path)
+            .to_string(),
+    )
+}
+
+fn parse_range(range: &str) -> (usize, usize) {
+    let mut parts = range.split(',');
+    let start = parts.next().and_then(|value| value.parse().ok()).unwrap_or(0);
+    let count = parts.next().and_then(|value| value.parse().ok()).unwrap_or(1);
+    (start, count)
+}
+
+fn parse_hunk_counts(header: &str) -> (usize, usize) {
+    let ranges = header
+        .strip_prefix("@@ -")
+        .and_then(|rest| rest.split_once(" @@"))
+        .map(|(ranges, _)| ranges)
+        .unwrap_or_default();
+
+    let (old_range, new_range) = ranges.split_once(" +").unwrap_or((ranges, ""));
+    (parse_range(old_range).1, parse_range(new_range).1)
+}
+
+/// Parses unified diff output (as produced by `git diff`, `git show` or `git diff --no-index`)
+/// into files, hunks and lines.
+pub fn parse_diff(raw: &str) -> Vec<DiffFile> {
+    let mut files: Vec<DiffFile> = Vec::new();
+    let mut old_line = 0_usize;
+    let mut new_line = 0_usize;
+
+    for line in raw.lines() {
+        if line.starts_with("diff --git ") || line.starts_with("diff --cc ") {
+            let mut file = DiffFile::new();
+            if let Some((old, new)) = line.strip_prefix("diff --git ").and_then(|rest| rest.split_once(" b/")) {
+                file.old_path = strip_side_prefix(old);
+                file.new_path = Some(unquote_path(new));
+            }
+            files.push(file);
+            continue;
+        }
+
+        let Some(file) = files.last_mut() else {
+            continue;
+        };
+
+        if line.starts_with("@@") {
+            if let Some((old_start, new_start, _)) = parse_hunk_header(line) {
+                let (old_lines, new_lines) = parse_hunk_counts(line);
+                old_line = old_start;
+                new_line = new_start;
+                file.hunks.push(DiffHunk {
+                    header: line.to_string(),
+                    old_start,
+                    old_lines,
+                    new_start,
+                    new_lines,
+                    lines: Vec::new(),
+                });
+            }
+            continue;
+        }
+
+        if let Some(hunk) = file.hunks.last_mut() {
+            match line.as_bytes().first() {
+                Some(b'+') => {
+                    hunk.lines.push(DiffLine {
+                        kind: DiffLineKind::Added,
+                        content: line[1..].to_string(),
+                        old_line: None,
+                        new_line: Some(new_line),
+                        no_newline: false,
+                        changes: Vec::new(),
+                    });
+                    new_line += 1;
+                    file.additions += 1;
+                }
+                Some(b'-') => {
+                    hunk.lines.push(DiffLine {
+                        kind: DiffLineKind::Removed,
+                        content: line[1..].to_string(),
+                        old_line: Some(old_line),
+                        new_line: None,
+                        no_newline: false,
+                        changes: Vec::new(),
+                    });
+                    old_line += 1;
+                    file.deletions += 1;
+                }
+                Some(b'\\') => {
+                    if let Some(previous) = hunk.lines.last_mut() {
+                        previous.no_newline = true;
+                    }
+                }
+                _ => {
+                    hunk.lines.push(DiffLine {
+                        kind: DiffLineKind::Context,
+                        content: line.get(1..).unwrap_or_default().to_string(),
+                        old_line: Some(old_line),
+                        new_line: Some(new_line),
+                        no_newline: false,
+                        changes: Vec::new(),
+                    });
+                    old_line += 1;
+                    new_line += 1;
+                }
+            }
+            continue;
+        }
+
+        if line.starts_with("new file mode") {
+            file.status = "added".to_string();
+        } else if line.starts_with("deleted file mode") {
+            file.status = "deleted".to_string();
+        } else if let Some(path) = line.strip_prefix("rename from ") {
+            file.status = "renamed".to_string();
+            file.old_path = Some(unquote_path(path));
+        } else if let Some(path) = line.strip_prefix("rename to ") {
+            file.new_path = Some(unquote_path(path));
+        } else if let Some(path) = line.strip_prefix("copy from ") {
+            file.status = "copied".to_string();
+            file.old_path = Some(unquote_path(path));
+        } else if let Some(path) = line.strip_prefix("copy to ") {
+            file.new_path = Some(unquote_path(path));
+        } else if let Some(path) = line.strip_prefix("--- ") {
+            file.old_path = strip_side_prefix(path);
+        } else if let Some(path) = line.strip_prefix("+++ ") {
+            file.new_path = strip_side_prefix(path);
+        } else if line.starts_with("Binary files ") || line.starts_with("GIT binary patch") {
+            file.binary = true;
+        }
+    }
+
+    for file in files.iter_mut() {
+        if file.old_path.is_none() && file.status == "modified" {
+            file.status = "added".to_string();
+        } else if file.new_path.is_none() && file.status == "modified" {
+            file.status = "deleted".to_string();
+        }
+    }
+
+    files
+}
+
+#[tauri::command]
+pub fn git_diff_structured(
+    repo_path: String,
+    path: Option<String>,
+    staged: bool,
+    untracked: bool,
+    word_diff: Option<bool>,
+) -> Result<Vec<DiffFile>, AppError> {
+    let raw = match path {
+        Some(path) => git_diff(repo_path, path, staged, untracked)?,
+        None if untracked => return Err(AppError::invalid("untracked diff requires a path")),
+        None => {
+            let repo = PathBuf::from(repo_path);
+
+            #[cfg(feature = "libgit2")]
+            if let Ok(diff) = super::libgit::diff(&repo, None, staged, false) {
+                return Ok(finish(parse_diff(&diff), word_diff));
+            }
+
+            if staged {
+                run_git(&repo, &["diff", "--staged"])?
+            } else {
+                run_git(&repo, &["diff"])?
+            }
+        }
+    };
+
+    Ok(finish(parse_diff(&raw), word_diff))
+}
+
+fn finish(mut files: Vec<DiffFile>, word_diff: Option<bool>) -> Vec<DiffFile> {
+    if word_diff.unwrap_or(false) {
+        word_diff::annotate_word_changes(&mut files);
+    }
+    files
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffStat {
+    pub path: String,
+    /// Source path of a rename or copy.
+    pub orig_path: Option<String>,
+    pub additions: usize,
+    pub deletions: usize,
+    pub binary: bool,
+}
+
+/// Parses `git diff --numstat -z`. A rename or copy leaves the path field empty and is followed
+/// by two records holding the old and new path; binary files report `-` for both counts.
+fn parse_numstat(raw: &str) -> Vec<DiffStat> {
+    let mut stats = Vec::new();
+    let mut records = raw.split('\0');
+
+    while let Some(record) = records.next() {
+        let mut fields = record.splitn(3, '\t');
+        let (Some(additions), Some(deletions), Some(path)) = (fields.next(), fields.next(), fields.next()) else {
+            continue;
+        };
+
+        let (path, orig_path) = if path.is_empty() {
+            let orig_path = records.next().unwrap_or_default().to_string();
+            (records.next().unwrap_or_default().to_string(), Some(orig_path))
+        } else {
+            (path.to_string(), None)
+        };
+
+        stats.push(DiffStat {
+            path,
+            orig_path,
+            additions: additions.parse().unwrap_or(0),
+            deletions: deletions.parse().unwrap_or(0),
+            binary: additions == "-" && deletions == "-",
+        });
+    }
+
+    stats
+}
+
+/// Added and removed line counts per changed file, for badges in the change list without
+/// loading whole diffs.
+#[tauri::command]
+pub fn git_diff_stat(repo_path: String, staged: bool) -> Result<Vec<DiffStat>, AppError> {
+    let repo = PathBuf::from(repo_path);
+    let mut args = vec!["diff", "--numstat", "-z", "--find-renames"];
+    if staged {
+        args.push("--staged");
+    }
+
+    let raw = run_git(&repo, &args)?;
+    Ok(parse_numstat(&raw))
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffImage {
+    mime: String,
+    /// Base64 of the image file.
+    data: String,
+    size: usize,
+    width: Option<u32>,
+    height: Option<u32>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageDiff {
+    /// `None` when the image was added.
+    old: Option<DiffImage>,
+    /// `None` when the image was deleted.
+    new: Option<DiffImage>,
+}
+
+fn diff_image(path: &str, bytes: Vec<u8>) -> Option<DiffImage> {
+    let (mime, dimensions) = match images::sniff(&bytes) {
+        ("application/octet-stream", _) if path.to_lowercase().ends_with(".svg") => ("image/svg+xml", None),
+        ("application/octet-stream", _) => return None,
+        sniffed => sniffed,
+    };
+    Some(DiffImage {
+        mime: mime.to_string(),
+        size: bytes.len(),
+        width: dimensions.map(|(width, _)| width),
+        height: dimensions.map(|(_, height)| height),
+        data: STANDARD.encode(bytes),
+    })
+}
+
+/// Before and after versions of a changed image, for previews in place of "binary files
+/// differ". Staged changes compare `HEAD` with the index, unstaged ones the index with the work
+/// tree. `orig_path` is the old name of a renamed image.
+#[tauri::command]
+pub fn git_diff_image(
+    repo_path: String,
+    path: String,
+    staged: bool,
+    orig_path: Option<String>,
+) -> Result<ImageDiff, AppError> {
+    let repo = PathBuf::from(repo_path);
+    let old_path = orig_path.as_deref().unwrap_or(&path);
+
+    let old_spec = blob_spec(old_path, staged.then_some("HEAD"));
+    let old = blob_bytes(&repo, &old_spec)?.map(|(_, bytes)| bytes);
+    let new = if staged {
+        blob_bytes(&repo, &blob_spec(&path, None))?.map(|(_, bytes)| bytes)
+    } else {
+        std::fs::read(resolve_git_root(&repo)?.join(&path)).ok()
+    };
+
+    let old = old.and_then(|bytes| diff_image(old_path, bytes));
+    let new = new.and_then(|bytes| diff_image(&path, bytes));
+    if old.is_none() && new.is_none() {
+        return Err(AppError::invalid(format!("not an image: {path}")));
+    }
+    Ok(ImageDiff { old, new })
+}
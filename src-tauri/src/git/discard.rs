@@ -0,0 +1,66 @@
+use super::{merge::repo_relative_path, rev_parse, run_git_mutating};
+use crate::error::AppError;
+use std::path::PathBuf;
+
+/// Restores `path` in the worktree from the index. With `staged` the staged change is thrown away
+/// as well and the file is restored from HEAD.
+#[tauri::command]
+pub fn git_discard(repo_path: String, path: String, staged: Option<bool>) -> Result<(), AppError> {
+    let repo = PathBuf::from(repo_path);
+    repo_relative_path(&repo, &path)?;
+
+    if staged.unwrap_or(false) && rev_parse(&repo, "HEAD").is_some() {
+        return run_git_mutating(
+            &repo,
+            &["restore", "--source=HEAD", "--staged", "--worktree", "--", path.as_str()],
+        )
+        .map(|_| ());
+    }
+    run_git_mutating(&repo, &["restore", "--worktree", "--", path.as_str()]).map(|_| ())
+}
+
+/// Discards every unstaged change to tracked files. Untracked files are left to `git_clean`.
+#[tauri::command]
+pub fn git_discard_all(repo_path: String, staged: Option<bool>) -> Result<(), AppError> {
+    let repo = PathBuf::from(repo_path);
+
+    if staged.unwrap_or(false) && rev_parse(&repo, "HEAD").is_some() {
+        return run_git_mutating(&repo, &["restore", "--source=HEAD", "--staged", "--worktree", "--", ":/"]).map(|_| ());
+    }
+    run_git_mutating(&repo, &["restore", "--worktree", "--", ":/"]).map(|_| ())
+}
+
+/// Removes untracked files. With `dry_run` nothing is deleted and the returned list says what
+/// would be. Ignored files are never touched.
+#[tauri::command]
+pub fn git_clean(
+    repo_path: String,
+    paths: Vec<String>,
+    dry_run: bool,
+    directories: Option<bool>,
+) -> Result<Vec<String>, AppError> {
+    let repo = PathBuf::from(repo_path);
+    if paths.is_empty() {
+        return Err(AppError::invalid("no paths to clean"));
+    }
+    for path in &paths {
+        repo_relative_path(&repo, path)?;
+    }
+
+    let mut args = vec!["clean", if dry_run { "--dry-run" } else { "--force" }];
+    if directories.unwrap_or(false) {
+        args.push("-d");
+    }
+    args.push("--");
+    args.extend(paths.iter().map(String::as_str));
+
+    let raw = run_git_mutating(&repo, &args)?;
+    Ok(raw
+        .lines()
+        .filter_map(|line| {
+            line.strip_prefix("Would remove ")
+                .or_else(|| line.strip_prefix("Removing "))
+        })
+        .map(ToOwned::to_owned)
+        .collect())
+}
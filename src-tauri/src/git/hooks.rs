@@ -0,0 +1,199 @@
+use super::{git_path, resolve_git_root, run_git};
+use crate::error::{AppError, ErrorKind, HookFailure};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitCommitTemplate {
+    /// Path from `commit.template`.
+    template_path: Option<String>,
+    template: Option<String>,
+    /// The message after `prepare-commit-msg` ran over the template, when the hook is installed.
+    prepared: Option<String>,
+}
+
+/// Installed hook by name, honouring `core.hooksPath`. Samples and non-executable files don't
+/// run, so they are skipped the same way git skips them.
+pub(super) fn hook_path(repo: &Path, name: &str) -> Option<PathBuf> {
+    let path = git_path(repo, &format!("hooks/{name}")).ok()?;
+    let metadata = std::fs::metadata(&path).ok()?;
+    if !metadata.is_file() {
+        return None;
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if metadata.permissions().mode() & 0o111 == 0 {
+            return None;
+        }
+    }
+
+    Some(path)
+}
+
+fn hook_command(hook: &Path) -> Command {
+    // Hooks are shell scripts on Windows too; git runs them through its bundled `sh`.
+    if cfg!(windows) {
+        let mut command = Command::new("sh");
+        command.arg(hook);
+        command
+    } else {
+        Command::new(hook)
+    }
+}
+
+fn temp_file(prefix: &str) -> PathBuf {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_nanos())
+        .unwrap_or(0);
+    std::env::temp_dir().join(format!("{prefix}-{}-{nanos}", std::process::id()))
+}
+
+#[derive(Deserialize)]
+struct TraceEvent {
+    event: String,
+    child_id: Option<u64>,
+    child_class: Option<String>,
+    hook_name: Option<String>,
+    code: Option<i32>,
+}
+
+/// Trace of the hooks a git command runs. Git writes trace2 events for every child it starts,
+/// which tells a failing hook apart from git refusing the command itself.
+pub(super) struct HookTrace {
+    path: PathBuf,
+}
+
+impl HookTrace {
+    pub(super) fn new() -> Self {
+        Self {
+            path: temp_file("nlk-trace2"),
+        }
+    }
+
+    pub(super) fn env(&self) -> (&'static str, &Path) {
+        ("GIT_TRACE2_EVENT", &self.path)
+    }
+
+    /// Name of the first hook that exited with an error.
+    pub(super) fn failed_hook(&self) -> Option<String> {
+        let raw = std::fs::read_to_string(&self.path).ok()?;
+        let mut hooks = HashMap::new();
+
+        for line in raw.lines() {
+            let Ok(event) = serde_json::from_str::<TraceEvent>(line) else {
+                continue;
+            };
+            let Some(child_id) = event.child_id else {
+                continue;
+            };
+            match event.event.as_str() {
+                "child_start" if event.child_class.as_deref() == Some("hook") => {
+                    if let Some(name) = event.hook_name {
+                        hooks.insert(child_id, name);
+                    }
+                }
+                "child_exit" if event.code.is_some_and(|code| code != 0) => {
+                    if let Some(name) = hooks.remove(&child_id) {
+                        return Some(name);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        None
+    }
+}
+
+impl Drop for HookTrace {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Error for a command rejected by `hook`, carrying everything the hook printed.
+pub(super) fn hook_error(hook: String, stdout: &str, stderr: &str, exit_code: Option<i32>) -> AppError {
+    let output = [stdout.trim(), stderr.trim()]
+        .into_iter()
+        .filter(|output| !output.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n");
+    AppError {
+        detail: (!output.is_empty()).then(|| output.clone()),
+        exit_code,
+        hook: Some(HookFailure {
+            name: hook.clone(),
+            output,
+        }),
+        ..AppError::new(ErrorKind::GitHookRejected, format!("{hook} hook rejected the commit"))
+    }
+}
+
+/// Runs `prepare-commit-msg` over `message` the way `git commit` would before opening the
+/// editor, and returns the message it leaves behind.
+fn prepare_message(repo: &Path, hook: &Path, message: &str, source: Option<&str>) -> Result<String, AppError> {
+    let file = temp_file("nlk-commit-msg");
+    std::fs::write(&file, message).map_err(|error| format!("failed to write commit message: {error}"))?;
+
+    let mut command = hook_command(hook);
+    command.arg(&file).args(source).current_dir(resolve_git_root(repo)?);
+    let output = command.output();
+    let prepared = std::fs::read_to_string(&file);
+    let _ = std::fs::remove_file(&file);
+
+    let output = output.map_err(|error| format!("failed to run prepare-commit-msg: {error}"))?;
+    if !output.status.success() {
+        return Err(hook_error(
+            "prepare-commit-msg".to_string(),
+            &String::from_utf8_lossy(&output.stdout),
+            &String::from_utf8_lossy(&output.stderr),
+            output.status.code(),
+        ));
+    }
+    prepared.map_err(|error| AppError::from(format!("failed to read commit message: {error}")))
+}
+
+/// Starting text for the commit message box: the `commit.template` file and what
+/// `prepare-commit-msg` makes of it.
+#[tauri::command]
+pub fn git_commit_template(repo_path: String) -> Result<GitCommitTemplate, AppError> {
+    let repo = PathBuf::from(repo_path);
+    let template_path = run_git(&repo, &["config", "--path", "--get", "commit.template"])
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty());
+
+    let template = match &template_path {
+        Some(path) => {
+            let path = Path::new(path);
+            let path = if path.is_absolute() { path.to_path_buf() } else { repo.join(path) };
+            Some(
+                std::fs::read_to_string(&path)
+                    .map_err(|error| format!("failed to read commit template {}: {error}", path.display()))?,
+            )
+        }
+        None => None,
+    };
+
+    let prepared = match hook_path(&repo, "prepare-commit-msg") {
+        Some(hook) => {
+            let source = template.is_some().then_some("template");
+            Some(prepare_message(&repo, &hook, template.as_deref().unwrap_or_default(), source)?)
+        }
+        None => None,
+    };
+
+    Ok(GitCommitTemplate {
+        template_path,
+        template,
+        prepared,
+    })
+}
@@ -0,0 +1,195 @@
+use super::{
+    config::{config_get, config_set, GitConfigScope},
+    run_git,
+    status_cache,
+};
+use crate::error::AppError;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::Manager;
+
+/// A named author, e.g. "Work" or "Personal", that can be assigned to repositories.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitIdentity {
+    #[serde(default)]
+    pub id: String,
+    /// Label shown in the UI.
+    pub label: String,
+    pub name: String,
+    pub email: String,
+    #[serde(default)]
+    pub signing_key: Option<String>,
+    /// Remote URL fragments such as `github.com/acme/` or path prefixes such as `~/work/` of the
+    /// repositories this identity belongs to.
+    #[serde(default)]
+    pub patterns: Vec<String>,
+}
+
+/// The repository commits as someone other than the identity its remote or path calls for.
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IdentityMismatch {
+    expected_id: String,
+    expected_label: String,
+    expected_email: String,
+    /// Effective `user.email`, `None` when unset.
+    actual_email: Option<String>,
+}
+
+fn identities_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_config_dir()
+        .map_err(|error| format!("failed to resolve config dir: {error}"))?;
+    Ok(dir.join("git-identities.json"))
+}
+
+fn load_identities(app: &tauri::AppHandle) -> Result<Vec<GitIdentity>, String> {
+    let path = identities_path(app)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let raw = std::fs::read_to_string(&path).map_err(|error| format!("failed to read identities: {error}"))?;
+    serde_json::from_str(&raw).map_err(|error| format!("failed to parse identities: {error}"))
+}
+
+fn store_identities(app: &tauri::AppHandle, identities: &[GitIdentity]) -> Result<(), String> {
+    let path = identities_path(app)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|error| format!("failed to create config dir: {error}"))?;
+    }
+
+    let raw =
+        serde_json::to_string_pretty(identities).map_err(|error| format!("failed to encode identities: {error}"))?;
+    std::fs::write(&path, raw).map_err(|error| format!("failed to write identities: {error}"))?;
+    // Statuses carry the mismatch check, which depends on the identities.
+    status_cache::clear();
+    Ok(())
+}
+
+fn generate_identity_id() -> String {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_nanos())
+        .unwrap_or(0);
+    format!("identity-{nanos:x}")
+}
+
+fn expand_home(pattern: &str) -> String {
+    match pattern.strip_prefix("~/") {
+        Some(rest) => std::env::var("HOME")
+            .map(|home| Path::new(&home).join(rest).to_string_lossy().to_string())
+            .unwrap_or_else(|_| pattern.to_string()),
+        None => pattern.to_string(),
+    }
+}
+
+fn remote_urls(repo: &Path) -> Vec<String> {
+    run_git(repo, &["config", "--get-regexp", r"^remote\..*\.url$"])
+        .map(|raw| {
+            raw.lines()
+                .filter_map(|line| line.split_once(' '))
+                .map(|(_, url)| url.trim().to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Checks the repository's author against the first identity whose patterns match one of its
+/// remotes or its path. Repositories no identity claims are never reported.
+pub(crate) fn check_mismatch(app: &tauri::AppHandle, repo: &Path) -> Option<IdentityMismatch> {
+    let identities = load_identities(app).ok()?;
+    if identities.iter().all(|identity| identity.patterns.is_empty()) {
+        return None;
+    }
+
+    let urls = remote_urls(repo);
+    let repo_path = repo.to_string_lossy();
+    let expected = identities.into_iter().find(|identity| {
+        identity.patterns.iter().any(|pattern| {
+            let pattern = pattern.trim();
+            !pattern.is_empty()
+                && (urls.iter().any(|url| url.contains(pattern)) || repo_path.starts_with(&expand_home(pattern)))
+        })
+    })?;
+
+    let actual_email = config_get(repo, "user.email", None).ok().flatten();
+    if actual_email
+        .as_deref()
+        .is_some_and(|email| email.eq_ignore_ascii_case(&expected.email))
+    {
+        return None;
+    }
+
+    Some(IdentityMismatch {
+        expected_id: expected.id,
+        expected_label: expected.label,
+        expected_email: expected.email,
+        actual_email,
+    })
+}
+
+#[tauri::command]
+pub fn list_identities(app: tauri::AppHandle) -> Result<Vec<GitIdentity>, AppError> {
+    Ok(load_identities(&app)?)
+}
+
+#[tauri::command]
+pub fn save_identity(identity: GitIdentity, app: tauri::AppHandle) -> Result<GitIdentity, AppError> {
+    let mut identity = identity;
+    identity.label = identity.label.trim().to_string();
+    identity.name = identity.name.trim().to_string();
+    identity.email = identity.email.trim().to_string();
+    identity.signing_key = identity
+        .signing_key
+        .map(|key| key.trim().to_string())
+        .filter(|key| !key.is_empty());
+
+    if identity.label.is_empty() {
+        return Err(AppError::invalid("identity label is empty"));
+    }
+    if identity.name.is_empty() || identity.email.is_empty() {
+        return Err(AppError::invalid("identity needs a name and an email"));
+    }
+    if identity.id.trim().is_empty() {
+        identity.id = generate_identity_id();
+    }
+
+    let mut identities = load_identities(&app)?;
+    match identities.iter_mut().find(|existing| existing.id == identity.id) {
+        Some(existing) => *existing = identity.clone(),
+        None => identities.push(identity.clone()),
+    }
+    store_identities(&app, &identities)?;
+
+    Ok(identity)
+}
+
+#[tauri::command]
+pub fn delete_identity(id: String, app: tauri::AppHandle) -> Result<(), AppError> {
+    let mut identities = load_identities(&app)?;
+    let before = identities.len();
+    identities.retain(|identity| identity.id != id);
+
+    if identities.len() == before {
+        return Err(AppError::invalid(format!("identity not found: {id}")));
+    }
+
+    Ok(store_identities(&app, &identities)?)
+}
+
+/// Writes an identity's name, email and signing key to the repository's local config.
+#[tauri::command]
+pub fn assign_identity(repo_path: String, identity_id: String, app: tauri::AppHandle) -> Result<(), AppError> {
+    let repo = PathBuf::from(repo_path);
+    let identity = load_identities(&app)?
+        .into_iter()
+        .find(|identity| identity.id == identity_id)
+        .ok_or_else(|| AppError::invalid(format!("identity not found: {identity_id}")))?;
+
+    config_set(&repo, "user.name", Some(&identity.name), GitConfigScope::Local)?;
+    config_set(&repo, "user.email", Some(&identity.email), GitConfigScope::Local)?;
+    config_set(&repo, "user.signingkey", identity.signing_key.as_deref(), GitConfigScope::Local)
+}
@@ -0,0 +1,62 @@
+use super::{resolve_git_root, run_git_mutating, validate_branch_name};
+use crate::error::AppError;
+use std::path::PathBuf;
+
+/// Starter `.gitignore` files by template name.
+const GITIGNORE_TEMPLATES: [(&str, &str); 5] = [
+    ("rust", "/target/\n"),
+    ("node", "node_modules/\ndist/\n.env\nnpm-debug.log*\nyarn-error.log*\n"),
+    ("python", "__pycache__/\n*.py[cod]\n.venv/\nvenv/\n.env\ndist/\nbuild/\n*.egg-info/\n"),
+    ("go", "/bin/\n*.test\n*.out\n"),
+    ("java", "target/\nbuild/\n.gradle/\n*.class\n*.jar\n"),
+];
+
+/// Editor and OS clutter added to every template.
+const GITIGNORE_COMMON: &str = ".DS_Store\nThumbs.db\n.idea/\n.vscode/\n";
+
+/// Turns `path` into a repository. `gitignore_template` names one of `GITIGNORE_TEMPLATES` to
+/// seed `.gitignore` with; an existing `.gitignore` is left alone. Returns the repository root.
+#[tauri::command]
+pub fn git_init(
+    path: String,
+    initial_branch: Option<String>,
+    gitignore_template: Option<String>,
+) -> Result<String, AppError> {
+    let path = PathBuf::from(path);
+    if !path.is_dir() {
+        return Err(AppError::invalid(format!("folder does not exist: {}", path.display())));
+    }
+    if path.join(".git").exists() {
+        return Err(AppError::invalid(format!("already a git repository: {}", path.display())));
+    }
+
+    let gitignore = gitignore_template
+        .map(|name| {
+            GITIGNORE_TEMPLATES
+                .iter()
+                .find(|(template, _)| template.eq_ignore_ascii_case(name.trim()))
+                .map(|(_, content)| format!("{content}{GITIGNORE_COMMON}"))
+                .ok_or_else(|| AppError::invalid(format!("unknown .gitignore template: {name}")))
+        })
+        .transpose()?;
+
+    let branch = initial_branch
+        .filter(|branch| !branch.trim().is_empty())
+        .map(|branch| validate_branch_name(&path, &branch))
+        .transpose()?;
+    let branch_arg = branch.map(|branch| format!("--initial-branch={branch}"));
+
+    let mut args = vec!["init"];
+    args.extend(branch_arg.as_deref());
+    run_git_mutating(&path, &args)?;
+
+    if let Some(gitignore) = gitignore {
+        let gitignore_path = path.join(".gitignore");
+        if !gitignore_path.exists() {
+            std::fs::write(&gitignore_path, gitignore)
+                .map_err(|error| format!("failed to write .gitignore: {error}"))?;
+        }
+    }
+
+    Ok(resolve_git_root(&path)?.to_string_lossy().to_string())
+}
@@ -0,0 +1,326 @@
+//! In-process implementations of the hot read paths using libgit2. Callers fall back to the git
+//! CLI whenever these return an error, so anything unusual can simply bail out.
+
+use super::{blame::GitBlameLine, GitChange, GitChangeKind, GitStatusOptions, GitStatusResponse, GitUntrackedMode};
+use git2::{BlameOptions, BranchType, DiffFormat, DiffOptions, Oid, Repository, Status, StatusOptions};
+use std::{collections::HashMap, path::Path};
+
+fn error_string(error: git2::Error) -> String {
+    error.message().to_string()
+}
+
+fn open(path: &Path) -> Result<Repository, String> {
+    Repository::discover(path).map_err(error_string)
+}
+
+fn index_code(status: Status) -> char {
+    if status.is_index_new() {
+        'A'
+    } else if status.is_index_modified() {
+        'M'
+    } else if status.is_index_deleted() {
+        'D'
+    } else if status.is_index_renamed() {
+        'R'
+    } else if status.is_index_typechange() {
+        'T'
+    } else {
+        ' '
+    }
+}
+
+fn worktree_code(status: Status) -> char {
+    if status.is_wt_modified() {
+        'M'
+    } else if status.is_wt_deleted() {
+        'D'
+    } else if status.is_wt_renamed() {
+        'R'
+    } else if status.is_wt_typechange() {
+        'T'
+    } else {
+        ' '
+    }
+}
+
+struct BranchState {
+    name: String,
+    upstream: Option<String>,
+    upstream_gone: bool,
+    ahead: usize,
+    behind: usize,
+}
+
+impl BranchState {
+    fn untracked(name: String) -> Self {
+        Self {
+            name,
+            upstream: None,
+            upstream_gone: false,
+            ahead: 0,
+            behind: 0,
+        }
+    }
+}
+
+fn branch_state(repo: &Repository) -> BranchState {
+    let head = match repo.head() {
+        Ok(head) => head,
+        Err(_) => {
+            // Unborn branch: HEAD still names the branch even though it has no commit.
+            let branch = repo
+                .find_reference("HEAD")
+                .ok()
+                .and_then(|head| head.symbolic_target().map(ToOwned::to_owned))
+                .map(|target| target.trim_start_matches("refs/heads/").to_string())
+                .unwrap_or_else(|| "unknown".to_string());
+            return BranchState::untracked(branch);
+        }
+    };
+
+    if !head.is_branch() {
+        return BranchState::untracked("HEAD (no branch)".to_string());
+    }
+
+    let name = head.shorthand().unwrap_or("unknown").to_string();
+    // The configured upstream name exists even when its ref has been deleted on the remote.
+    let configured = head
+        .name()
+        .and_then(|refname| repo.branch_upstream_name(refname).ok())
+        .and_then(|upstream| upstream.as_str().map(|name| name.trim_start_matches("refs/remotes/").to_string()));
+    let upstream_target = repo
+        .find_branch(&name, BranchType::Local)
+        .ok()
+        .and_then(|branch| branch.upstream().ok())
+        .and_then(|upstream| upstream.get().target());
+
+    let (ahead, behind) = upstream_target
+        .zip(head.target())
+        .and_then(|(upstream, local)| repo.graph_ahead_behind(local, upstream).ok())
+        .unwrap_or((0, 0));
+
+    BranchState {
+        upstream_gone: configured.is_some() && upstream_target.is_none(),
+        upstream: configured,
+        name,
+        ahead,
+        behind,
+    }
+}
+
+pub fn status(
+    explicit_path: Option<&str>,
+    status_options: &GitStatusOptions,
+) -> Result<GitStatusResponse, String> {
+    let start = match explicit_path {
+        Some(path) => Path::new(path).to_path_buf(),
+        None => std::env::current_dir().map_err(|error| format!("cwd error: {error}"))?,
+    };
+    let repo = open(&start)?;
+    let root = repo.workdir().ok_or_else(|| "bare repository".to_string())?;
+
+    let mut options = StatusOptions::new();
+    options
+        .include_untracked(status_options.untracked != GitUntrackedMode::No)
+        .recurse_untracked_dirs(status_options.untracked == GitUntrackedMode::All)
+        .include_ignored(status_options.include_ignored)
+        .recurse_ignored_dirs(false)
+        .renames_head_to_index(true)
+        .renames_index_to_workdir(true);
+    if let Some(pathspec) = &status_options.pathspec {
+        options.pathspec(pathspec);
+    }
+    let statuses = repo.statuses(Some(&mut options)).map_err(error_string)?;
+
+    let mut changes = Vec::new();
+    let mut ignored = Vec::new();
+    for entry in statuses.iter() {
+        let status = entry.status();
+        if status.is_ignored() {
+            ignored.extend(entry.path().map(ToOwned::to_owned));
+            continue;
+        }
+
+        let code = if status.is_conflicted() {
+            "UU".to_string()
+        } else if status.is_wt_new() && !status.intersects(Status::INDEX_NEW | Status::INDEX_MODIFIED) {
+            "??".to_string()
+        } else {
+            format!("{}{}", index_code(status), worktree_code(status))
+        };
+
+        let renamed = entry
+            .head_to_index()
+            .filter(|_| status.is_index_renamed())
+            .or_else(|| entry.index_to_workdir().filter(|_| status.is_wt_renamed()));
+        let path = renamed
+            .as_ref()
+            .and_then(|delta| delta.new_file().path().map(|path| path.to_string_lossy().to_string()))
+            .or_else(|| entry.path().map(ToOwned::to_owned))
+            .unwrap_or_default();
+        let orig_path = renamed
+            .as_ref()
+            .and_then(|delta| delta.old_file().path().map(|path| path.to_string_lossy().to_string()));
+
+        let kind = if status.is_conflicted() {
+            GitChangeKind::Conflicted
+        } else if code == "??" {
+            GitChangeKind::Untracked
+        } else if renamed.is_some() {
+            GitChangeKind::Renamed
+        } else {
+            GitChangeKind::Ordinary
+        };
+
+        let x = code.chars().next().unwrap_or(' ');
+        let y = code.chars().nth(1).unwrap_or(' ');
+        let conflicted = kind == GitChangeKind::Conflicted;
+        let directory = code == "??" && path.ends_with('/');
+        changes.push(GitChange {
+            path,
+            kind,
+            staged: !conflicted && x != ' ' && x != '?',
+            unstaged: conflicted || y != ' ',
+            untracked: code == "??",
+            orig_path,
+            similarity: None,
+            directory,
+            status: code,
+            submodule: None,
+        });
+    }
+
+    let branch = branch_state(&repo);
+    Ok(GitStatusResponse {
+        repo_path: root.to_string_lossy().trim_end_matches(['/', '\\']).to_string(),
+        branch: branch.name,
+        upstream: branch.upstream,
+        upstream_gone: branch.upstream_gone,
+        ahead: branch.ahead,
+        behind: branch.behind,
+        changes,
+        ignored,
+        identity_mismatch: None,
+    })
+}
+
+/// Renders a diff as unified patch text, matching what `git diff` prints.
+pub fn diff(repo: &Path, path: Option<&str>, staged: bool, untracked: bool) -> Result<String, String> {
+    let repo = open(repo)?;
+    let mut options = DiffOptions::new();
+    if let Some(path) = path {
+        options.pathspec(path).disable_pathspec_match(true);
+    }
+    if untracked {
+        options
+            .include_untracked(true)
+            .recurse_untracked_dirs(true)
+            .show_untracked_content(true);
+    }
+
+    let diff = if staged {
+        let head = repo.head().ok().and_then(|head| head.peel_to_tree().ok());
+        repo.diff_tree_to_index(head.as_ref(), None, Some(&mut options))
+    } else {
+        repo.diff_index_to_workdir(None, Some(&mut options))
+    }
+    .map_err(error_string)?;
+
+    let mut patch = Vec::new();
+    diff.print(DiffFormat::Patch, |_, _, line| {
+        if matches!(line.origin(), '+' | '-' | ' ') {
+            patch.push(line.origin() as u8);
+        }
+        patch.extend_from_slice(line.content());
+        true
+    })
+    .map_err(error_string)?;
+
+    Ok(String::from_utf8_lossy(&patch).to_string())
+}
+
+fn format_offset(minutes: i32) -> String {
+    let sign = if minutes < 0 { '-' } else { '+' };
+    let minutes = minutes.abs();
+    format!("{sign}{:02}{:02}", minutes / 60, minutes % 60)
+}
+
+pub fn blame(repo: &Path, path: &str, rev: Option<&str>) -> Result<Vec<GitBlameLine>, String> {
+    let repo = open(repo)?;
+    let mut options = BlameOptions::new();
+    let committed;
+
+    let (blame, content) = match rev {
+        Some(rev) => {
+            let commit = repo
+                .revparse_single(rev)
+                .and_then(|object| object.peel_to_commit())
+                .map_err(error_string)?;
+            options.newest_commit(commit.id());
+            let blob = commit
+                .tree()
+                .and_then(|tree| tree.get_path(Path::new(path)))
+                .and_then(|entry| entry.to_object(&repo))
+                .and_then(|object| object.peel_to_blob())
+                .map_err(error_string)?;
+            let content = String::from_utf8_lossy(blob.content()).to_string();
+            (repo.blame_file(Path::new(path), Some(&mut options)).map_err(error_string)?, content)
+        }
+        None => {
+            // Without a revision git blames the working tree file, including uncommitted lines.
+            let root = repo.workdir().ok_or_else(|| "bare repository".to_string())?;
+            let content =
+                std::fs::read(root.join(path)).map_err(|error| format!("failed to read {path}: {error}"))?;
+            committed = repo.blame_file(Path::new(path), Some(&mut options)).map_err(error_string)?;
+            let blame = committed.blame_buffer(&content).map_err(error_string)?;
+            (blame, String::from_utf8_lossy(&content).to_string())
+        }
+    };
+
+    let mut summaries: HashMap<Oid, String> = HashMap::new();
+    let mut lines = Vec::new();
+
+    for (index, content) in content.lines().enumerate() {
+        let line_number = index + 1;
+        let Some(hunk) = blame.get_line(line_number) else {
+            continue;
+        };
+
+        let hash = hunk.final_commit_id();
+        let committed = !hash.is_zero();
+        let summary = summaries
+            .entry(hash)
+            .or_insert_with(|| {
+                repo.find_commit(hash)
+                    .ok()
+                    .and_then(|commit| commit.summary().map(ToOwned::to_owned))
+                    .unwrap_or_default()
+            })
+            .clone();
+
+        let signature = hunk.final_signature();
+        let previous_path = hunk
+            .path()
+            .map(|original| original.to_string_lossy().to_string())
+            .filter(|original| original != path);
+
+        lines.push(GitBlameLine {
+            line_number,
+            original_line: hunk.orig_start_line() + (line_number - hunk.final_start_line()),
+            hash: hash.to_string(),
+            author: if committed {
+                signature.name().unwrap_or_default().to_string()
+            } else {
+                "Not Committed Yet".to_string()
+            },
+            email: signature.email().unwrap_or_default().to_string(),
+            author_time: signature.when().seconds(),
+            author_tz: format_offset(signature.when().offset_minutes()),
+            summary,
+            previous_path,
+            content: content.to_string(),
+        });
+    }
+
+    Ok(lines)
+}
@@ -0,0 +1,271 @@
+use super::{
+    diff::{parse_diff, DiffFile},
+    run_git,
+    word_diff::annotate_word_changes,
+};
+use crate::error::AppError;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+const FIELD_SEPARATOR: char = '\u{1f}';
+const RECORD_SEPARATOR: char = '\u{1e}';
+pub(super) const LOG_FORMAT: &str = "--format=%H%x1f%an%x1f%ae%x1f%aI%x1f%s%x1f%b%x1f%P%x1f%D%x1e";
+/// Same fields as `LOG_FORMAT`, but records start with the separator so that per-commit
+/// `--name-only` output ends up in a trailing field.
+const FILE_LOG_FORMAT: &str = "--format=%x1e%H%x1f%an%x1f%ae%x1f%aI%x1f%s%x1f%b%x1f%P%x1f%D%x1f";
+
+const DEFAULT_LOG_LIMIT: usize = 200;
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitCommit {
+    hash: String,
+    author: String,
+    email: String,
+    date: String,
+    subject: String,
+    body: String,
+    parents: Vec<String>,
+    refs: Vec<String>,
+}
+
+fn parse_commit(fields: &[&str]) -> Option<GitCommit> {
+    if fields.len() < 8 {
+        return None;
+    }
+
+    Some(GitCommit {
+        hash: fields[0].trim().to_string(),
+        author: fields[1].to_string(),
+        email: fields[2].to_string(),
+        date: fields[3].to_string(),
+        subject: fields[4].to_string(),
+        body: fields[5].trim_end().to_string(),
+        parents: fields[6].split_whitespace().map(ToOwned::to_owned).collect(),
+        refs: fields[7]
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(ToOwned::to_owned)
+            .collect(),
+    })
+}
+
+/// Parses output produced with `LOG_FORMAT`.
+pub(super) fn parse_log(raw: &str) -> Vec<GitCommit> {
+    raw.split(RECORD_SEPARATOR)
+        .filter_map(|record| {
+            let record = record.trim_start_matches(['\n', '\r']);
+            parse_commit(&record.split(FIELD_SEPARATOR).collect::<Vec<&str>>())
+        })
+        .collect()
+}
+
+pub(super) fn validate_revision(revision: &str) -> Result<&str, AppError> {
+    let trimmed = revision.trim();
+    if trimmed.is_empty() {
+        return Err(AppError::invalid("revision is empty"));
+    }
+    if trimmed.starts_with('-') {
+        return Err(AppError::invalid(format!("invalid revision: {trimmed}")));
+    }
+    Ok(trimmed)
+}
+
+#[tauri::command]
+pub fn git_log(
+    repo_path: String,
+    limit: Option<usize>,
+    skip: Option<usize>,
+    branch: Option<String>,
+) -> Result<Vec<GitCommit>, AppError> {
+    let repo = PathBuf::from(repo_path);
+    let limit = limit.unwrap_or(DEFAULT_LOG_LIMIT).to_string();
+    let skip = skip.unwrap_or(0).to_string();
+
+    let mut args = vec!["log", LOG_FORMAT, "-n", limit.as_str(), "--skip", skip.as_str()];
+    if let Some(branch) = branch.as_deref() {
+        args.push(validate_revision(branch)?);
+    }
+    args.push("--");
+
+    let raw = run_git(&repo, &args)?;
+    Ok(parse_log(&raw))
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitFileCommit {
+    #[serde(flatten)]
+    commit: GitCommit,
+    /// Path of the file in this commit, which differs from the requested path across renames.
+    path: String,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitShowResponse {
+    commit: GitCommit,
+    files: Vec<DiffFile>,
+    additions: usize,
+    deletions: usize,
+}
+
+#[tauri::command]
+pub fn git_show(repo_path: String, commit: String, word_diff: Option<bool>) -> Result<GitShowResponse, AppError> {
+    let repo = PathBuf::from(repo_path);
+    let revision = validate_revision(&commit)?;
+
+    let raw = run_git(&repo, &["log", "-1", LOG_FORMAT, revision, "--"])?;
+    let commit = parse_log(&raw)
+        .into_iter()
+        .next()
+        .ok_or_else(|| format!("commit not found: {revision}"))?;
+
+    let patch = run_git(
+        &repo,
+        &[
+            "show",
+            "--format=",
+            "--patch",
+            "--find-renames",
+            "--diff-merges=first-parent",
+            revision,
+            "--",
+        ],
+    )?;
+
+    let mut files = parse_diff(&patch);
+    if word_diff.unwrap_or(false) {
+        annotate_word_changes(&mut files);
+    }
+
+    let additions = files.iter().map(|file| file.additions).sum();
+    let deletions = files.iter().map(|file| file.deletions).sum();
+
+    Ok(GitShowResponse {
+        commit,
+        files,
+        additions,
+        deletions,
+    })
+}
+
+#[tauri::command]
+pub fn git_file_log(
+    repo_path: String,
+    path: String,
+    limit: Option<usize>,
+    skip: Option<usize>,
+) -> Result<Vec<GitFileCommit>, AppError> {
+    let repo = PathBuf::from(repo_path);
+    let limit = limit.unwrap_or(DEFAULT_LOG_LIMIT).to_string();
+    let skip = skip.unwrap_or(0).to_string();
+
+    let raw = run_git(
+        &repo,
+        &[
+            "log",
+            "--follow",
+            "--name-only",
+            FILE_LOG_FORMAT,
+            "-n",
+            limit.as_str(),
+            "--skip",
+            skip.as_str(),
+            "--",
+            path.as_str(),
+        ],
+    )?;
+
+    let commits = raw
+        .split(RECORD_SEPARATOR)
+        .filter_map(|record| {
+            let fields = record.splitn(9, FIELD_SEPARATOR).collect::<Vec<&str>>();
+            let commit = parse_commit(&fields)?;
+            let file_path = fields
+                .get(8)
+                .and_then(|names| names.lines().map(str::trim).find(|name| !name.is_empty()))
+                .unwrap_or(path.as_str())
+                .to_string();
+
+            Some(GitFileCommit {
+                commit,
+                path: file_path,
+            })
+        })
+        .collect();
+
+    Ok(commits)
+}
+
+#[derive(Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum GitSearchMode {
+    /// Commit messages, case-insensitively (`--grep`).
+    Message,
+    /// Commits that change how often the text occurs (`-S`).
+    Pickaxe,
+    /// Commits whose diff adds or removes lines matching a regex (`-G`).
+    Regex,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitSearchResult {
+    #[serde(flatten)]
+    commit: GitCommit,
+    /// Changed files; for content searches only the ones that matched.
+    files: Vec<String>,
+}
+
+/// Searches history on the current branch, or on `branch`, newest first.
+#[tauri::command]
+pub fn git_search(
+    repo_path: String,
+    query: String,
+    mode: GitSearchMode,
+    branch: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<GitSearchResult>, AppError> {
+    let repo = PathBuf::from(repo_path);
+    if query.is_empty() {
+        return Err(AppError::invalid("search query is empty"));
+    }
+    let limit = limit.unwrap_or(DEFAULT_LOG_LIMIT).to_string();
+    let filter = match mode {
+        GitSearchMode::Message => format!("--grep={query}"),
+        GitSearchMode::Pickaxe => format!("-S{query}"),
+        GitSearchMode::Regex => format!("-G{query}"),
+    };
+
+    let mut args = vec!["log", "--name-only", FILE_LOG_FORMAT, "-n", limit.as_str(), filter.as_str()];
+    if matches!(mode, GitSearchMode::Message) {
+        args.push("--regexp-ignore-case");
+    }
+    if let Some(branch) = branch.as_deref() {
+        args.push(validate_revision(branch)?);
+    }
+    args.push("--");
+
+    let raw = run_git(&repo, &args)?;
+    Ok(raw
+        .split(RECORD_SEPARATOR)
+        .filter_map(|record| {
+            let fields = record.splitn(9, FIELD_SEPARATOR).collect::<Vec<&str>>();
+            let commit = parse_commit(&fields)?;
+            let files = fields
+                .get(8)
+                .map(|names| {
+                    names
+                        .lines()
+                        .map(str::trim)
+                        .filter(|name| !name.is_empty())
+                        .map(ToOwned::to_owned)
+                        .collect()
+                })
+                .unwrap_or_default();
+            Some(GitSearchResult { commit, files })
+        })
+        .collect())
+}
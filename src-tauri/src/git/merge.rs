@@ -0,0 +1,103 @@
+use super::{conflicted_paths, log::validate_revision, run_git, run_git_mutating};
+use crate::error::AppError;
+use serde::Serialize;
+use std::path::{Component, Path, PathBuf};
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitMergeResponse {
+    merged: bool,
+    message: String,
+    conflicts: Vec<String>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitConflict {
+    path: String,
+    base: Option<String>,
+    ours: Option<String>,
+    theirs: Option<String>,
+}
+
+fn index_stage(repo: &Path, stage: u8, path: &str) -> Option<String> {
+    let spec = format!(":{stage}:{path}");
+    run_git(repo, &["show", spec.as_str()]).ok()
+}
+
+/// Rejects paths that would escape the repository when joined onto it.
+pub(super) fn repo_relative_path(repo: &Path, path: &str) -> Result<PathBuf, AppError> {
+    let relative = Path::new(path);
+    let escapes = relative
+        .components()
+        .any(|component| !matches!(component, Component::Normal(_) | Component::CurDir));
+
+    if path.trim().is_empty() || escapes {
+        return Err(AppError::invalid(format!("invalid repository path: {path}")));
+    }
+
+    Ok(repo.join(relative))
+}
+
+#[tauri::command]
+pub fn git_merge(repo_path: String, branch: String, no_ff: bool) -> Result<GitMergeResponse, AppError> {
+    let repo = PathBuf::from(repo_path);
+    let branch = validate_revision(&branch)?;
+
+    let mut args = vec!["merge", "--no-edit"];
+    if no_ff {
+        args.push("--no-ff");
+    }
+    args.push(branch);
+
+    match run_git_mutating(&repo, &args) {
+        Ok(output) => Ok(GitMergeResponse {
+            merged: true,
+            message: output.trim().to_string(),
+            conflicts: Vec::new(),
+        }),
+        Err(error) => {
+            let conflicts = conflicted_paths(&repo)?;
+            if conflicts.is_empty() {
+                return Err(error);
+            }
+            Ok(GitMergeResponse {
+                merged: false,
+                message: error.into(),
+                conflicts,
+            })
+        }
+    }
+}
+
+#[tauri::command]
+pub fn git_merge_abort(repo_path: String) -> Result<(), AppError> {
+    let repo = PathBuf::from(repo_path);
+    run_git_mutating(&repo, &["merge", "--abort"]).map(|_| ())
+}
+
+#[tauri::command]
+pub fn git_conflicts(repo_path: String) -> Result<Vec<GitConflict>, AppError> {
+    let repo = PathBuf::from(repo_path);
+
+    let conflicts = conflicted_paths(&repo)?
+        .into_iter()
+        .map(|path| GitConflict {
+            base: index_stage(&repo, 1, &path),
+            ours: index_stage(&repo, 2, &path),
+            theirs: index_stage(&repo, 3, &path),
+            path,
+        })
+        .collect();
+
+    Ok(conflicts)
+}
+
+#[tauri::command]
+pub fn git_resolve_conflict(repo_path: String, path: String, resolved_content: String) -> Result<(), AppError> {
+    let repo = PathBuf::from(repo_path);
+    let target = repo_relative_path(&repo, &path)?;
+
+    std::fs::write(&target, resolved_content).map_err(|error| format!("failed to write {path}: {error}"))?;
+    run_git_mutating(&repo, &["add", "--", path.as_str()]).map(|_| ())
+}
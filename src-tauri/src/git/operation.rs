@@ -0,0 +1,215 @@
+use crate::{
+    askpass,
+    error::{AppError, ErrorKind},
+};
+use serde::Serialize;
+use std::{
+    collections::{HashMap, HashSet},
+    io::{BufReader, Read},
+    path::Path,
+    process::{Child, Command, Stdio},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+use tauri::{Emitter, Manager};
+
+#[derive(Default)]
+pub struct GitOperations {
+    running: Mutex<HashMap<String, Arc<Mutex<Child>>>>,
+    cancelled: Mutex<HashSet<String>>,
+    next_id: AtomicU64,
+}
+
+impl GitOperations {
+    pub fn operation_id(&self, requested: Option<String>) -> String {
+        requested
+            .filter(|id| !id.trim().is_empty())
+            .unwrap_or_else(|| format!("git-{}", self.next_id.fetch_add(1, Ordering::Relaxed)))
+    }
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GitProgressEvent {
+    operation_id: String,
+    phase: String,
+    percentage: Option<u8>,
+    current: Option<u64>,
+    total: Option<u64>,
+    line: String,
+}
+
+pub struct NetworkOutput {
+    pub success: bool,
+    pub exit_code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// Parses progress lines such as `Receiving objects:  45% (450/1000), 1.20 MiB | 2.00 MiB/s`.
+fn parse_progress(operation_id: &str, line: &str) -> GitProgressEvent {
+    let text = line.strip_prefix("remote: ").unwrap_or(line);
+    let (phase, rest) = text.split_once(':').unwrap_or((text, ""));
+
+    let percentage = rest
+        .split_once('%')
+        .and_then(|(before, _)| before.trim().rsplit(' ').next())
+        .and_then(|value| value.parse::<u8>().ok());
+
+    let (current, total) = rest
+        .split_once('(')
+        .and_then(|(_, after)| after.split_once(')'))
+        .and_then(|(counts, _)| counts.split_once('/'))
+        .map(|(current, total)| (current.trim().parse().ok(), total.trim().parse().ok()))
+        .unwrap_or((None, None));
+
+    GitProgressEvent {
+        operation_id: operation_id.to_string(),
+        phase: phase.trim().to_string(),
+        percentage,
+        current,
+        total,
+        line: line.to_string(),
+    }
+}
+
+/// Runs a network git command with credential prompting, streaming `--progress` output from
+/// stderr as `git-progress` events. The child can be killed with `git_cancel_operation`.
+pub fn run_network_command(
+    app: &tauri::AppHandle,
+    repo: &Path,
+    args: &[&str],
+    operation_id: &str,
+) -> Result<NetworkOutput, AppError> {
+    let operations = app.state::<GitOperations>();
+
+    let mut child = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(args)
+        .envs(askpass::git_env(app)?)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|error| format!("failed to run git: {error}"))?;
+
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+    let child = Arc::new(Mutex::new(child));
+
+    operations
+        .running
+        .lock()
+        .map_err(|_| "failed to lock git operations".to_string())?
+        .insert(operation_id.to_string(), Arc::clone(&child));
+
+    let stdout_reader = std::thread::spawn(move || {
+        let mut output = String::new();
+        if let Some(stdout) = stdout {
+            let _ = BufReader::new(stdout).read_to_string(&mut output);
+        }
+        output
+    });
+
+    // Progress updates are separated by carriage returns, final lines by newlines.
+    let mut stderr_output = String::new();
+    if let Some(stderr) = stderr {
+        let mut line = Vec::new();
+        for byte in BufReader::new(stderr).bytes() {
+            let Ok(byte) = byte else {
+                break;
+            };
+            if byte != b'\r' && byte != b'\n' {
+                line.push(byte);
+                continue;
+            }
+
+            let text = String::from_utf8_lossy(&line).trim().to_string();
+            line.clear();
+            if text.is_empty() {
+                continue;
+            }
+            let _ = app.emit("git-progress", parse_progress(operation_id, &text));
+            if byte == b'\n' {
+                stderr_output.push_str(&text);
+                stderr_output.push('\n');
+            }
+        }
+        if !line.is_empty() {
+            stderr_output.push_str(&String::from_utf8_lossy(&line));
+        }
+    }
+
+    let stdout_output = stdout_reader.join().unwrap_or_default();
+    let status = child
+        .lock()
+        .map_err(|_| "failed to lock git process".to_string())?
+        .wait()
+        .map_err(|error| format!("failed to wait for git: {error}"));
+
+    if let Ok(mut running) = operations.running.lock() {
+        running.remove(operation_id);
+    }
+    // Fetches move remote-tracking refs and pulls change the worktree.
+    super::status_cache::invalidate(repo);
+    let cancelled = operations
+        .cancelled
+        .lock()
+        .map(|mut cancelled| cancelled.remove(operation_id))
+        .unwrap_or(false);
+
+    if cancelled {
+        return Err(AppError::new(ErrorKind::Cancelled, "operation cancelled"));
+    }
+
+    let status = status?;
+    Ok(NetworkOutput {
+        success: status.success(),
+        exit_code: status.code(),
+        stdout: stdout_output,
+        stderr: stderr_output.trim().to_string(),
+    })
+}
+
+pub fn run_network_git(
+    app: &tauri::AppHandle,
+    repo: &Path,
+    args: &[&str],
+    operation_id: &str,
+) -> Result<String, AppError> {
+    let output = run_network_command(app, repo, args, operation_id)?;
+    if output.success {
+        return Ok(format!("{}{}", output.stdout, output.stderr));
+    }
+
+    Err(AppError::git(output.stderr, output.exit_code))
+}
+
+#[tauri::command]
+pub fn git_cancel_operation(operation_id: String, state: tauri::State<GitOperations>) -> Result<bool, AppError> {
+    let running = state
+        .running
+        .lock()
+        .map_err(|_| "failed to lock git operations".to_string())?;
+
+    let Some(child) = running.get(&operation_id) else {
+        return Ok(false);
+    };
+
+    state
+        .cancelled
+        .lock()
+        .map_err(|_| "failed to lock git operations".to_string())?
+        .insert(operation_id.clone());
+
+    child
+        .lock()
+        .map_err(|_| "failed to lock git process".to_string())?
+        .kill()
+        .map_err(|error| format!("failed to cancel {operation_id}: {error}"))?;
+
+    Ok(true)
+}
@@ -0,0 +1,253 @@
+use super::{run_git, run_git_with_input, status_cache};
+use crate::error::AppError;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// Inclusive range of line positions within a file diff. Position 0 is the first `@@` line
+/// and every following line (including later hunk headers) counts as one position.
+#[derive(Clone, Copy, Deserialize)]
+pub struct LineRange {
+    start: usize,
+    end: usize,
+}
+
+impl LineRange {
+    fn contains(&self, position: usize) -> bool {
+        self.start <= position && position <= self.end
+    }
+}
+
+/// A single-file unified diff split into its header (`diff --git`, `---`, `+++` lines)
+/// and its hunks, each starting with an `@@` line.
+pub(super) struct FilePatch {
+    pub header: String,
+    pub hunks: Vec<String>,
+}
+
+pub(super) fn split_file_patch(diff: &str) -> FilePatch {
+    let mut header = String::new();
+    let mut hunks: Vec<String> = Vec::new();
+
+    for line in diff.split_inclusive('\n') {
+        if line.starts_with("@@") {
+            hunks.push(line.to_string());
+        } else if let Some(hunk) = hunks.last_mut() {
+            hunk.push_str(line);
+        } else {
+            header.push_str(line);
+        }
+    }
+
+    FilePatch { header, hunks }
+}
+
+pub(super) fn file_diff(repo: &Path, path: &str, staged: bool) -> Result<String, AppError> {
+    if staged {
+        run_git(repo, &["diff", "--staged", "--", path])
+    } else {
+        run_git(repo, &["diff", "--", path])
+    }
+}
+
+pub(super) fn apply_to_index(repo: &Path, patch: &str, reverse: bool) -> Result<(), AppError> {
+    let mut patch = patch.to_string();
+    if !patch.ends_with('\n') {
+        patch.push('\n');
+    }
+
+    let mut args = vec!["apply", "--cached", "--whitespace=nowarn"];
+    if reverse {
+        args.push("--reverse");
+    }
+    args.push("-");
+
+    let result = run_git_with_input(repo, &args, &patch).map(|_| ());
+    status_cache::invalidate(repo);
+    result
+}
+
+fn hunk_patch(repo: &Path, path: &str, hunk_index: usize, staged: bool) -> Result<String, AppError> {
+    let diff = file_diff(repo, path, staged)?;
+    let file_patch = split_file_patch(&diff);
+
+    let hunk = file_patch
+        .hunks
+        .get(hunk_index)
+        .ok_or_else(|| AppError::invalid(format!("hunk {hunk_index} not found in {path}")))?;
+
+    Ok(format!("{}{}", file_patch.header, hunk))
+}
+
+/// Returns the old and new start lines and the trailing section heading of an
+/// `@@ -a,b +c,d @@ heading` line.
+pub(super) fn parse_hunk_header(line: &str) -> Option<(usize, usize, &str)> {
+    let rest = line.strip_prefix("@@ -")?;
+    let (ranges, heading) = rest.split_once(" @@")?;
+    let (old_range, new_range) = ranges.split_once(" +")?;
+    let old_start = old_range.split(',').next()?.parse::<usize>().ok()?;
+    let new_start = new_range.split(',').next()?.parse::<usize>().ok()?;
+    Some((old_start, new_start, heading.trim_end_matches(['\n', '\r'])))
+}
+
+/// Rebuilds a hunk keeping only the selected changes. Unselected changes are turned into
+/// context or dropped depending on which side of the patch already matches the index.
+fn filter_hunk(
+    hunk: &str,
+    first_position: usize,
+    ranges: &[LineRange],
+    reverse: bool,
+    offset: &mut isize,
+) -> Option<String> {
+    let mut lines = hunk.split_inclusive('\n');
+    let (old_start, new_start, heading) = parse_hunk_header(lines.next()?)?;
+
+    let mut body = String::new();
+    let mut old_count = 0_usize;
+    let mut new_count = 0_usize;
+    let mut has_changes = false;
+    let mut previous_kept = false;
+
+    for (index, line) in lines.enumerate() {
+        let position = first_position + index + 1;
+        let selected = ranges.iter().any(|range| range.contains(position));
+        let content = &line[line.len().min(1)..];
+
+        match line.as_bytes().first() {
+            Some(b'+') if selected => {
+                body.push_str(line);
+                new_count += 1;
+                has_changes = true;
+                previous_kept = true;
+            }
+            Some(b'-') if selected => {
+                body.push_str(line);
+                old_count += 1;
+                has_changes = true;
+                previous_kept = true;
+            }
+            Some(b'+') if reverse => {
+                body.push(' ');
+                body.push_str(content);
+                old_count += 1;
+                new_count += 1;
+                previous_kept = true;
+            }
+            Some(b'-') if !reverse => {
+                body.push(' ');
+                body.push_str(content);
+                old_count += 1;
+                new_count += 1;
+                previous_kept = true;
+            }
+            Some(b'+') | Some(b'-') => previous_kept = false,
+            Some(b'\\') => {
+                if previous_kept {
+                    body.push_str(line);
+                }
+            }
+            _ => {
+                body.push_str(line);
+                old_count += 1;
+                new_count += 1;
+                previous_kept = true;
+            }
+        }
+    }
+
+    if !has_changes {
+        return None;
+    }
+
+    // Only one side of the hunk is rewritten: the old side when unstaging, the new side
+    // otherwise. An empty side points at the line before the change, hence the shift.
+    let (old_start, new_start) = if reverse {
+        let shift = isize::from(new_count == 0) - isize::from(old_count == 0);
+        ((new_start as isize - *offset + shift).max(0) as usize, new_start)
+    } else {
+        let shift = isize::from(old_count == 0) - isize::from(new_count == 0);
+        (old_start, (old_start as isize + *offset + shift).max(0) as usize)
+    };
+    *offset += new_count as isize - old_count as isize;
+
+    Some(format!(
+        "@@ -{old_start},{old_count} +{new_start},{new_count} @@{heading}\n{body}"
+    ))
+}
+
+fn lines_patch(repo: &Path, path: &str, ranges: &[LineRange], staged: bool) -> Result<String, AppError> {
+    let diff = file_diff(repo, path, staged)?;
+    let file_patch = split_file_patch(&diff);
+
+    let mut patch = file_patch.header.clone();
+    let mut position = 0_usize;
+    let mut offset = 0_isize;
+    let mut selected_hunks = 0_usize;
+
+    for hunk in &file_patch.hunks {
+        if let Some(filtered) = filter_hunk(hunk, position, ranges, staged, &mut offset) {
+            patch.push_str(&filtered);
+            selected_hunks += 1;
+        }
+        position += hunk.split_inclusive('\n').count();
+    }
+
+    if selected_hunks == 0 {
+        return Err(AppError::invalid("no changed lines selected"));
+    }
+
+    Ok(patch)
+}
+
+fn apply_hunk(
+    repo_path: String,
+    patch: Option<String>,
+    path: Option<String>,
+    hunk_index: Option<usize>,
+    reverse: bool,
+) -> Result<(), AppError> {
+    let repo = PathBuf::from(repo_path);
+
+    let patch = match (patch, path, hunk_index) {
+        (Some(patch), _, _) => patch,
+        (None, Some(path), Some(hunk_index)) => hunk_patch(&repo, &path, hunk_index, reverse)?,
+        _ => return Err(AppError::invalid("either a patch or a path and hunk index is required")),
+    };
+
+    if patch.trim().is_empty() {
+        return Err(AppError::invalid("patch is empty"));
+    }
+
+    apply_to_index(&repo, &patch, reverse)
+}
+
+#[tauri::command]
+pub fn git_stage_hunk(
+    repo_path: String,
+    patch: Option<String>,
+    path: Option<String>,
+    hunk_index: Option<usize>,
+) -> Result<(), AppError> {
+    apply_hunk(repo_path, patch, path, hunk_index, false)
+}
+
+#[tauri::command]
+pub fn git_unstage_hunk(
+    repo_path: String,
+    patch: Option<String>,
+    path: Option<String>,
+    hunk_index: Option<usize>,
+) -> Result<(), AppError> {
+    apply_hunk(repo_path, patch, path, hunk_index, true)
+}
+
+#[tauri::command]
+pub fn git_stage_lines(
+    repo_path: String,
+    path: String,
+    line_ranges: Vec<LineRange>,
+    staged: bool,
+) -> Result<(), AppError> {
+    let repo = PathBuf::from(repo_path);
+    let patch = lines_patch(&repo, &path, &line_ranges, staged)?;
+    apply_to_index(&repo, &patch, staged)
+}
@@ -0,0 +1,256 @@
+use super::{
+    conflicted_paths, git_path, log::validate_revision, run_git, run_git_mutating, run_git_with_env, status_cache,
+};
+use crate::error::AppError;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RebaseActionKind {
+    Pick,
+    Reword,
+    Edit,
+    Squash,
+    Fixup,
+    Drop,
+}
+
+impl RebaseActionKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            RebaseActionKind::Pick => "pick",
+            RebaseActionKind::Reword => "reword",
+            RebaseActionKind::Edit => "edit",
+            RebaseActionKind::Squash => "squash",
+            RebaseActionKind::Fixup => "fixup",
+            RebaseActionKind::Drop => "drop",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "pick" | "p" => Some(RebaseActionKind::Pick),
+            "reword" | "r" => Some(RebaseActionKind::Reword),
+            "edit" | "e" => Some(RebaseActionKind::Edit),
+            "squash" | "s" => Some(RebaseActionKind::Squash),
+            "fixup" | "f" => Some(RebaseActionKind::Fixup),
+            "drop" | "d" => Some(RebaseActionKind::Drop),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RebaseAction {
+    action: RebaseActionKind,
+    hash: String,
+    #[serde(default)]
+    subject: String,
+    /// New commit message for `reword`. Without it the original message is kept.
+    #[serde(default)]
+    message: Option<String>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitRebaseStatus {
+    in_progress: bool,
+    head_name: Option<String>,
+    onto: Option<String>,
+    step: usize,
+    total: usize,
+    done: Vec<RebaseAction>,
+    todo: Vec<RebaseAction>,
+    conflicts: Vec<String>,
+}
+
+/// Editors run by git through `sh`, so paths are quoted for a POSIX shell on every platform.
+fn shell_quote(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    format!("'{}'", path.replace('\'', "'\\''"))
+}
+
+fn temp_file(prefix: &str) -> PathBuf {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_nanos())
+        .unwrap_or(0);
+    std::env::temp_dir().join(format!("{prefix}-{}-{nanos}", std::process::id()))
+}
+
+fn parse_todo(raw: &str) -> Vec<RebaseAction> {
+    raw.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let mut parts = line.splitn(3, ' ');
+            let action = RebaseActionKind::parse(parts.next()?)?;
+            let hash = parts.next()?.to_string();
+            let subject = parts.next().unwrap_or_default().to_string();
+            Some(RebaseAction {
+                action,
+                hash,
+                subject,
+                message: None,
+            })
+        })
+        .collect()
+}
+
+fn read_state_file(dir: &Path, name: &str) -> Option<String> {
+    std::fs::read_to_string(dir.join(name))
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+/// Builds the todo file and writes reworded messages to files picked up by `exec` lines.
+fn build_todo(actions: &[RebaseAction], message_files: &mut Vec<PathBuf>) -> Result<String, AppError> {
+    let mut todo = String::new();
+
+    for action in actions {
+        let hash = validate_revision(&action.hash)?;
+        let message = action
+            .message
+            .as_deref()
+            .map(str::trim)
+            .filter(|message| !message.is_empty());
+
+        match (action.action, message) {
+            (RebaseActionKind::Reword, Some(message)) => {
+                let path = temp_file("nlk-rebase-message");
+                std::fs::write(&path, message).map_err(|error| format!("failed to write commit message: {error}"))?;
+                todo.push_str(&format!("pick {hash}\n"));
+                todo.push_str(&format!("exec git commit --amend --quiet -F {}\n", shell_quote(&path)));
+                message_files.push(path);
+            }
+            (kind, _) => todo.push_str(&format!("{} {hash}\n", kind.as_str())),
+        }
+    }
+
+    Ok(todo)
+}
+
+fn rebase_status(repo: &Path) -> Result<GitRebaseStatus, AppError> {
+    let merge_dir = git_path(repo, "rebase-merge")?;
+    let in_progress = merge_dir.is_dir() || git_path(repo, "rebase-apply")?.is_dir();
+
+    if !in_progress {
+        return Ok(GitRebaseStatus {
+            in_progress: false,
+            head_name: None,
+            onto: None,
+            step: 0,
+            total: 0,
+            done: Vec::new(),
+            todo: Vec::new(),
+            conflicts: Vec::new(),
+        });
+    }
+
+    Ok(GitRebaseStatus {
+        in_progress: true,
+        head_name: read_state_file(&merge_dir, "head-name")
+            .map(|name| name.trim_start_matches("refs/heads/").to_string()),
+        onto: read_state_file(&merge_dir, "onto"),
+        step: read_state_file(&merge_dir, "msgnum")
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(0),
+        total: read_state_file(&merge_dir, "end")
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(0),
+        done: parse_todo(&read_state_file(&merge_dir, "done").unwrap_or_default()),
+        todo: parse_todo(&read_state_file(&merge_dir, "git-rebase-todo").unwrap_or_default()),
+        conflicts: conflicted_paths(repo)?,
+    })
+}
+
+/// Runs a rebase step. A stop for conflicts or `edit` is not an error; the status says where it stopped.
+fn run_rebase_step(repo: &Path, args: &[&str], envs: &[(&str, &str)]) -> Result<GitRebaseStatus, AppError> {
+    let result = run_git_with_env(repo, args, envs);
+    status_cache::invalidate(repo);
+    if let Err(error) = result {
+        let status = rebase_status(repo)?;
+        if !status.in_progress {
+            return Err(error);
+        }
+        return Ok(status);
+    }
+
+    rebase_status(repo)
+}
+
+#[tauri::command]
+pub fn git_rebase_todo(repo_path: String, onto: String) -> Result<Vec<RebaseAction>, AppError> {
+    let repo = PathBuf::from(repo_path);
+    let onto = validate_revision(&onto)?;
+    let range = format!("{onto}..HEAD");
+    let raw = run_git(
+        &repo,
+        &["log", "--reverse", "--no-merges", "--format=pick %h %s", range.as_str()],
+    )?;
+    Ok(parse_todo(&raw))
+}
+
+#[tauri::command]
+pub fn git_rebase_start(
+    repo_path: String,
+    onto: String,
+    todo_actions: Vec<RebaseAction>,
+) -> Result<GitRebaseStatus, AppError> {
+    let repo = PathBuf::from(repo_path);
+    let onto = validate_revision(&onto)?;
+    if todo_actions.is_empty() {
+        return Err(AppError::invalid("rebase todo is empty"));
+    }
+
+    let mut message_files = Vec::new();
+    let todo = build_todo(&todo_actions, &mut message_files)?;
+    let todo_path = temp_file("nlk-rebase-todo");
+    std::fs::write(&todo_path, todo).map_err(|error| format!("failed to write rebase todo: {error}"))?;
+
+    let sequence_editor = format!("cp {}", shell_quote(&todo_path));
+    let result = run_rebase_step(
+        &repo,
+        &["rebase", "-i", onto],
+        &[("GIT_SEQUENCE_EDITOR", sequence_editor.as_str()), ("GIT_EDITOR", "true")],
+    );
+
+    let _ = std::fs::remove_file(&todo_path);
+    // Message files are read by `exec` lines that may run after a conflict stop; keep them until
+    // the rebase is no longer in progress.
+    if matches!(&result, Ok(status) if !status.in_progress) || result.is_err() {
+        for path in message_files {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+
+    result
+}
+
+#[tauri::command]
+pub fn git_rebase_status(repo_path: String) -> Result<GitRebaseStatus, AppError> {
+    let repo = PathBuf::from(repo_path);
+    rebase_status(&repo)
+}
+
+#[tauri::command]
+pub fn git_rebase_continue(repo_path: String) -> Result<GitRebaseStatus, AppError> {
+    let repo = PathBuf::from(repo_path);
+    run_rebase_step(&repo, &["rebase", "--continue"], &[("GIT_EDITOR", "true")])
+}
+
+#[tauri::command]
+pub fn git_rebase_skip(repo_path: String) -> Result<GitRebaseStatus, AppError> {
+    let repo = PathBuf::from(repo_path);
+    run_rebase_step(&repo, &["rebase", "--skip"], &[("GIT_EDITOR", "true")])
+}
+
+#[tauri::command]
+pub fn git_rebase_abort(repo_path: String) -> Result<GitRebaseStatus, AppError> {
+    let repo = PathBuf::from(repo_path);
+    run_git_mutating(&repo, &["rebase", "--abort"])?;
+    rebase_status(&repo)
+}
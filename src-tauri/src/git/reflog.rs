@@ -0,0 +1,101 @@
+use super::{log::validate_revision, rev_parse, run_git, run_git_mutating};
+use crate::error::AppError;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+const DEFAULT_REFLOG_LIMIT: usize = 100;
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitReflogEntry {
+    /// Commit HEAD pointed at after the operation.
+    hash: String,
+    /// Selector such as `HEAD@{1}`, usable as a revision.
+    selector: String,
+    /// What moved HEAD, e.g. `commit`, `commit (amend)`, `merge main` or `rebase (finish)`.
+    action: String,
+    message: String,
+    date: String,
+}
+
+#[derive(Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GitResetMode {
+    /// Moves HEAD only; the undone changes stay staged.
+    Soft,
+    /// Also resets the index; the undone changes stay in the work tree.
+    Mixed,
+    /// Also resets the work tree, discarding the undone changes.
+    Hard,
+}
+
+impl GitResetMode {
+    fn as_arg(self) -> &'static str {
+        match self {
+            GitResetMode::Soft => "--soft",
+            GitResetMode::Mixed => "--mixed",
+            GitResetMode::Hard => "--hard",
+        }
+    }
+}
+
+/// Parses `git reflog` output printed with `%H %gd %gs`, where `%gd` carries the date. With a
+/// date format git prints date-based selectors too, so the index-based `HEAD@{n}` comes from the
+/// entry's position instead.
+fn parse_reflog(raw: &str) -> Vec<GitReflogEntry> {
+    raw.lines()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            let mut fields = line.splitn(3, '\x1f');
+            let hash = fields.next()?.trim().to_string();
+            let selector = format!("HEAD@{{{index}}}");
+            let date = fields
+                .next()?
+                .split_once("@{")
+                .map(|(_, date)| date.trim_end_matches('}').to_string())
+                .unwrap_or_default();
+            let subject = fields.next().unwrap_or_default();
+            let (action, message) = subject.split_once(": ").unwrap_or((subject, ""));
+            Some(GitReflogEntry {
+                hash,
+                selector,
+                action: action.to_string(),
+                message: message.to_string(),
+                date,
+            })
+        })
+        .collect()
+}
+
+/// Recent positions of HEAD, newest first, for undoing the last commit, merge or rebase.
+#[tauri::command]
+pub fn git_reflog(repo_path: String, limit: Option<usize>) -> Result<Vec<GitReflogEntry>, AppError> {
+    let repo = PathBuf::from(repo_path);
+    let limit = format!("-n{}", limit.unwrap_or(DEFAULT_REFLOG_LIMIT));
+    let raw = run_git(
+        &repo,
+        &[
+            "reflog",
+            "show",
+            "--date=iso-strict",
+            "--format=%H%x1f%gd%x1f%gs",
+            &limit,
+            "HEAD",
+            "--",
+        ],
+    )?;
+    Ok(parse_reflog(&raw))
+}
+
+/// Moves the current branch to `rev`, typically a reflog selector like `HEAD@{1}`. Returns the
+/// commit HEAD now points at.
+#[tauri::command]
+pub fn git_reset_to(repo_path: String, rev: String, mode: GitResetMode) -> Result<String, AppError> {
+    let repo = PathBuf::from(repo_path);
+    let rev = validate_revision(&rev)?;
+    let target = rev_parse(&repo, &format!("{rev}^{{commit}}"))
+        .ok_or_else(|| AppError::invalid(format!("unknown revision: {rev}")))?;
+
+    run_git_mutating(&repo, &["reset", mode.as_arg(), &target, "--"])?;
+    Ok(target)
+}
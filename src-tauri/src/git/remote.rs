@@ -0,0 +1,119 @@
+use super::{default_remote, run_git, run_git_mutating};
+use crate::error::AppError;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitRemote {
+    name: String,
+    fetch_url: String,
+    push_url: String,
+}
+
+pub(super) fn validate_remote_name(name: &str) -> Result<&str, AppError> {
+    let trimmed = name.trim();
+    if trimmed.is_empty() {
+        return Err(AppError::invalid("remote name is empty"));
+    }
+    if trimmed.starts_with('-') || trimmed.contains(char::is_whitespace) {
+        return Err(AppError::invalid(format!("invalid remote name: {trimmed}")));
+    }
+    Ok(trimmed)
+}
+
+/// Name and fetch URL of `name`, or of the default remote when no name is given.
+pub fn remote_url(repo: &Path, name: Option<&str>) -> Result<(String, String), AppError> {
+    let name = match name {
+        Some(name) => validate_remote_name(name)?.to_string(),
+        None => default_remote(repo)?,
+    };
+    let url = run_git(repo, &["remote", "get-url", name.as_str()])?.trim().to_string();
+    Ok((name, url))
+}
+
+fn validate_remote_url(url: &str) -> Result<&str, AppError> {
+    let trimmed = url.trim();
+    if trimmed.is_empty() {
+        return Err(AppError::invalid("remote url is empty"));
+    }
+    if trimmed.starts_with('-') {
+        return Err(AppError::invalid(format!("invalid remote url: {trimmed}")));
+    }
+    Ok(trimmed)
+}
+
+#[tauri::command]
+pub fn git_remotes(repo_path: String) -> Result<Vec<GitRemote>, AppError> {
+    let repo = PathBuf::from(repo_path);
+    let raw = run_git(&repo, &["remote", "-v"])?;
+    let mut remotes: Vec<GitRemote> = Vec::new();
+
+    for line in raw.lines() {
+        let Some((name, rest)) = line.split_once('\t') else {
+            continue;
+        };
+        let Some((url, kind)) = rest.rsplit_once(' ') else {
+            continue;
+        };
+
+        let index = match remotes.iter().position(|remote| remote.name == name) {
+            Some(index) => index,
+            None => {
+                remotes.push(GitRemote {
+                    name: name.to_string(),
+                    fetch_url: String::new(),
+                    push_url: String::new(),
+                });
+                remotes.len() - 1
+            }
+        };
+
+        match kind {
+            "(fetch)" => remotes[index].fetch_url = url.to_string(),
+            "(push)" => remotes[index].push_url = url.to_string(),
+            _ => {}
+        }
+    }
+
+    Ok(remotes)
+}
+
+#[tauri::command]
+pub fn git_remote_add(repo_path: String, name: String, url: String) -> Result<(), AppError> {
+    let repo = PathBuf::from(repo_path);
+    let name = validate_remote_name(&name)?;
+    let url = validate_remote_url(&url)?;
+    run_git_mutating(&repo, &["remote", "add", name, url]).map(|_| ())
+}
+
+#[tauri::command]
+pub fn git_remote_remove(repo_path: String, name: String) -> Result<(), AppError> {
+    let repo = PathBuf::from(repo_path);
+    let name = validate_remote_name(&name)?;
+    run_git_mutating(&repo, &["remote", "remove", name]).map(|_| ())
+}
+
+#[tauri::command]
+pub fn git_remote_rename(repo_path: String, old_name: String, new_name: String) -> Result<(), AppError> {
+    let repo = PathBuf::from(repo_path);
+    let old_name = validate_remote_name(&old_name)?;
+    let new_name = validate_remote_name(&new_name)?;
+    run_git_mutating(&repo, &["remote", "rename", old_name, new_name]).map(|_| ())
+}
+
+#[tauri::command]
+pub fn git_remote_set_url(repo_path: String, name: String, url: String, push: bool) -> Result<(), AppError> {
+    let repo = PathBuf::from(repo_path);
+    let name = validate_remote_name(&name)?;
+    let url = validate_remote_url(&url)?;
+
+    let mut args = vec!["remote", "set-url"];
+    if push {
+        args.push("--push");
+    }
+    args.push(name);
+    args.push(url);
+
+    run_git_mutating(&repo, &args).map(|_| ())
+}
@@ -0,0 +1,77 @@
+use super::{log::validate_revision, rev_parse};
+use crate::error::AppError;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::Serialize;
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+/// Bytes git looks at when deciding whether a file is binary.
+const BINARY_SNIFF_LEN: usize = 8000;
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitFileContent {
+    /// Blob hash of this version.
+    hash: String,
+    size: usize,
+    binary: bool,
+    /// The text, or base64 of the bytes for a binary file.
+    content: String,
+}
+
+/// Object spec of `path` at `rev`, or in the index when `rev` is `None`.
+pub(super) fn blob_spec(path: &str, rev: Option<&str>) -> String {
+    format!("{}:{}", rev.unwrap_or_default(), path.trim_start_matches("./"))
+}
+
+/// Reads a blob's raw bytes; `None` when `spec` names no object, e.g. a file added after `rev`.
+pub(super) fn blob_bytes(repo: &Path, spec: &str) -> Result<Option<(String, Vec<u8>)>, AppError> {
+    let Some(hash) = rev_parse(repo, spec) else {
+        return Ok(None);
+    };
+
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(["cat-file", "blob", &hash])
+        .output()
+        .map_err(|error| format!("failed to run git: {error}"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        return Err(AppError::git(stderr, output.status.code()));
+    }
+    Ok(Some((hash, output.stdout)))
+}
+
+/// Whole contents of `path` at `rev`, or as staged when `rev` is omitted, for side-by-side
+/// views. Returns `None` when the file doesn't exist there.
+#[tauri::command]
+pub fn git_show_file(repo_path: String, path: String, rev: Option<String>) -> Result<Option<GitFileContent>, AppError> {
+    let repo = PathBuf::from(repo_path);
+    let rev = rev.as_deref().map(validate_revision).transpose()?;
+    if let Some(rev) = rev {
+        if rev_parse(&repo, &format!("{rev}^{{object}}")).is_none() {
+            return Err(AppError::invalid(format!("unknown revision: {rev}")));
+        }
+    }
+
+    let Some((hash, bytes)) = blob_bytes(&repo, &blob_spec(&path, rev))? else {
+        return Ok(None);
+    };
+
+    let size = bytes.len();
+    let (binary, content) = match String::from_utf8(bytes) {
+        Ok(text) if !text.as_bytes()[..size.min(BINARY_SNIFF_LEN)].contains(&0) => (false, text),
+        Ok(text) => (true, STANDARD.encode(text)),
+        Err(error) => (true, STANDARD.encode(error.into_bytes())),
+    };
+
+    Ok(Some(GitFileContent {
+        hash,
+        size,
+        binary,
+        content,
+    }))
+}
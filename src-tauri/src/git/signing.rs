@@ -0,0 +1,179 @@
+use super::run_git;
+use crate::{askpass, error::AppError};
+use serde::Serialize;
+use std::{
+    io::{Read, Write},
+    path::{Path, PathBuf},
+    process::{Command, Output, Stdio},
+};
+
+const GPG_PROGRAM_ENV: &str = "NLK_GPG_PROGRAM";
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitSigningConfig {
+    /// `commit.gpgsign`, the default when a commit doesn't choose explicitly.
+    enabled: bool,
+    /// `gpg.format`: `openpgp`, `x509` or `ssh`.
+    format: String,
+    key: Option<String>,
+    program: String,
+    /// Whether the signing program could be started.
+    available: bool,
+}
+
+fn config_value(repo: &Path, key: &str) -> Option<String> {
+    run_git(repo, &["config", "--get", key])
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+/// `gpg.format` and the program git signs with for it.
+fn signing_program(repo: &Path) -> (String, String) {
+    let format = config_value(repo, "gpg.format").unwrap_or_else(|| "openpgp".to_string());
+    let program = match format.as_str() {
+        "ssh" => config_value(repo, "gpg.ssh.program").unwrap_or_else(|| "ssh-keygen".to_string()),
+        "x509" => config_value(repo, "gpg.x509.program").unwrap_or_else(|| "gpgsm".to_string()),
+        _ => config_value(repo, "gpg.openpgp.program")
+            .or_else(|| config_value(repo, "gpg.program"))
+            .unwrap_or_else(|| "gpg".to_string()),
+    };
+    (format, program)
+}
+
+/// `commit.gpgsign`, whether commits are signed unless they say otherwise.
+pub(super) fn signing_enabled(repo: &Path) -> bool {
+    run_git(repo, &["config", "--type=bool", "--get", "commit.gpgsign"]).is_ok_and(|value| value.trim() == "true")
+}
+
+pub(super) fn signing_config(repo: &Path) -> GitSigningConfig {
+    let (format, program) = signing_program(repo);
+
+    GitSigningConfig {
+        enabled: signing_enabled(repo),
+        key: config_value(repo, "user.signingkey"),
+        available: Command::new(&program)
+            .arg("--version")
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .is_ok(),
+        format,
+        program,
+    }
+}
+
+/// Extra arguments and environment for a signing git command.
+#[derive(Default)]
+pub(super) struct SigningArgs {
+    /// `-c` overrides that go before the subcommand.
+    pub config_args: Vec<String>,
+    pub envs: Vec<(String, String)>,
+}
+
+/// OpenPGP signing is routed through this binary so passphrase prompts reach the frontend; SSH
+/// signing already uses the askpass environment.
+pub(super) fn signing_args(repo: &Path) -> Result<SigningArgs, String> {
+    let (format, program) = signing_program(repo);
+    if format != "openpgp" {
+        return Ok(SigningArgs::default());
+    }
+
+    let helper = std::env::current_exe().map_err(|error| format!("failed to resolve signing helper: {error}"))?;
+    Ok(SigningArgs {
+        config_args: vec!["-c".to_string(), format!("gpg.program={}", helper.to_string_lossy())],
+        envs: vec![(GPG_PROGRAM_ENV.to_string(), program)],
+    })
+}
+
+/// Rewrites git's signing failures so the frontend can tell them apart from other commit errors.
+pub(super) fn signing_error(stderr: &str) -> Option<String> {
+    let lowered = stderr.to_lowercase();
+    let signing = lowered.contains("gpg failed to sign")
+        || lowered.contains("failed to sign the data")
+        || lowered.contains("cannot run gpg")
+        || lowered.contains("ssh-keygen")
+        || lowered.contains("signing key");
+
+    signing.then(|| format!("signing failed: {stderr}"))
+}
+
+fn needs_passphrase(stderr: &[u8]) -> bool {
+    let stderr = String::from_utf8_lossy(stderr).to_lowercase();
+    ["pinentry", "passphrase", "inappropriate ioctl", "no tty"]
+        .iter()
+        .any(|marker| stderr.contains(marker))
+}
+
+fn run_gpg(program: &str, args: &[String], input: &[u8]) -> Option<Output> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .ok()?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        let _ = stdin.write_all(input);
+    }
+    child.wait_with_output().ok()
+}
+
+fn forward(output: Output) -> i32 {
+    let _ = std::io::stdout().write_all(&output.stdout);
+    let _ = std::io::stderr().write_all(&output.stderr);
+    output.status.code().unwrap_or(1)
+}
+
+/// Entry point when git runs this binary as `gpg.program`. Signs with the real gpg, and if that
+/// fails for lack of a usable pinentry asks for the passphrase over the askpass bridge and retries
+/// in loopback mode. Returns `None` for normal app launches.
+pub fn run_gpg_wrapper() -> Option<i32> {
+    let program = std::env::var(GPG_PROGRAM_ENV).ok()?;
+    let args = std::env::args().skip(1).collect::<Vec<String>>();
+    // Askpass helpers inherit the same environment but are called with a prompt, not options.
+    if !args.first().is_some_and(|arg| arg.starts_with('-')) {
+        return None;
+    }
+
+    let mut payload = Vec::new();
+    if std::io::stdin().read_to_end(&mut payload).is_err() {
+        return Some(2);
+    }
+
+    let Some(output) = run_gpg(&program, &args, &payload) else {
+        eprintln!("cannot run {program}");
+        return Some(2);
+    };
+    if output.status.success() || !needs_passphrase(&output.stderr) {
+        return Some(forward(output));
+    }
+
+    let Some(passphrase) = askpass::request("Enter passphrase for signing key:") else {
+        return Some(forward(output));
+    };
+
+    // gpg reads the passphrase line from fd 0 byte by byte, leaving the payload behind it intact.
+    let mut input = passphrase.trim_end_matches(['\r', '\n']).as_bytes().to_vec();
+    input.push(b'\n');
+    input.extend_from_slice(&payload);
+
+    let mut loopback_args = ["--pinentry-mode", "loopback", "--passphrase-fd", "0"]
+        .iter()
+        .map(|arg| arg.to_string())
+        .collect::<Vec<String>>();
+    loopback_args.extend(args);
+
+    match run_gpg(&program, &loopback_args, &input) {
+        Some(output) => Some(forward(output)),
+        None => Some(2),
+    }
+}
+
+#[tauri::command]
+pub fn git_signing_config(repo_path: String) -> Result<GitSigningConfig, AppError> {
+    let repo = PathBuf::from(repo_path);
+    Ok(signing_config(&repo))
+}
@@ -0,0 +1,101 @@
+use super::{run_git, run_git_mutating};
+use crate::error::AppError;
+use serde::Serialize;
+use std::path::PathBuf;
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitStashEntry {
+    index: usize,
+    reference: String,
+    hash: String,
+    branch: Option<String>,
+    message: String,
+    date: String,
+}
+
+fn stash_ref(index: usize) -> String {
+    format!("stash@{{{index}}}")
+}
+
+/// Splits a stash reflog subject such as `On main: message` or `WIP on main: abc123 subject`.
+fn parse_stash_subject(subject: &str) -> (Option<String>, String) {
+    let rest = subject
+        .strip_prefix("WIP on ")
+        .or_else(|| subject.strip_prefix("On "));
+
+    match rest.and_then(|rest| rest.split_once(": ")) {
+        Some((branch, message)) => (Some(branch.to_string()), message.to_string()),
+        None => (None, subject.to_string()),
+    }
+}
+
+#[tauri::command]
+pub fn git_stash_save(repo_path: String, message: Option<String>, include_untracked: bool) -> Result<String, AppError> {
+    let repo = PathBuf::from(repo_path);
+    let mut args = vec!["stash", "push"];
+    if include_untracked {
+        args.push("--include-untracked");
+    }
+
+    let message = message.map(|message| message.trim().to_string()).unwrap_or_default();
+    if !message.is_empty() {
+        args.push("-m");
+        args.push(message.as_str());
+    }
+
+    run_git_mutating(&repo, &args)
+}
+
+#[tauri::command]
+pub fn git_stash_list(repo_path: String) -> Result<Vec<GitStashEntry>, AppError> {
+    let repo = PathBuf::from(repo_path);
+    let raw = run_git(&repo, &["stash", "list", "--format=%gd%x1f%H%x1f%gs%x1f%aI"])?;
+
+    let entries = raw
+        .lines()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            let mut fields = line.split('\u{1f}');
+            let reference = fields.next()?.to_string();
+            let hash = fields.next()?.to_string();
+            let (branch, message) = parse_stash_subject(fields.next()?);
+            let date = fields.next().unwrap_or_default().to_string();
+
+            Some(GitStashEntry {
+                index,
+                reference,
+                hash,
+                branch,
+                message,
+                date,
+            })
+        })
+        .collect();
+
+    Ok(entries)
+}
+
+#[tauri::command]
+pub fn git_stash_apply(repo_path: String, index: usize) -> Result<String, AppError> {
+    let repo = PathBuf::from(repo_path);
+    run_git_mutating(&repo, &["stash", "apply", stash_ref(index).as_str()])
+}
+
+#[tauri::command]
+pub fn git_stash_pop(repo_path: String, index: usize) -> Result<String, AppError> {
+    let repo = PathBuf::from(repo_path);
+    run_git_mutating(&repo, &["stash", "pop", stash_ref(index).as_str()])
+}
+
+#[tauri::command]
+pub fn git_stash_drop(repo_path: String, index: usize) -> Result<String, AppError> {
+    let repo = PathBuf::from(repo_path);
+    run_git_mutating(&repo, &["stash", "drop", stash_ref(index).as_str()])
+}
+
+#[tauri::command]
+pub fn git_stash_show(repo_path: String, index: usize) -> Result<String, AppError> {
+    let repo = PathBuf::from(repo_path);
+    run_git(&repo, &["stash", "show", "-p", "--stat", stash_ref(index).as_str()])
+}
@@ -0,0 +1,152 @@
+use super::{default_remote, git_path, resolve_git_root, rev_parse, run_git};
+use crate::error::AppError;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// Contributors returned at most, busiest first. `contributor_count` has the full number.
+const MAX_CONTRIBUTORS: usize = 50;
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitContributor {
+    name: String,
+    email: String,
+    commits: usize,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitHeadInfo {
+    /// `None` when HEAD is detached.
+    branch: Option<String>,
+    hash: String,
+    subject: String,
+    author: String,
+    /// Author date, ISO 8601.
+    date: String,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitRepoStats {
+    repo_path: String,
+    commit_count: usize,
+    contributor_count: usize,
+    contributors: Vec<GitContributor>,
+    /// `None` on a branch without commits.
+    head: Option<GitHeadInfo>,
+    /// Bytes used by the object database, loose and packed.
+    size_bytes: u64,
+    /// Whether the repository uses Git LFS, going by `.gitattributes` or an LFS object store.
+    uses_lfs: bool,
+    default_branch: Option<String>,
+}
+
+/// Parses `git shortlog -sne` lines like `   42\tJane Doe <jane@example.com>`.
+fn parse_shortlog(raw: &str) -> Vec<GitContributor> {
+    raw.lines()
+        .filter_map(|line| {
+            let (commits, ident) = line.trim().split_once('\t')?;
+            let (name, email) = match ident.rsplit_once(" <") {
+                Some((name, email)) => (name, email.trim_end_matches('>')),
+                None => (ident, ""),
+            };
+            Some(GitContributor {
+                name: name.to_string(),
+                email: email.to_string(),
+                commits: commits.trim().parse().ok()?,
+            })
+        })
+        .collect()
+}
+
+fn head_info(repo: &Path) -> Option<GitHeadInfo> {
+    let raw = run_git(repo, &["log", "-1", "--format=%H%x1f%s%x1f%an%x1f%aI"]).ok()?;
+    let mut fields = raw.trim_end().split('\x1f');
+    let hash = fields.next().filter(|hash| !hash.is_empty())?.to_string();
+    let branch = run_git(repo, &["branch", "--show-current"])
+        .ok()
+        .map(|branch| branch.trim().to_string())
+        .filter(|branch| !branch.is_empty());
+
+    Some(GitHeadInfo {
+        branch,
+        hash,
+        subject: fields.next().unwrap_or_default().to_string(),
+        author: fields.next().unwrap_or_default().to_string(),
+        date: fields.next().unwrap_or_default().to_string(),
+    })
+}
+
+/// Sums `size` and `size-pack` from `git count-objects -v`, which are in KiB.
+fn object_size(repo: &Path) -> u64 {
+    let Ok(raw) = run_git(repo, &["count-objects", "-v"]) else {
+        return 0;
+    };
+    raw.lines()
+        .filter_map(|line| line.split_once(": "))
+        .filter(|(key, _)| matches!(*key, "size" | "size-pack"))
+        .filter_map(|(_, value)| value.trim().parse::<u64>().ok())
+        .sum::<u64>()
+        * 1024
+}
+
+fn uses_lfs(root: &Path) -> bool {
+    let attributes = std::fs::read_to_string(root.join(".gitattributes")).unwrap_or_default();
+    attributes.contains("filter=lfs") || git_path(root, "lfs").is_ok_and(|path| path.is_dir())
+}
+
+/// The remote's default branch from `refs/remotes/<remote>/HEAD`, falling back to
+/// `init.defaultBranch` and then to whichever of `main` and `master` exists.
+fn default_branch(repo: &Path) -> Option<String> {
+    if let Ok(remote) = default_remote(repo) {
+        let head = format!("refs/remotes/{remote}/HEAD");
+        if let Ok(target) = run_git(repo, &["symbolic-ref", "--short", "--quiet", &head]) {
+            if let Some(branch) = target.trim().strip_prefix(&format!("{remote}/")) {
+                return Some(branch.to_string());
+            }
+        }
+    }
+
+    if let Some(branch) = run_git(repo, &["config", "--get", "init.defaultBranch"])
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+    {
+        if rev_parse(repo, &format!("refs/heads/{branch}")).is_some() {
+            return Some(branch);
+        }
+    }
+
+    ["main", "master"]
+        .into_iter()
+        .find(|branch| rev_parse(repo, &format!("refs/heads/{branch}")).is_some())
+        .map(str::to_string)
+}
+
+/// Overview of a repository for its header: history size, contributors, HEAD and storage.
+#[tauri::command]
+pub fn git_repo_stats(repo_path: String) -> Result<GitRepoStats, AppError> {
+    let repo = PathBuf::from(repo_path);
+    let root = resolve_git_root(&repo)?;
+    let head = head_info(&root);
+
+    let (commit_count, contributors) = if head.is_some() {
+        let count = run_git(&root, &["rev-list", "--count", "HEAD"])?;
+        let shortlog = run_git(&root, &["shortlog", "-sne", "HEAD"])?;
+        (count.trim().parse().unwrap_or(0), parse_shortlog(&shortlog))
+    } else {
+        (0, Vec::new())
+    };
+
+    Ok(GitRepoStats {
+        repo_path: root.to_string_lossy().to_string(),
+        commit_count,
+        contributor_count: contributors.len(),
+        contributors: contributors.into_iter().take(MAX_CONTRIBUTORS).collect(),
+        head,
+        size_bytes: object_size(&root),
+        uses_lfs: uses_lfs(&root),
+        default_branch: default_branch(&root),
+    })
+}
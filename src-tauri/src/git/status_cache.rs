@@ -0,0 +1,57 @@
+use super::GitStatusResponse;
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{Mutex, OnceLock},
+    time::{Duration, Instant},
+};
+
+/// How long a status is served without asking git again. Changes made through the app or seen
+/// by the watcher invalidate it sooner.
+const TTL: Duration = Duration::from_secs(2);
+
+struct CachedStatus {
+    root: PathBuf,
+    status: GitStatusResponse,
+    loaded_at: Instant,
+}
+
+/// Statuses keyed by the path they were requested for, usually the repository root.
+fn cache() -> &'static Mutex<HashMap<PathBuf, CachedStatus>> {
+    static CACHE: OnceLock<Mutex<HashMap<PathBuf, CachedStatus>>> = OnceLock::new();
+    CACHE.get_or_init(Default::default)
+}
+
+pub(super) fn get(path: &Path) -> Option<GitStatusResponse> {
+    let cache = cache().lock().ok()?;
+    let cached = cache.get(path)?;
+    (cached.loaded_at.elapsed() < TTL).then(|| cached.status.clone())
+}
+
+pub(super) fn store(path: &Path, status: &GitStatusResponse) {
+    if let Ok(mut cache) = cache().lock() {
+        cache.retain(|_, cached| cached.loaded_at.elapsed() < TTL);
+        cache.insert(
+            path.to_path_buf(),
+            CachedStatus {
+                root: PathBuf::from(&status.repo_path),
+                status: status.clone(),
+                loaded_at: Instant::now(),
+            },
+        );
+    }
+}
+
+/// Drops cached statuses of the repository containing `path`, after a change made through the
+/// app or reported by the watcher.
+pub fn invalidate(path: &Path) {
+    if let Ok(mut cache) = cache().lock() {
+        cache.retain(|key, cached| !path.starts_with(&cached.root) && !key.starts_with(path));
+    }
+}
+
+pub fn clear() {
+    if let Ok(mut cache) = cache().lock() {
+        cache.clear();
+    }
+}
@@ -0,0 +1,187 @@
+use super::{operation::run_network_git, run_git, run_operation};
+use crate::error::AppError;
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SubmoduleState {
+    Current,
+    Uninitialized,
+    NewCommits,
+    Conflict,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitSubmodule {
+    name: String,
+    path: String,
+    url: Option<String>,
+    sha: String,
+    describe: Option<String>,
+    state: SubmoduleState,
+}
+
+/// Why a submodule shows up as changed in the parent repository.
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubmoduleChange {
+    new_commits: bool,
+    modified_content: bool,
+    untracked_content: bool,
+}
+
+/// Parses `git submodule status` lines: `<flag><sha> <path> (<describe>)`.
+fn parse_status(raw: &str) -> Vec<(SubmoduleState, String, String, Option<String>)> {
+    raw.lines()
+        .filter(|line| !line.is_empty())
+        .filter_map(|line| {
+            let state = match line.chars().next()? {
+                '-' => SubmoduleState::Uninitialized,
+                '+' => SubmoduleState::NewCommits,
+                'U' => SubmoduleState::Conflict,
+                _ => SubmoduleState::Current,
+            };
+            let mut parts = line[1..].splitn(3, ' ');
+            let sha = parts.next()?.to_string();
+            let path = parts.next()?.to_string();
+            let describe = parts
+                .next()
+                .map(|value| value.trim().trim_start_matches('(').trim_end_matches(')').to_string());
+            Some((state, sha, path, describe))
+        })
+        .collect()
+}
+
+/// Maps submodule paths to their `.gitmodules` names and URLs.
+fn configured_submodules(repo: &Path) -> HashMap<String, (String, Option<String>)> {
+    let raw = run_git(
+        repo,
+        &["config", "-f", ".gitmodules", "--get-regexp", r"^submodule\..*\.(path|url)$"],
+    )
+    .unwrap_or_default();
+
+    let mut names: HashMap<String, (Option<String>, Option<String>)> = HashMap::new();
+    for line in raw.lines() {
+        let Some((key, value)) = line.split_once(' ') else {
+            continue;
+        };
+        let Some(key) = key.strip_prefix("submodule.") else {
+            continue;
+        };
+        if let Some(name) = key.strip_suffix(".path") {
+            names.entry(name.to_string()).or_default().0 = Some(value.to_string());
+        } else if let Some(name) = key.strip_suffix(".url") {
+            names.entry(name.to_string()).or_default().1 = Some(value.to_string());
+        }
+    }
+
+    names
+        .into_iter()
+        .filter_map(|(name, (path, url))| Some((path?, (name, url))))
+        .collect()
+}
+
+/// Reads the `S<c><m><u>` submodule field of a porcelain v2 status record.
+pub(super) fn from_status_flags(flags: &str) -> Option<SubmoduleChange> {
+    let flags = flags.strip_prefix('S')?.as_bytes();
+    Some(SubmoduleChange {
+        new_commits: flags.first() == Some(&b'C'),
+        modified_content: flags.get(1) == Some(&b'M'),
+        untracked_content: flags.get(2) == Some(&b'U'),
+    })
+}
+
+/// Adds submodule details to status entries so a dirty submodule isn't shown as a plain
+/// modified file. Only runs when the repository has a `.gitmodules` file; the CLI status gets
+/// these from porcelain v2 directly.
+#[cfg(feature = "libgit2")]
+pub(super) fn annotate_changes(repo: &Path, changes: &mut [super::GitChange]) {
+    if !repo.join(".gitmodules").is_file() {
+        return;
+    }
+
+    let Ok(raw) = run_git(repo, &["submodule", "status"]) else {
+        return;
+    };
+    let states = parse_status(&raw)
+        .into_iter()
+        .map(|(state, _, path, _)| (path, state))
+        .collect::<HashMap<String, SubmoduleState>>();
+
+    for change in changes.iter_mut() {
+        let Some(state) = states.get(&change.path) else {
+            continue;
+        };
+
+        let inner = run_git(&repo.join(&change.path), &["status", "--porcelain"]).unwrap_or_default();
+        change.submodule = Some(SubmoduleChange {
+            new_commits: matches!(state, SubmoduleState::NewCommits),
+            modified_content: inner.lines().any(|line| !line.starts_with("??")),
+            untracked_content: inner.lines().any(|line| line.starts_with("??")),
+        });
+    }
+}
+
+#[tauri::command]
+pub fn git_submodules(repo_path: String, recursive: Option<bool>) -> Result<Vec<GitSubmodule>, AppError> {
+    let repo = PathBuf::from(repo_path);
+    let mut args = vec!["submodule", "status"];
+    if recursive.unwrap_or(false) {
+        args.push("--recursive");
+    }
+
+    let raw = run_git(&repo, &args)?;
+    let configured = configured_submodules(&repo);
+
+    Ok(parse_status(&raw)
+        .into_iter()
+        .map(|(state, sha, path, describe)| {
+            let (name, url) = configured
+                .get(&path)
+                .cloned()
+                .unwrap_or_else(|| (path.clone(), None));
+            GitSubmodule {
+                name,
+                path,
+                url,
+                sha,
+                describe,
+                state,
+            }
+        })
+        .collect())
+}
+
+#[tauri::command]
+pub async fn git_submodule_update(
+    repo_path: String,
+    init: Option<bool>,
+    recursive: Option<bool>,
+    paths: Option<Vec<String>>,
+    operation_id: Option<String>,
+    app: tauri::AppHandle,
+) -> Result<String, AppError> {
+    let repo = PathBuf::from(repo_path);
+    run_operation(app, operation_id, move |app, operation_id| {
+        let mut args = vec!["submodule", "update", "--progress"];
+        if init.unwrap_or(false) {
+            args.push("--init");
+        }
+        if recursive.unwrap_or(false) {
+            args.push("--recursive");
+        }
+        let paths = paths.unwrap_or_default();
+        if !paths.is_empty() {
+            args.push("--");
+            args.extend(paths.iter().map(String::as_str));
+        }
+
+        run_network_git(app, &repo, &args, operation_id)
+    })
+    .await
+}
@@ -0,0 +1,197 @@
+use super::{resolve_git_root, run_git, run_git_with_input, status_cache};
+use crate::error::AppError;
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use std::{
+    collections::{BTreeSet, HashMap},
+    path::{Path, PathBuf},
+    sync::{mpsc, Mutex},
+    time::Duration,
+};
+use tauri::{Emitter, Manager};
+
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GitStatusDirtyEvent {
+    repo_path: String,
+    worktree: bool,
+    git_dir: bool,
+}
+
+struct RepoWatcher {
+    // Dropping the watcher closes the event channel, which ends the debounce thread.
+    _watcher: RecommendedWatcher,
+}
+
+#[derive(Default)]
+pub struct GitWatcherState {
+    watchers: Mutex<HashMap<PathBuf, RepoWatcher>>,
+}
+
+/// Paths inside the git dir that change without affecting status, including the index lock
+/// taken by `git status` itself.
+fn is_noise(git_dir: &Path, path: &Path) -> bool {
+    let Ok(relative) = path.strip_prefix(git_dir) else {
+        return false;
+    };
+
+    relative.starts_with("objects")
+        || relative.starts_with("logs")
+        || relative.extension().is_some_and(|extension| extension == "lock")
+}
+
+/// Drops worktree paths matched by `.gitignore`, such as build output.
+fn tracked_candidates(repo: &Path, paths: BTreeSet<PathBuf>) -> Vec<PathBuf> {
+    let input = paths
+        .iter()
+        .filter_map(|path| path.strip_prefix(repo).ok())
+        .map(|path| path.to_string_lossy().to_string())
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    // check-ignore exits with 1 when nothing is ignored.
+    let ignored = run_git_with_input(repo, &["check-ignore", "--stdin"], &input).unwrap_or_default();
+    let ignored = ignored.lines().map(|line| repo.join(line.trim())).collect::<BTreeSet<PathBuf>>();
+
+    paths.into_iter().filter(|path| !ignored.contains(path)).collect()
+}
+
+fn debounce(app: tauri::AppHandle, repo: PathBuf, git_dir: PathBuf, events: mpsc::Receiver<notify::Event>) {
+    while let Ok(first) = events.recv() {
+        let mut batch = vec![first];
+        loop {
+            match events.recv_timeout(DEBOUNCE) {
+                Ok(event) => batch.push(event),
+                Err(mpsc::RecvTimeoutError::Timeout) => break,
+                Err(mpsc::RecvTimeoutError::Disconnected) => return,
+            }
+        }
+
+        let mut git_dir_changed = false;
+        let mut worktree_paths = BTreeSet::new();
+        for event in batch {
+            if matches!(event.kind, EventKind::Access(_)) {
+                continue;
+            }
+            for path in event.paths {
+                if path.starts_with(&git_dir) {
+                    git_dir_changed |= !is_noise(&git_dir, &path);
+                } else {
+                    worktree_paths.insert(path);
+                }
+            }
+        }
+
+        let worktree_changed = !worktree_paths.is_empty() && !tracked_candidates(&repo, worktree_paths).is_empty();
+        if !git_dir_changed && !worktree_changed {
+            continue;
+        }
+
+        // Drop the stale status first so a listener reloading right away gets fresh data.
+        status_cache::invalidate(&repo);
+        crate::workspace::invalidate(&app, &repo);
+        let _ = app.emit(
+            "git-status-dirty",
+            GitStatusDirtyEvent {
+                repo_path: repo.to_string_lossy().to_string(),
+                worktree: worktree_changed,
+                git_dir: git_dir_changed,
+            },
+        );
+    }
+}
+
+/// Starts watching the worktree and git dir of the repository containing `path`. Watching an
+/// already watched repository is a no-op. Returns the repository root.
+pub fn watch(app: &tauri::AppHandle, path: &Path) -> Result<PathBuf, AppError> {
+    let repo = resolve_git_root(path)?;
+    let state = app.state::<GitWatcherState>();
+    let mut watchers = state
+        .watchers
+        .lock()
+        .map_err(|_| "failed to lock git watcher".to_string())?;
+    if watchers.contains_key(&repo) {
+        return Ok(repo);
+    }
+
+    let git_dir = PathBuf::from(run_git(&repo, &["rev-parse", "--absolute-git-dir"])?.trim());
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(move |result: notify::Result<notify::Event>| {
+        if let Ok(event) = result {
+            let _ = sender.send(event);
+        }
+    })
+    .map_err(|error| format!("failed to create watcher: {error}"))?;
+
+    watcher
+        .watch(&repo, RecursiveMode::Recursive)
+        .map_err(|error| format!("failed to watch {}: {error}", repo.display()))?;
+    // Linked worktrees and separate git dirs live outside the worktree.
+    if !git_dir.starts_with(&repo) {
+        watcher
+            .watch(&git_dir, RecursiveMode::Recursive)
+            .map_err(|error| format!("failed to watch {}: {error}", git_dir.display()))?;
+    }
+
+    let thread_app = app.clone();
+    let thread_repo = repo.clone();
+    std::thread::spawn(move || debounce(thread_app, thread_repo, git_dir, receiver));
+
+    watchers.insert(repo.clone(), RepoWatcher { _watcher: watcher });
+    Ok(repo)
+}
+
+/// Marks every watched repository dirty, as if its files had changed, so views reload status.
+pub fn refresh_all(app: &tauri::AppHandle) -> Result<(), AppError> {
+    let repos = app
+        .state::<GitWatcherState>()
+        .watchers
+        .lock()
+        .map_err(|_| "failed to lock git watcher".to_string())?
+        .keys()
+        .cloned()
+        .collect::<Vec<_>>();
+
+    status_cache::clear();
+    for repo in repos {
+        let _ = app.emit(
+            "git-status-dirty",
+            GitStatusDirtyEvent {
+                repo_path: repo.to_string_lossy().to_string(),
+                worktree: true,
+                git_dir: true,
+            },
+        );
+        crate::workspace::invalidate(app, &repo);
+    }
+    Ok(())
+}
+
+pub fn unwatch(app: &tauri::AppHandle, repo: &Path) {
+    if let Ok(mut watchers) = app.state::<GitWatcherState>().watchers.lock() {
+        watchers.remove(repo);
+    }
+}
+
+#[tauri::command]
+pub fn git_watch(repo_path: String, app: tauri::AppHandle) -> Result<String, AppError> {
+    let repo = watch(&app, Path::new(&repo_path))?;
+    Ok(repo.to_string_lossy().to_string())
+}
+
+/// Stops watching one repository, or every repository when no path is given.
+#[tauri::command]
+pub fn git_unwatch(repo_path: Option<String>, app: tauri::AppHandle) -> Result<(), AppError> {
+    match repo_path {
+        Some(repo_path) => unwatch(&app, &resolve_git_root(Path::new(&repo_path))?),
+        None => app
+            .state::<GitWatcherState>()
+            .watchers
+            .lock()
+            .map_err(|_| "failed to lock git watcher".to_string())?
+            .clear(),
+    }
+    Ok(())
+}
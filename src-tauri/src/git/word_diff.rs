@@ -0,0 +1,158 @@
+use super::diff::{DiffFile, DiffLineKind, TokenRange};
+
+/// Token pairs beyond this size are left without intraline ranges to keep the LCS table small.
+const MAX_TABLE_CELLS: usize = 250_000;
+
+struct Token<'a> {
+    text: &'a str,
+    start: usize,
+    end: usize,
+}
+
+fn char_class(ch: char) -> u8 {
+    if ch.is_alphanumeric() || ch == '_' {
+        0
+    } else if ch.is_whitespace() {
+        1
+    } else {
+        2
+    }
+}
+
+/// Splits a line into word, whitespace and punctuation tokens. Offsets are UTF-16 code units so the
+/// frontend can use them directly with `String.prototype.slice`.
+fn tokenize(line: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut token_start_byte = 0;
+    let mut token_start = 0;
+    let mut offset = 0;
+    let mut previous_class: Option<u8> = None;
+
+    for (byte_index, ch) in line.char_indices() {
+        let class = char_class(ch);
+        let split = match previous_class {
+            Some(previous) => previous != class || class == 2,
+            None => false,
+        };
+
+        if split {
+            tokens.push(Token {
+                text: &line[token_start_byte..byte_index],
+                start: token_start,
+                end: offset,
+            });
+            token_start_byte = byte_index;
+            token_start = offset;
+        }
+
+        previous_class = Some(class);
+        offset += ch.len_utf16();
+    }
+
+    if token_start_byte < line.len() {
+        tokens.push(Token {
+            text: &line[token_start_byte..],
+            start: token_start,
+            end: offset,
+        });
+    }
+
+    tokens
+}
+
+fn push_range(ranges: &mut Vec<TokenRange>, token: &Token) {
+    match ranges.last_mut() {
+        Some(last) if last.end == token.start => last.end = token.end,
+        _ => ranges.push(TokenRange {
+            start: token.start,
+            end: token.end,
+        }),
+    }
+}
+
+/// Returns the changed token ranges of the old and new line using a longest common subsequence.
+fn diff_tokens(old: &str, new: &str) -> Option<(Vec<TokenRange>, Vec<TokenRange>)> {
+    let old_tokens = tokenize(old);
+    let new_tokens = tokenize(new);
+    let rows = old_tokens.len() + 1;
+    let cols = new_tokens.len() + 1;
+
+    if rows * cols > MAX_TABLE_CELLS {
+        return None;
+    }
+
+    let mut table = vec![0_u32; rows * cols];
+    for i in (0..old_tokens.len()).rev() {
+        for j in (0..new_tokens.len()).rev() {
+            table[i * cols + j] = if old_tokens[i].text == new_tokens[j].text {
+                table[(i + 1) * cols + j + 1] + 1
+            } else {
+                table[(i + 1) * cols + j].max(table[i * cols + j + 1])
+            };
+        }
+    }
+
+    let mut old_ranges = Vec::new();
+    let mut new_ranges = Vec::new();
+    let (mut i, mut j) = (0, 0);
+
+    while i < old_tokens.len() && j < new_tokens.len() {
+        if old_tokens[i].text == new_tokens[j].text {
+            i += 1;
+            j += 1;
+        } else if table[(i + 1) * cols + j] >= table[i * cols + j + 1] {
+            push_range(&mut old_ranges, &old_tokens[i]);
+            i += 1;
+        } else {
+            push_range(&mut new_ranges, &new_tokens[j]);
+            j += 1;
+        }
+    }
+    for token in &old_tokens[i..] {
+        push_range(&mut old_ranges, token);
+    }
+    for token in &new_tokens[j..] {
+        push_range(&mut new_ranges, token);
+    }
+
+    Some((old_ranges, new_ranges))
+}
+
+/// Pairs each block of removed lines with the added lines that directly follow it and fills in
+/// the intraline change ranges for every pair.
+pub fn annotate_word_changes(files: &mut [DiffFile]) {
+    for file in files.iter_mut() {
+        for hunk in file.hunks.iter_mut() {
+            let lines = &mut hunk.lines;
+            let mut index = 0;
+
+            while index < lines.len() {
+                if lines[index].kind != DiffLineKind::Removed {
+                    index += 1;
+                    continue;
+                }
+
+                let removed_start = index;
+                while index < lines.len() && lines[index].kind == DiffLineKind::Removed {
+                    index += 1;
+                }
+                let added_start = index;
+                while index < lines.len() && lines[index].kind == DiffLineKind::Added {
+                    index += 1;
+                }
+
+                let pairs = (added_start - removed_start).min(index - added_start);
+                for offset in 0..pairs {
+                    let old_index = removed_start + offset;
+                    let new_index = added_start + offset;
+                    if let Some((old_ranges, new_ranges)) =
+                        diff_tokens(&lines[old_index].content, &lines[new_index].content)
+                    {
+                        lines[old_index].changes = old_ranges;
+                        lines[new_index].changes = new_ranges;
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use super::{log::validate_revision, run_git, run_git_mutating, validate_branch_name};
+use crate::error::AppError;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+#[derive(Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GitWorktree {
+    path: String,
+    head: Option<String>,
+    branch: Option<String>,
+    detached: bool,
+    bare: bool,
+    locked: bool,
+    prunable: bool,
+    /// The worktree the command was run from.
+    current: bool,
+}
+
+/// Parses `git worktree list --porcelain`, where each worktree is a block of attribute lines.
+fn parse_worktrees(raw: &str) -> Vec<GitWorktree> {
+    let mut worktrees: Vec<GitWorktree> = Vec::new();
+
+    for line in raw.lines() {
+        let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+        if key == "worktree" {
+            worktrees.push(GitWorktree {
+                path: value.to_string(),
+                ..GitWorktree::default()
+            });
+            continue;
+        }
+
+        let Some(worktree) = worktrees.last_mut() else {
+            continue;
+        };
+        match key {
+            "HEAD" => worktree.head = Some(value.to_string()),
+            "branch" => worktree.branch = Some(value.trim_start_matches("refs/heads/").to_string()),
+            "detached" => worktree.detached = true,
+            "bare" => worktree.bare = true,
+            "locked" => worktree.locked = true,
+            "prunable" => worktree.prunable = true,
+            _ => {}
+        }
+    }
+
+    worktrees
+}
+
+fn list_worktrees(repo: &Path) -> Result<Vec<GitWorktree>, AppError> {
+    let raw = run_git(repo, &["worktree", "list", "--porcelain"])?;
+    let current = run_git(repo, &["rev-parse", "--show-toplevel"])
+        .map(|root| PathBuf::from(root.trim()))
+        .ok();
+
+    let mut worktrees = parse_worktrees(&raw);
+    for worktree in worktrees.iter_mut() {
+        worktree.current = current.as_deref() == Some(PathBuf::from(&worktree.path).as_path());
+    }
+    Ok(worktrees)
+}
+
+#[tauri::command]
+pub fn git_worktrees(repo_path: String) -> Result<Vec<GitWorktree>, AppError> {
+    let repo = PathBuf::from(repo_path);
+    list_worktrees(&repo)
+}
+
+/// Adds a worktree at `path`. With `create_branch` a new branch is created from `base` (or HEAD),
+/// otherwise the existing `branch` is checked out.
+#[tauri::command]
+pub fn git_worktree_add(
+    repo_path: String,
+    path: String,
+    branch: String,
+    create_branch: Option<bool>,
+    base: Option<String>,
+) -> Result<GitWorktree, AppError> {
+    let repo = PathBuf::from(repo_path);
+    let path = path.trim();
+    if path.is_empty() || path.starts_with('-') {
+        return Err(AppError::invalid(format!("invalid worktree path: {path}")));
+    }
+    let branch = validate_branch_name(&repo, branch.trim())?;
+
+    let mut args = vec!["worktree", "add"];
+    if create_branch.unwrap_or(false) {
+        args.extend(["-b", branch.as_str(), path]);
+        if let Some(base) = base.as_deref() {
+            args.push(validate_revision(base)?);
+        }
+    } else {
+        args.extend([path, branch.as_str()]);
+    }
+    run_git_mutating(&repo, &args)?;
+
+    let target = std::fs::canonicalize(repo.join(path)).map_err(|error| format!("failed to resolve {path}: {error}"))?;
+    list_worktrees(&repo)?
+        .into_iter()
+        .find(|worktree| std::fs::canonicalize(&worktree.path).ok().as_ref() == Some(&target))
+        .ok_or_else(|| AppError::from(format!("worktree not found after add: {path}")))
+}
+
+#[tauri::command]
+pub fn git_worktree_remove(repo_path: String, path: String, force: Option<bool>) -> Result<(), AppError> {
+    let repo = PathBuf::from(repo_path);
+    let mut args = vec!["worktree", "remove"];
+    if force.unwrap_or(false) {
+        args.push("--force");
+    }
+    args.push("--");
+    args.push(path.as_str());
+    run_git_mutating(&repo, &args).map(|_| ())
+}
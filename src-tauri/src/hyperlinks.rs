@@ -0,0 +1,52 @@
+use crate::{error::AppError, terminal::TerminalState};
+use tauri_plugin_opener::OpenerExt;
+
+/// Schemes opened from terminal output; anything else could launch arbitrary handlers.
+const OPENABLE_SCHEMES: &[&str] = &["http", "https", "mailto", "ftp", "file"];
+
+fn lookup(state: &TerminalState, tab_id: &str, id: &str) -> Result<Option<String>, AppError> {
+    let sessions = state
+        .sessions
+        .lock()
+        .map_err(|_| "failed to lock terminal sessions".to_string())?;
+
+    let session = sessions
+        .get(tab_id)
+        .ok_or_else(|| AppError::session_not_found(tab_id))?;
+    let reported = session
+        .reported
+        .lock()
+        .map_err(|_| "failed to lock session state".to_string())?;
+    Ok(reported.hyperlinks.resolve(id))
+}
+
+/// Returns the URI an OSC 8 hyperlink id points to in a session.
+#[tauri::command]
+pub fn resolve_hyperlink(
+    tab_id: String,
+    id: String,
+    state: tauri::State<TerminalState>,
+) -> Result<Option<String>, AppError> {
+    lookup(&state, &tab_id, &id)
+}
+
+#[tauri::command]
+pub fn open_hyperlink(
+    tab_id: String,
+    id: String,
+    app: tauri::AppHandle,
+    state: tauri::State<TerminalState>,
+) -> Result<String, AppError> {
+    let uri = lookup(&state, &tab_id, &id)?
+        .ok_or_else(|| AppError::invalid(format!("hyperlink not found: {id}")))?;
+
+    let scheme = uri.split_once(':').map(|(scheme, _)| scheme.to_ascii_lowercase());
+    if !scheme.is_some_and(|scheme| OPENABLE_SCHEMES.contains(&scheme.as_str())) {
+        return Err(AppError::invalid(format!("refusing to open link: {uri}")));
+    }
+
+    app.opener()
+        .open_url(uri.as_str(), None::<&str>)
+        .map_err(|error| format!("failed to open link: {error}"))?;
+    Ok(uri)
+}
@@ -0,0 +1,697 @@
+use crate::{
+    error::AppError,
+    sixel,
+    terminal::{self, OutputObserver, TerminalState},
+    windows,
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::Serialize;
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+};
+use tauri::Manager;
+
+/// Largest escape sequence buffered for a single image; longer ones are dropped.
+const MAX_SEQUENCE_BYTES: usize = 32 * 1024 * 1024;
+/// Decoded image data kept across all sessions before the oldest images are forgotten.
+const MAX_STORED_BYTES: usize = 256 * 1024 * 1024;
+/// MIME type of decoded pixels: `width * height` RGBA quadruplets.
+const RGBA_MIME: &str = "image/x-rgba";
+
+struct StoredImage {
+    tab_id: String,
+    mime: String,
+    width: Option<u32>,
+    height: Option<u32>,
+    data: Vec<u8>,
+}
+
+#[derive(Default)]
+struct ImageStore {
+    images: HashMap<String, StoredImage>,
+    order: VecDeque<String>,
+    bytes: usize,
+}
+
+impl ImageStore {
+    fn insert(&mut self, id: String, image: StoredImage) {
+        self.bytes += image.data.len();
+        self.images.insert(id.clone(), image);
+        self.order.push_back(id);
+
+        while self.bytes > MAX_STORED_BYTES {
+            let Some(oldest) = self.order.pop_front() else {
+                break;
+            };
+            if let Some(image) = self.images.remove(&oldest) {
+                self.bytes -= image.data.len();
+            }
+        }
+    }
+
+    fn remove(&mut self, id: &str) {
+        if let Some(image) = self.images.remove(id) {
+            self.bytes -= image.data.len();
+            self.order.retain(|existing| existing != id);
+        }
+    }
+}
+
+/// Images decoded from terminal output, fetched by the frontend through `get_terminal_image`.
+#[derive(Default)]
+pub struct ImageState {
+    store: Mutex<ImageStore>,
+    next_id: AtomicU64,
+}
+
+impl ImageState {
+    fn insert(&self, image: StoredImage) -> String {
+        let id = format!("img-{}", self.next_id.fetch_add(1, Ordering::Relaxed) + 1);
+        if let Ok(mut store) = self.store.lock() {
+            store.insert(id.clone(), image);
+        }
+        id
+    }
+
+    fn remove(&self, id: &str) {
+        if let Ok(mut store) = self.store.lock() {
+            store.remove(id);
+        }
+    }
+}
+
+/// Drops every image decoded from `tab_id`'s output.
+pub fn forget_tab(app: &tauri::AppHandle, tab_id: &str) {
+    let state = app.state::<ImageState>();
+    let Ok(mut store) = state.store.lock() else {
+        return;
+    };
+    let ids = store
+        .images
+        .iter()
+        .filter(|(_, image)| image.tab_id == tab_id)
+        .map(|(id, _)| id.clone())
+        .collect::<Vec<_>>();
+    for id in ids {
+        store.remove(&id);
+    }
+}
+
+/// Where and how an image should be shown, as requested by the sequence that carried it.
+#[derive(Clone, Serialize)]
+#[serde(tag = "protocol", rename_all = "camelCase")]
+enum ImagePlacement {
+    Sixel,
+    /// iTerm2 sizes are passed through as written: `N` cells, `Npx`, `N%` or `auto`.
+    #[serde(rename_all = "camelCase")]
+    Iterm {
+        name: Option<String>,
+        width: Option<String>,
+        height: Option<String>,
+        preserve_aspect_ratio: bool,
+    },
+    #[serde(rename_all = "camelCase")]
+    Kitty {
+        kitty_id: Option<u32>,
+        placement_id: Option<u32>,
+        columns: Option<u32>,
+        rows: Option<u32>,
+        source_x: Option<u32>,
+        source_y: Option<u32>,
+        source_width: Option<u32>,
+        source_height: Option<u32>,
+        cell_x_offset: Option<u32>,
+        cell_y_offset: Option<u32>,
+        z_index: Option<i32>,
+        move_cursor: bool,
+    },
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TerminalImageEvent {
+    tab_id: String,
+    image_id: String,
+    mime: String,
+    width: Option<u32>,
+    height: Option<u32>,
+    placement: ImagePlacement,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TerminalImageDeletedEvent {
+    tab_id: String,
+    /// `None` clears every image in the tab.
+    image_id: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TerminalImage {
+    id: String,
+    mime: String,
+    width: Option<u32>,
+    height: Option<u32>,
+    /// Base64 encoded image file, or raw RGBA pixels for `image/x-rgba`.
+    data: String,
+}
+
+/// Identifies common image formats and reads their pixel size from the header.
+pub(crate) fn sniff(data: &[u8]) -> (&'static str, Option<(u32, u32)>) {
+    let be = |bytes: &[u8]| bytes.iter().fold(0_u32, |value, &byte| (value << 8) | u32::from(byte));
+
+    if data.starts_with(b"\x89PNG\r\n\x1a\n") && data.len() >= 24 {
+        return ("image/png", Some((be(&data[16..20]), be(&data[20..24]))));
+    }
+    if (data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a")) && data.len() >= 10 {
+        let width = u32::from(u16::from_le_bytes([data[6], data[7]]));
+        let height = u32::from(u16::from_le_bytes([data[8], data[9]]));
+        return ("image/gif", Some((width, height)));
+    }
+    if data.starts_with(&[0xff, 0xd8]) {
+        // Walk the segments up to the first start-of-frame marker.
+        let mut index = 2;
+        while index + 9 < data.len() && data[index] == 0xff {
+            let marker = data[index + 1];
+            if matches!(marker, 0xc0..=0xcf) && !matches!(marker, 0xc4 | 0xc8 | 0xcc) {
+                let height = be(&data[index + 5..index + 7]);
+                let width = be(&data[index + 7..index + 9]);
+                return ("image/jpeg", Some((width, height)));
+            }
+            index += 2 + be(&data[index + 2..index + 4]) as usize;
+        }
+        return ("image/jpeg", None);
+    }
+    if data.len() >= 12 && &data[..4] == b"RIFF" && &data[8..12] == b"WEBP" {
+        return ("image/webp", None);
+    }
+    if data.starts_with(b"BM") {
+        return ("image/bmp", None);
+    }
+    ("application/octet-stream", None)
+}
+
+fn decode_base64(payload: &[u8]) -> Option<Vec<u8>> {
+    let compact = payload
+        .iter()
+        .copied()
+        .filter(|byte| !byte.is_ascii_whitespace())
+        .collect::<Vec<_>>();
+    STANDARD.decode(compact).ok()
+}
+
+/// Parsed `ESC _ G` control data. Keys missing from the sequence keep kitty's defaults.
+struct KittyCommand {
+    action: u8,
+    format: u32,
+    medium: u8,
+    compressed: bool,
+    pixel_width: Option<u32>,
+    pixel_height: Option<u32>,
+    id: Option<u32>,
+    placement_id: Option<u32>,
+    more: bool,
+    quiet: u32,
+    columns: Option<u32>,
+    rows: Option<u32>,
+    source_x: Option<u32>,
+    source_y: Option<u32>,
+    source_width: Option<u32>,
+    source_height: Option<u32>,
+    cell_x_offset: Option<u32>,
+    cell_y_offset: Option<u32>,
+    z_index: Option<i32>,
+    move_cursor: bool,
+    delete: u8,
+    payload: Vec<u8>,
+}
+
+impl KittyCommand {
+    fn parse(control: &[u8]) -> Self {
+        let mut command = Self {
+            action: b't',
+            format: 32,
+            medium: b'd',
+            compressed: false,
+            pixel_width: None,
+            pixel_height: None,
+            id: None,
+            placement_id: None,
+            more: false,
+            quiet: 0,
+            columns: None,
+            rows: None,
+            source_x: None,
+            source_y: None,
+            source_width: None,
+            source_height: None,
+            cell_x_offset: None,
+            cell_y_offset: None,
+            z_index: None,
+            move_cursor: true,
+            delete: b'a',
+            payload: Vec::new(),
+        };
+
+        for pair in String::from_utf8_lossy(control).split(',') {
+            let Some((key, value)) = pair.split_once('=') else {
+                continue;
+            };
+            let number = value.parse::<u32>().ok();
+            let letter = value.bytes().next();
+            match key {
+                "a" => command.action = letter.unwrap_or(b't'),
+                "f" => command.format = number.unwrap_or(32),
+                "t" => command.medium = letter.unwrap_or(b'd'),
+                "o" => command.compressed = true,
+                "s" => command.pixel_width = number,
+                "v" => command.pixel_height = number,
+                "i" => command.id = number,
+                "p" => command.placement_id = number,
+                "m" => command.more = number == Some(1),
+                "q" => command.quiet = number.unwrap_or(0),
+                "c" => command.columns = number,
+                "r" => command.rows = number,
+                "x" => command.source_x = number,
+                "y" => command.source_y = number,
+                "w" => command.source_width = number,
+                "h" => command.source_height = number,
+                "X" => command.cell_x_offset = number,
+                "Y" => command.cell_y_offset = number,
+                "z" => command.z_index = value.parse().ok(),
+                "C" => command.move_cursor = number != Some(1),
+                "d" => command.delete = letter.unwrap_or(b'a'),
+                _ => {}
+            }
+        }
+        command
+    }
+
+    fn placement(&self) -> ImagePlacement {
+        ImagePlacement::Kitty {
+            kitty_id: self.id,
+            placement_id: self.placement_id,
+            columns: self.columns,
+            rows: self.rows,
+            source_x: self.source_x,
+            source_y: self.source_y,
+            source_width: self.source_width,
+            source_height: self.source_height,
+            cell_x_offset: self.cell_x_offset,
+            cell_y_offset: self.cell_y_offset,
+            z_index: self.z_index,
+            move_cursor: self.move_cursor,
+        }
+    }
+
+    /// Decodes the transmitted image. Errors use kitty's `CODE:message` reply format.
+    fn decode(&self, tab_id: &str) -> Result<StoredImage, String> {
+        if self.medium != b'd' {
+            return Err("EINVAL:only direct transmission is supported".to_string());
+        }
+        if self.compressed {
+            return Err("EINVAL:compressed data is not supported".to_string());
+        }
+        let data = decode_base64(&self.payload).ok_or_else(|| "EINVAL:invalid base64 data".to_string())?;
+
+        let (mime, size, data) = match self.format {
+            100 => match sniff(&data) {
+                ("image/png", size) => ("image/png", size, data),
+                _ => return Err("EBADPNG:not a PNG image".to_string()),
+            },
+            24 | 32 => {
+                let (Some(width), Some(height)) = (self.pixel_width, self.pixel_height) else {
+                    return Err("EINVAL:missing image dimensions".to_string());
+                };
+                let channels = if self.format == 24 { 3 } else { 4 };
+                if data.len() != width as usize * height as usize * channels {
+                    return Err("ENODATA:image data does not match its dimensions".to_string());
+                }
+                let rgba = if channels == 3 {
+                    data.chunks_exact(3)
+                        .flat_map(|pixel| [pixel[0], pixel[1], pixel[2], 255])
+                        .collect()
+                } else {
+                    data
+                };
+                (RGBA_MIME, Some((width, height)), rgba)
+            }
+            format => return Err(format!("EINVAL:unsupported format {format}")),
+        };
+
+        Ok(StoredImage {
+            tab_id: tab_id.to_string(),
+            mime: mime.to_string(),
+            width: size.map(|(width, _)| width),
+            height: size.map(|(_, height)| height),
+            data,
+        })
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum SequenceKind {
+    Dcs,
+    Osc,
+    Apc,
+}
+
+#[derive(Clone, Copy, Default)]
+enum ScanState {
+    #[default]
+    Ground,
+    Escape,
+    Sequence(SequenceKind),
+    SequenceEscape(SequenceKind),
+}
+
+/// Picks sixel (DCS `q`), iTerm2 (OSC 1337 `File=`) and kitty (APC `G`) images out of the
+/// output, stores them in `ImageState` and announces them with `terminal-image` events.
+#[derive(Default)]
+pub struct ImageScanner {
+    state: ScanState,
+    payload: Vec<u8>,
+    /// Set once the current sequence is too long or not an image, so the rest is skipped.
+    skipping: bool,
+    /// Kitty image ids chosen by the application, mapped to stored image ids.
+    kitty_images: HashMap<u32, String>,
+    /// A kitty transmission still waiting for chunks sent with `m=1`.
+    kitty_pending: Option<KittyCommand>,
+}
+
+impl ImageScanner {
+    fn begin(&mut self, byte: u8) -> ScanState {
+        let kind = match byte {
+            0x1b => return ScanState::Escape,
+            b'P' => SequenceKind::Dcs,
+            b']' => SequenceKind::Osc,
+            b'_' => SequenceKind::Apc,
+            _ => return ScanState::Ground,
+        };
+        self.payload.clear();
+        self.skipping = false;
+        ScanState::Sequence(kind)
+    }
+
+    fn push(&mut self, kind: SequenceKind, byte: u8) {
+        if self.skipping {
+            return;
+        }
+        if self.payload.len() >= MAX_SEQUENCE_BYTES {
+            self.skip();
+            return;
+        }
+        self.payload.push(byte);
+
+        let wanted = match kind {
+            SequenceKind::Dcs => true,
+            SequenceKind::Osc => b"1337;File=".starts_with(&self.payload) || self.payload.starts_with(b"1337;File="),
+            SequenceKind::Apc => self.payload[0] == b'G',
+        };
+        if !wanted {
+            self.skip();
+        }
+    }
+
+    fn skip(&mut self) {
+        self.skipping = true;
+        self.payload = Vec::new();
+    }
+
+    fn dispatch(&mut self, app: &tauri::AppHandle, tab_id: &str, kind: SequenceKind) {
+        if self.skipping {
+            return;
+        }
+        let payload = std::mem::take(&mut self.payload);
+        match kind {
+            SequenceKind::Dcs => self.dispatch_sixel(app, tab_id, &payload),
+            SequenceKind::Osc => {
+                if let Some(file) = payload.strip_prefix(b"1337;File=") {
+                    self.dispatch_iterm(app, tab_id, file);
+                }
+            }
+            SequenceKind::Apc => {
+                if let Some(graphics) = payload.strip_prefix(b"G") {
+                    self.dispatch_kitty(app, tab_id, graphics);
+                }
+            }
+        }
+    }
+
+    fn dispatch_sixel(&mut self, app: &tauri::AppHandle, tab_id: &str, payload: &[u8]) {
+        let Some(split) = payload.iter().position(|byte| !(byte.is_ascii_digit() || *byte == b';')) else {
+            return;
+        };
+        if payload[split] != b'q' {
+            return;
+        }
+        let params = String::from_utf8_lossy(&payload[..split]);
+        let Some(image) = sixel::decode(&params, &payload[split + 1..]) else {
+            return;
+        };
+
+        let stored = StoredImage {
+            tab_id: tab_id.to_string(),
+            mime: RGBA_MIME.to_string(),
+            width: Some(image.width),
+            height: Some(image.height),
+            data: image.rgba,
+        };
+        let id = app.state::<ImageState>().insert(stored);
+        emit_image(app, tab_id, &id, ImagePlacement::Sixel);
+    }
+
+    /// Handles `OSC 1337 ; File = [args] : base64`. Non-inline files are downloads, not images.
+    fn dispatch_iterm(&mut self, app: &tauri::AppHandle, tab_id: &str, payload: &[u8]) {
+        let Some(split) = payload.iter().position(|byte| *byte == b':') else {
+            return;
+        };
+        let args = String::from_utf8_lossy(&payload[..split]);
+        let args = args
+            .split(';')
+            .filter_map(|arg| arg.split_once('='))
+            .collect::<HashMap<_, _>>();
+        if args.get("inline") != Some(&"1") {
+            return;
+        }
+        let Some(data) = decode_base64(&payload[split + 1..]) else {
+            return;
+        };
+
+        let name = args
+            .get("name")
+            .and_then(|name| STANDARD.decode(name).ok())
+            .map(|name| String::from_utf8_lossy(&name).to_string());
+        let (mime, size) = sniff(&data);
+        let stored = StoredImage {
+            tab_id: tab_id.to_string(),
+            mime: mime.to_string(),
+            width: size.map(|(width, _)| width),
+            height: size.map(|(_, height)| height),
+            data,
+        };
+        let id = app.state::<ImageState>().insert(stored);
+        emit_image(
+            app,
+            tab_id,
+            &id,
+            ImagePlacement::Iterm {
+                name,
+                width: args.get("width").map(|width| width.to_string()),
+                height: args.get("height").map(|height| height.to_string()),
+                preserve_aspect_ratio: args.get("preserveAspectRatio") != Some(&"0"),
+            },
+        );
+    }
+
+    fn dispatch_kitty(&mut self, app: &tauri::AppHandle, tab_id: &str, payload: &[u8]) {
+        let split = payload.iter().position(|byte| *byte == b';').unwrap_or(payload.len());
+        let mut command = KittyCommand::parse(&payload[..split]);
+        let data = payload.get(split + 1..).unwrap_or_default();
+
+        // Follow-up chunks only carry `m`; the first chunk's keys apply to the whole image.
+        let command = match self.kitty_pending.take() {
+            Some(mut pending) => {
+                pending.payload.extend_from_slice(data);
+                if pending.payload.len() > MAX_SEQUENCE_BYTES {
+                    return;
+                }
+                if command.more {
+                    self.kitty_pending = Some(pending);
+                    return;
+                }
+                pending
+            }
+            None => {
+                command.payload = data.to_vec();
+                if command.more && matches!(command.action, b't' | b'T' | b'q') {
+                    self.kitty_pending = Some(command);
+                    return;
+                }
+                command
+            }
+        };
+
+        match command.action {
+            b't' | b'T' | b'q' => match command.decode(tab_id) {
+                Ok(_) if command.action == b'q' => reply_kitty(app, tab_id, &command, Ok(())),
+                Ok(image) => {
+                    let state = app.state::<ImageState>();
+                    let id = state.insert(image);
+                    if let Some(kitty_id) = command.id {
+                        if let Some(previous) = self.kitty_images.insert(kitty_id, id.clone()) {
+                            state.remove(&previous);
+                        }
+                    }
+                    reply_kitty(app, tab_id, &command, Ok(()));
+                    if command.action == b'T' {
+                        emit_image(app, tab_id, &id, command.placement());
+                    }
+                }
+                Err(error) => reply_kitty(app, tab_id, &command, Err(error)),
+            },
+            b'p' => match command.id.and_then(|kitty_id| self.kitty_images.get(&kitty_id)) {
+                Some(id) => {
+                    let id = id.clone();
+                    reply_kitty(app, tab_id, &command, Ok(()));
+                    emit_image(app, tab_id, &id, command.placement());
+                }
+                None => reply_kitty(app, tab_id, &command, Err("ENOENT:image not found".to_string())),
+            },
+            b'd' => self.delete_kitty(app, tab_id, &command),
+            _ => {}
+        }
+    }
+
+    /// Supports deleting everything (`d=a`) or one image (`d=i`); the uppercase forms also
+    /// free the stored data.
+    fn delete_kitty(&mut self, app: &tauri::AppHandle, tab_id: &str, command: &KittyCommand) {
+        let free = command.delete.is_ascii_uppercase();
+        match command.delete.to_ascii_lowercase() {
+            b'a' => {
+                emit_deleted(app, tab_id, None);
+                if free {
+                    let state = app.state::<ImageState>();
+                    for (_, id) in self.kitty_images.drain() {
+                        state.remove(&id);
+                    }
+                }
+            }
+            b'i' => {
+                let Some(kitty_id) = command.id else {
+                    return;
+                };
+                let Some(id) = self.kitty_images.get(&kitty_id).cloned() else {
+                    return;
+                };
+                emit_deleted(app, tab_id, Some(id.clone()));
+                if free {
+                    self.kitty_images.remove(&kitty_id);
+                    app.state::<ImageState>().remove(&id);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn emit_image(app: &tauri::AppHandle, tab_id: &str, image_id: &str, placement: ImagePlacement) {
+    let state = app.state::<ImageState>();
+    let Ok(store) = state.store.lock() else {
+        return;
+    };
+    let Some(image) = store.images.get(image_id) else {
+        return;
+    };
+    let event = TerminalImageEvent {
+        tab_id: tab_id.to_string(),
+        image_id: image_id.to_string(),
+        mime: image.mime.clone(),
+        width: image.width,
+        height: image.height,
+        placement,
+    };
+    drop(store);
+    windows::emit(app, tab_id, "terminal-image", event);
+}
+
+fn emit_deleted(app: &tauri::AppHandle, tab_id: &str, image_id: Option<String>) {
+    windows::emit(
+        app,
+        tab_id,
+        "terminal-image-deleted",
+        TerminalImageDeletedEvent {
+            tab_id: tab_id.to_string(),
+            image_id,
+        },
+    );
+}
+
+/// Answers a kitty command on the PTY. Commands without an id get no reply, and `q=1` / `q=2`
+/// silence the OK and error replies respectively.
+fn reply_kitty(app: &tauri::AppHandle, tab_id: &str, command: &KittyCommand, result: Result<(), String>) {
+    let Some(id) = command.id else {
+        return;
+    };
+    let message = match result {
+        Ok(()) if command.quiet == 0 => "OK".to_string(),
+        Err(error) if command.quiet < 2 => error,
+        _ => return,
+    };
+
+    let mut keys = format!("i={id}");
+    if let Some(placement_id) = command.placement_id {
+        keys.push_str(&format!(",p={placement_id}"));
+    }
+    let reply = format!("\x1b_G{keys};{message}\x1b\\");
+    let _ = terminal::write_session(&app.state::<TerminalState>(), tab_id, reply.as_bytes());
+}
+
+impl OutputObserver for ImageScanner {
+    fn observe(&mut self, app: &tauri::AppHandle, tab_id: &str, bytes: &[u8]) {
+        for &byte in bytes {
+            self.state = match (self.state, byte) {
+                (ScanState::Ground, 0x1b) => ScanState::Escape,
+                (ScanState::Ground, _) => ScanState::Ground,
+                (ScanState::Escape, _) => self.begin(byte),
+                (ScanState::Sequence(SequenceKind::Osc), 0x07) => {
+                    self.dispatch(app, tab_id, SequenceKind::Osc);
+                    ScanState::Ground
+                }
+                (ScanState::Sequence(kind), 0x1b) => ScanState::SequenceEscape(kind),
+                (ScanState::Sequence(kind), _) => {
+                    self.push(kind, byte);
+                    ScanState::Sequence(kind)
+                }
+                (ScanState::SequenceEscape(kind), b'\\') => {
+                    self.dispatch(app, tab_id, kind);
+                    ScanState::Ground
+                }
+                // Any other escape cancels the sequence and starts a new one.
+                (ScanState::SequenceEscape(_), _) => self.begin(byte),
+            };
+        }
+    }
+}
+
+#[tauri::command]
+pub fn get_terminal_image(image_id: String, state: tauri::State<ImageState>) -> Result<TerminalImage, AppError> {
+    let store = state.store.lock().map_err(|_| "failed to lock terminal images".to_string())?;
+    let image = store
+        .images
+        .get(&image_id)
+        .ok_or_else(|| format!("terminal image not found: {image_id}"))?;
+
+    Ok(TerminalImage {
+        id: image_id,
+        mime: image.mime.clone(),
+        width: image.width,
+        height: image.height,
+        data: STANDARD.encode(&image.data),
+    })
+}
@@ -0,0 +1,397 @@
+use crate::error::AppError;
+use serde::{Deserialize, Serialize};
+use std::{path::PathBuf, sync::Mutex};
+use tauri::Manager;
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SplitDirection {
+    /// Children side by side.
+    Horizontal,
+    /// Children stacked top to bottom.
+    Vertical,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum LayoutNode {
+    Pane {
+        #[serde(rename = "tabId")]
+        tab_id: String,
+    },
+    Split {
+        direction: SplitDirection,
+        children: Vec<LayoutNode>,
+        /// Relative sizes of `children`, summing to 1.
+        sizes: Vec<f32>,
+    },
+}
+
+/// A set of panes shown together, e.g. one tab of a window with its splits.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaneGroup {
+    pub id: String,
+    pub window: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub root: Option<LayoutNode>,
+}
+
+/// Pane groups of all windows. `None` until loaded from disk.
+#[derive(Default)]
+pub struct LayoutState {
+    groups: Mutex<Option<Vec<PaneGroup>>>,
+}
+
+impl LayoutNode {
+    fn contains(&self, tab_id: &str) -> bool {
+        match self {
+            LayoutNode::Pane { tab_id: id } => id == tab_id,
+            LayoutNode::Split { children, .. } => children.iter().any(|child| child.contains(tab_id)),
+        }
+    }
+
+    fn tab_ids(&self, ids: &mut Vec<String>) {
+        match self {
+            LayoutNode::Pane { tab_id } => ids.push(tab_id.clone()),
+            LayoutNode::Split { children, .. } => children.iter().for_each(|child| child.tab_ids(ids)),
+        }
+    }
+
+    /// Puts `tab_id` next to `target`, reusing the parent split when it already runs in
+    /// `direction`. Returns false when `target` isn't in this subtree.
+    fn insert_next_to(&mut self, target: &str, tab_id: &str, direction: SplitDirection, before: bool) -> bool {
+        let pane = LayoutNode::Pane {
+            tab_id: tab_id.to_string(),
+        };
+
+        match self {
+            LayoutNode::Pane { tab_id: id } if id == target => {
+                let existing = LayoutNode::Pane { tab_id: id.clone() };
+                let children = if before { vec![pane, existing] } else { vec![existing, pane] };
+                *self = LayoutNode::Split {
+                    direction,
+                    children,
+                    sizes: vec![0.5, 0.5],
+                };
+                true
+            }
+            LayoutNode::Pane { .. } => false,
+            LayoutNode::Split {
+                direction: split_direction,
+                children,
+                sizes,
+            } => {
+                let direct = children
+                    .iter()
+                    .position(|child| matches!(child, LayoutNode::Pane { tab_id: id } if id == target));
+                if let (Some(index), true) = (direct, *split_direction == direction) {
+                    children.insert(if before { index } else { index + 1 }, pane);
+                    *sizes = vec![1.0 / children.len() as f32; children.len()];
+                    return true;
+                }
+                children
+                    .iter_mut()
+                    .any(|child| child.insert_next_to(target, tab_id, direction, before))
+            }
+        }
+    }
+
+    /// Removes `tab_id` and collapses splits left with a single child. Returns `None` when the
+    /// whole subtree is gone.
+    fn remove(self, tab_id: &str) -> Option<LayoutNode> {
+        match self {
+            LayoutNode::Pane { tab_id: id } if id == tab_id => None,
+            LayoutNode::Pane { .. } => Some(self),
+            LayoutNode::Split {
+                direction,
+                children,
+                sizes,
+            } => {
+                let before = children.len();
+                let mut kept_sizes = Vec::new();
+                let mut kept = Vec::new();
+                for (child, size) in children.into_iter().zip(sizes.into_iter().chain(std::iter::repeat(0.0))) {
+                    if let Some(child) = child.remove(tab_id) {
+                        kept.push(child);
+                        kept_sizes.push(size);
+                    }
+                }
+
+                match kept.len() {
+                    0 => None,
+                    1 => kept.pop(),
+                    len => {
+                        if len != before {
+                            let total = kept_sizes.iter().sum::<f32>();
+                            kept_sizes = if total > 0.0 {
+                                kept_sizes.iter().map(|size| size / total).collect()
+                            } else {
+                                vec![1.0 / len as f32; len]
+                            };
+                        }
+                        Some(LayoutNode::Split {
+                            direction,
+                            children: kept,
+                            sizes: kept_sizes,
+                        })
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn layout_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|error| format!("failed to resolve data dir: {error}"))?;
+    Ok(dir.join("layout.json"))
+}
+
+fn load_groups(app: &tauri::AppHandle) -> Result<Vec<PaneGroup>, String> {
+    let path = layout_path(app)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let raw = std::fs::read_to_string(&path).map_err(|error| format!("failed to read layout: {error}"))?;
+    serde_json::from_str(&raw).map_err(|error| format!("failed to parse layout: {error}"))
+}
+
+fn store_groups(app: &tauri::AppHandle, groups: &[PaneGroup]) -> Result<(), String> {
+    let path = layout_path(app)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|error| format!("failed to create data dir: {error}"))?;
+    }
+
+    let raw = serde_json::to_string_pretty(groups).map_err(|error| format!("failed to encode layout: {error}"))?;
+    std::fs::write(&path, raw).map_err(|error| format!("failed to write layout: {error}"))?;
+    Ok(())
+}
+
+/// Runs `read` on the pane groups, loading them from disk on first use.
+fn with_groups<T>(
+    app: &tauri::AppHandle,
+    read: impl FnOnce(&mut Vec<PaneGroup>) -> Result<T, AppError>,
+) -> Result<T, AppError> {
+    let state = app.state::<LayoutState>();
+    let mut groups = state
+        .groups
+        .lock()
+        .map_err(|_| "failed to lock layout".to_string())?;
+
+    if groups.is_none() {
+        *groups = Some(load_groups(app)?);
+    }
+    match groups.as_mut() {
+        Some(groups) => read(groups),
+        None => Err(AppError::from("layout unavailable")),
+    }
+}
+
+/// Like `with_groups`, but saves the groups once `update` succeeds.
+fn update_groups<T>(
+    app: &tauri::AppHandle,
+    update: impl FnOnce(&mut Vec<PaneGroup>) -> Result<T, AppError>,
+) -> Result<T, AppError> {
+    with_groups(app, |groups| {
+        let result = update(groups)?;
+        store_groups(app, groups)?;
+        Ok(result)
+    })
+}
+
+fn detach(groups: &mut [PaneGroup], tab_id: &str) {
+    for group in groups.iter_mut() {
+        if group.root.as_ref().is_some_and(|root| root.contains(tab_id)) {
+            group.root = group.root.take().and_then(|root| root.remove(tab_id));
+        }
+    }
+}
+
+fn attach(
+    group: &mut PaneGroup,
+    tab_id: &str,
+    target_tab_id: Option<&str>,
+    direction: SplitDirection,
+    before: bool,
+) -> Result<(), AppError> {
+    let Some(root) = group.root.as_mut() else {
+        group.root = Some(LayoutNode::Pane {
+            tab_id: tab_id.to_string(),
+        });
+        return Ok(());
+    };
+
+    let target = match target_tab_id {
+        Some(target) => target.to_string(),
+        None => {
+            let mut ids = Vec::new();
+            root.tab_ids(&mut ids);
+            ids.pop().ok_or_else(|| AppError::invalid("group has no panes"))?
+        }
+    };
+    if !root.insert_next_to(&target, tab_id, direction, before) {
+        return Err(AppError::invalid(format!("pane not found in group: {target}")));
+    }
+    Ok(())
+}
+
+fn generate_group_id() -> String {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_nanos())
+        .unwrap_or(0);
+    format!("group-{nanos:x}")
+}
+
+/// Returns the group holding `tab_id` with the tab ids of all its panes, including `tab_id`.
+pub fn pane_group(app: &tauri::AppHandle, tab_id: &str) -> Option<(String, Vec<String>)> {
+    with_groups(app, |groups| {
+        Ok(groups.iter().find_map(|group| {
+            let root = group.root.as_ref().filter(|root| root.contains(tab_id))?;
+            let mut ids = Vec::new();
+            root.tab_ids(&mut ids);
+            Some((group.id.clone(), ids))
+        }))
+    })
+    .ok()
+    .flatten()
+}
+
+/// Drops a closed session from the layout.
+pub fn forget_pane(app: &tauri::AppHandle, tab_id: &str) {
+    let _ = update_groups(app, |groups| {
+        detach(groups, tab_id);
+        Ok(())
+    });
+}
+
+#[tauri::command]
+pub fn create_group(window: String, name: Option<String>, app: tauri::AppHandle) -> Result<PaneGroup, AppError> {
+    update_groups(&app, |groups| {
+        let group = PaneGroup {
+            id: generate_group_id(),
+            window,
+            name,
+            root: None,
+        };
+        groups.push(group.clone());
+        Ok(group)
+    })
+}
+
+#[tauri::command]
+pub fn delete_group(group_id: String, app: tauri::AppHandle) -> Result<(), AppError> {
+    update_groups(&app, |groups| {
+        groups.retain(|group| group.id != group_id);
+        Ok(())
+    })
+}
+
+/// Adds `tab_id` as a new pane beside `target_tab_id` (the group's last pane when omitted).
+#[tauri::command]
+pub fn create_split(
+    group_id: String,
+    tab_id: String,
+    target_tab_id: Option<String>,
+    direction: SplitDirection,
+    before: Option<bool>,
+    app: tauri::AppHandle,
+) -> Result<PaneGroup, AppError> {
+    update_groups(&app, |groups| {
+        if groups
+            .iter()
+            .any(|group| group.root.as_ref().is_some_and(|root| root.contains(&tab_id)))
+        {
+            return Err(AppError::invalid(format!("pane already in layout: {tab_id}")));
+        }
+        let group = groups
+            .iter_mut()
+            .find(|group| group.id == group_id)
+            .ok_or_else(|| AppError::invalid(format!("group not found: {group_id}")))?;
+        attach(group, &tab_id, target_tab_id.as_deref(), direction, before.unwrap_or(false))?;
+        Ok(group.clone())
+    })
+}
+
+/// Moves a pane, possibly into another group or window, next to `target_tab_id`.
+#[tauri::command]
+pub fn move_pane(
+    tab_id: String,
+    group_id: String,
+    target_tab_id: Option<String>,
+    direction: SplitDirection,
+    before: Option<bool>,
+    app: tauri::AppHandle,
+) -> Result<PaneGroup, AppError> {
+    if target_tab_id.as_deref() == Some(tab_id.as_str()) {
+        return Err(AppError::invalid("cannot move a pane next to itself"));
+    }
+
+    update_groups(&app, |groups| {
+        if !groups.iter().any(|group| group.id == group_id) {
+            return Err(AppError::invalid(format!("group not found: {group_id}")));
+        }
+        detach(groups, &tab_id);
+        let group = groups
+            .iter_mut()
+            .find(|group| group.id == group_id)
+            .ok_or_else(|| AppError::invalid(format!("group not found: {group_id}")))?;
+        attach(group, &tab_id, target_tab_id.as_deref(), direction, before.unwrap_or(false))?;
+        Ok(group.clone())
+    })
+}
+
+/// Updates the relative sizes of the split that directly contains `tab_id`.
+#[tauri::command]
+pub fn resize_split(tab_id: String, sizes: Vec<f32>, app: tauri::AppHandle) -> Result<(), AppError> {
+    fn apply(node: &mut LayoutNode, tab_id: &str, sizes: &[f32]) -> bool {
+        let LayoutNode::Split {
+            children,
+            sizes: current,
+            ..
+        } = node
+        else {
+            return false;
+        };
+        let direct = children
+            .iter()
+            .any(|child| matches!(child, LayoutNode::Pane { tab_id: id } if id == tab_id));
+        if direct && sizes.len() == children.len() {
+            *current = sizes.to_vec();
+            return true;
+        }
+        children.iter_mut().any(|child| apply(child, tab_id, sizes))
+    }
+
+    update_groups(&app, |groups| {
+        let applied = groups
+            .iter_mut()
+            .filter_map(|group| group.root.as_mut())
+            .any(|root| apply(root, &tab_id, &sizes));
+        if !applied {
+            return Err(AppError::invalid(format!("no split with {} panes holds {tab_id}", sizes.len())));
+        }
+        Ok(())
+    })
+}
+
+/// Returns the pane groups of one window, or of all windows.
+#[tauri::command]
+pub fn serialize_layout(window: Option<String>, app: tauri::AppHandle) -> Result<Vec<PaneGroup>, AppError> {
+    with_groups(&app, |groups| {
+        Ok(groups
+            .iter()
+            .filter(|group| match window.as_deref() {
+                Some(window) => group.window == window,
+                None => true,
+            })
+            .cloned()
+            .collect())
+    })
+}
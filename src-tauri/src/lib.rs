@@ -1,275 +1,319 @@
+mod actions;
+mod activity;
+mod askpass;
+mod broadcast;
+mod detach;
+mod dropdown;
+mod editor;
+mod error;
+mod flow;
+mod forge;
 mod git;
-
-use portable_pty::{native_pty_system, Child, CommandBuilder, MasterPty, PtySize};
-use serde::Serialize;
-use std::{
-    collections::HashMap,
-    io::{Read, Write},
-    path::PathBuf,
-    sync::Mutex,
-};
-use tauri::Emitter;
-
-struct TerminalSession {
-    writer: Box<dyn Write + Send>,
-    master: Box<dyn MasterPty + Send>,
-    child: Box<dyn Child + Send + Sync>,
-    shell: String,
-}
-
-struct TerminalState {
-    sessions: Mutex<HashMap<String, TerminalSession>>,
-}
-
-#[derive(Clone, Serialize)]
-#[serde(rename_all = "camelCase")]
-struct OpenTerminalResponse {
-    shell: String,
-}
-
-#[derive(Clone, Serialize)]
-#[serde(rename_all = "camelCase")]
-struct TerminalDataEvent {
-    tab_id: String,
-    data: String,
-}
-
-#[derive(Clone, Serialize)]
-#[serde(rename_all = "camelCase")]
-struct TerminalExitEvent {
-    tab_id: String,
-}
-
-#[cfg(target_os = "windows")]
-fn shell_details() -> (String, CommandBuilder) {
-    let shell = "cmd.exe".to_string();
-    let builder = CommandBuilder::new(shell.clone());
-    (shell, builder)
-}
-
-#[cfg(not(target_os = "windows"))]
-fn shell_details() -> (String, CommandBuilder) {
-    let shell = std::env::var("SHELL").unwrap_or_else(|_| "/bin/bash".to_string());
-    let mut builder = CommandBuilder::new(shell.clone());
-    builder.env("TERM", "xterm-256color");
-    builder.env("COLORTERM", "truecolor");
-    builder.env("TERM_PROGRAM", "ghostty-web");
-    builder.env("CLICOLOR", "1");
-    (shell, builder)
-}
-
-#[tauri::command]
-fn terminal_cwd(tab_id: String, state: tauri::State<TerminalState>) -> Result<Option<String>, String> {
-    let sessions = state
-        .sessions
-        .lock()
-        .map_err(|_| "failed to lock terminal sessions".to_string())?;
-
-    let session = match sessions.get(&tab_id) {
-        Some(session) => session,
-        None => return Ok(None),
-    };
-
-    let pid = match session.child.process_id() {
-        Some(pid) => pid,
-        None => return Ok(None),
-    };
-
-    #[cfg(target_os = "linux")]
-    {
-        let link_path = PathBuf::from(format!("/proc/{pid}/cwd"));
-        let cwd = std::fs::read_link(&link_path)
-            .map_err(|error| format!("failed to read cwd link: {error}"))?;
-        return Ok(Some(cwd.to_string_lossy().to_string()));
-    }
-
-    #[cfg(not(target_os = "linux"))]
-    {
-        let _ = pid;
-        Ok(None)
-    }
-}
-
-#[tauri::command]
-fn open_terminal(
-    tab_id: String,
-    app: tauri::AppHandle,
-    state: tauri::State<TerminalState>,
-) -> Result<OpenTerminalResponse, String> {
-    let mut sessions = state
-        .sessions
-        .lock()
-        .map_err(|_| "failed to lock terminal sessions".to_string())?;
-
-    if let Some(session) = sessions.get(&tab_id) {
-        return Ok(OpenTerminalResponse {
-            shell: session.shell.clone(),
-        });
-    }
-
-    let pty_system = native_pty_system();
-    let pair = pty_system
-        .openpty(PtySize {
-            rows: 24,
-            cols: 80,
-            pixel_width: 0,
-            pixel_height: 0,
-        })
-        .map_err(|error| format!("failed to open pty: {error}"))?;
-
-    let (shell, shell_command) = shell_details();
-
-    let child = pair
-        .slave
-        .spawn_command(shell_command)
-        .map_err(|error| format!("failed to spawn shell: {error}"))?;
-
-    drop(pair.slave);
-
-    let mut reader = pair
-        .master
-        .try_clone_reader()
-        .map_err(|error| format!("failed to clone pty reader: {error}"))?;
-
-    let writer = pair
-        .master
-        .take_writer()
-        .map_err(|error| format!("failed to get pty writer: {error}"))?;
-
-    let app_handle = app.clone();
-    let reader_tab_id = tab_id.clone();
-
-    std::thread::spawn(move || {
-        let mut buffer = [0_u8; 8192];
-
-        loop {
-            match reader.read(&mut buffer) {
-                Ok(0) => break,
-                Ok(read) => {
-                    let data = String::from_utf8_lossy(&buffer[..read]).to_string();
-                    let _ = app_handle.emit(
-                        "terminal-data",
-                        TerminalDataEvent {
-                            tab_id: reader_tab_id.clone(),
-                            data,
-                        },
-                    );
-                }
-                Err(_) => break,
-            }
-        }
-
-        let _ = app_handle.emit(
-            "terminal-exit",
-            TerminalExitEvent {
-                tab_id: reader_tab_id,
-            },
-        );
-    });
-
-    sessions.insert(
-        tab_id,
-        TerminalSession {
-            writer,
-            master: pair.master,
-            child,
-            shell: shell.clone(),
-        },
-    );
-
-    Ok(OpenTerminalResponse { shell })
-}
-
-#[tauri::command]
-fn write_terminal(tab_id: String, data: String, state: tauri::State<TerminalState>) -> Result<(), String> {
-    let mut sessions = state
-        .sessions
-        .lock()
-        .map_err(|_| "failed to lock terminal sessions".to_string())?;
-
-    let session = sessions
-        .get_mut(&tab_id)
-        .ok_or_else(|| format!("terminal session not found: {tab_id}"))?;
-
-    session
-        .writer
-        .write_all(data.as_bytes())
-        .map_err(|error| format!("failed to write to pty: {error}"))?;
-
-    session
-        .writer
-        .flush()
-        .map_err(|error| format!("failed to flush pty writer: {error}"))?;
-
-    Ok(())
-}
-
-#[tauri::command]
-fn resize_terminal(tab_id: String, cols: u16, rows: u16, state: tauri::State<TerminalState>) -> Result<(), String> {
-    if cols == 0 || rows == 0 {
-        return Ok(());
-    }
-
-    let mut sessions = state
-        .sessions
-        .lock()
-        .map_err(|_| "failed to lock terminal sessions".to_string())?;
-
-    if let Some(session) = sessions.get_mut(&tab_id) {
-        session
-            .master
-            .resize(PtySize {
-                rows,
-                cols,
-                pixel_width: 0,
-                pixel_height: 0,
-            })
-            .map_err(|error| format!("failed to resize pty: {error}"))?;
-    }
-
-    Ok(())
-}
-
-#[tauri::command]
-fn close_terminal(tab_id: String, state: tauri::State<TerminalState>) -> Result<(), String> {
-    let mut sessions = state
-        .sessions
-        .lock()
-        .map_err(|_| "failed to lock terminal sessions".to_string())?;
-
-    if let Some(mut session) = sessions.remove(&tab_id) {
-        let _ = session.child.kill();
-        let _ = session.child.wait();
-    }
-
-    Ok(())
+mod hyperlinks;
+mod images;
+mod layout;
+mod links;
+mod notifications;
+mod paste;
+mod playback;
+mod process;
+mod profiles;
+mod recording;
+mod runtimes;
+mod screen;
+mod scrollback;
+mod serial;
+mod session_store;
+mod shell_integration;
+mod sixel;
+mod ssh;
+mod stats;
+mod stream;
+mod supervisor;
+mod tasks;
+mod tcp;
+mod terminal;
+mod tmux;
+mod triggers;
+mod windows;
+mod workspace;
+mod writer;
+
+use actions::ActionState;
+use activity::ActivityState;
+use askpass::AskpassState;
+use broadcast::BroadcastState;
+use detach::DetachState;
+use dropdown::DropdownState;
+use flow::FlowState;
+use git::{operation::GitOperations, watcher::GitWatcherState};
+use images::ImageState;
+use layout::LayoutState;
+use notifications::NotificationState;
+use paste::PasteState;
+use playback::PlaybackState;
+use process::ProcessMonitor;
+use stats::StatsState;
+use std::{collections::HashMap, sync::Mutex};
+use supervisor::SupervisorState;
+use tauri::Manager;
+use terminal::TerminalState;
+use tmux::TmuxState;
+use triggers::TriggerState;
+use windows::WindowState;
+use workspace::WorkspaceState;
+
+/// Runs the askpass or signing helper instead of the app when launched by git or ssh.
+pub fn askpass_client() -> Option<i32> {
+    git::signing::run_gpg_wrapper().or_else(askpass::run_client)
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_notification::init())
+        .plugin(
+            tauri_plugin_global_shortcut::Builder::new()
+                .with_handler(actions::handle_shortcut)
+                .build(),
+        )
+        .manage(ActionState::default())
+        .manage(ActivityState::default())
+        .manage(AskpassState::default())
+        .manage(BroadcastState::default())
+        .manage(DetachState::default())
+        .manage(DropdownState::default())
+        .manage(FlowState::default())
+        .manage(GitOperations::default())
+        .manage(GitWatcherState::default())
+        .manage(ImageState::default())
+        .manage(LayoutState::default())
+        .manage(NotificationState::default())
+        .manage(PasteState::default())
+        .manage(PlaybackState::default())
+        .manage(ProcessMonitor::default())
+        .manage(StatsState::default())
+        .manage(SupervisorState::default())
         .manage(TerminalState {
             sessions: Mutex::new(HashMap::new()),
         })
+        .manage(TmuxState::default())
+        .manage(TriggerState::default())
+        .manage(WindowState::default())
+        .manage(WorkspaceState::default())
+        .setup(|app| {
+            actions::register_global_shortcuts(app.handle());
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
+            actions::list_actions,
+            actions::dispatch_action,
+            actions::set_action_binding,
+            actions::reset_action_bindings,
+            askpass::git_credential_respond,
+            flow::get_flood_settings,
+            flow::set_flood_settings,
+            forge::forge_accounts,
+            forge::forge_set_token,
+            forge::forge_remove_token,
+            forge::forge_detect,
+            forge::forge_list_prs,
+            forge::forge_pr_details,
+            forge::forge_create_pr,
+            forge::forge_checkout_pr,
+            forge::git_web_url,
             git::git_status,
             git::git_diff,
+            git::diff::git_diff_structured,
+            git::diff::git_diff_stat,
+            git::diff::git_diff_image,
+            git::show::git_show_file,
+            git::stats::git_repo_stats,
+            git::bisect::git_bisect_start,
+            git::bisect::git_bisect_mark,
+            git::bisect::git_bisect_status,
+            git::bisect::git_bisect_reset,
+            git::reflog::git_reflog,
+            git::reflog::git_reset_to,
+            git::archive::git_archive,
+            git::init::git_init,
+            git::config::git_config_get,
+            git::config::git_config_set,
+            git::config::git_config_list,
+            git::identity::list_identities,
+            git::identity::save_identity,
+            git::identity::delete_identity,
+            git::identity::assign_identity,
             git::git_stage,
             git::git_stage_all,
             git::git_unstage,
+            git::git_stage_paths,
+            git::git_unstage_paths,
+            git::discard::git_discard,
+            git::discard::git_discard_all,
+            git::discard::git_clean,
             git::git_commit,
+            git::git_commit_paths,
+            git::hooks::git_commit_template,
+            git::conventional::git_commit_structured,
+            git::conventional::git_recent_commit_subjects,
             git::git_fetch,
             git::git_pull,
             git::git_push,
+            git::operation::git_cancel_operation,
             git::git_branches,
             git::git_checkout,
-            terminal_cwd,
-            open_terminal,
-            write_terminal,
-            resize_terminal,
-            close_terminal
+            git::git_branch_create,
+            git::git_branch_delete,
+            git::git_branch_rename,
+            git::log::git_log,
+            git::log::git_show,
+            git::log::git_file_log,
+            git::log::git_search,
+            git::blame::git_blame,
+            git::merge::git_merge,
+            git::merge::git_merge_abort,
+            git::merge::git_conflicts,
+            git::merge::git_resolve_conflict,
+            git::patch::git_stage_hunk,
+            git::patch::git_unstage_hunk,
+            git::patch::git_stage_lines,
+            git::rebase::git_rebase_todo,
+            git::rebase::git_rebase_start,
+            git::rebase::git_rebase_status,
+            git::rebase::git_rebase_continue,
+            git::rebase::git_rebase_skip,
+            git::rebase::git_rebase_abort,
+            git::remote::git_remotes,
+            git::remote::git_remote_add,
+            git::remote::git_remote_remove,
+            git::remote::git_remote_rename,
+            git::remote::git_remote_set_url,
+            git::signing::git_signing_config,
+            git::stash::git_stash_save,
+            git::stash::git_stash_list,
+            git::stash::git_stash_apply,
+            git::stash::git_stash_pop,
+            git::stash::git_stash_drop,
+            git::stash::git_stash_show,
+            git::submodule::git_submodules,
+            git::submodule::git_submodule_update,
+            git::watcher::git_watch,
+            git::watcher::git_unwatch,
+            git::worktree::git_worktrees,
+            git::worktree::git_worktree_add,
+            git::worktree::git_worktree_remove,
+            notifications::set_active_terminal,
+            notifications::get_command_notifications,
+            notifications::set_command_notifications,
+            profiles::list_profiles,
+            profiles::save_profile,
+            profiles::delete_profile,
+            session_store::restore_sessions,
+            ssh::open_ssh_terminal,
+            terminal::terminal_cwd,
+            terminal::terminal_env,
+            runtimes::terminal_runtimes,
+            terminal::terminal_has_foreground_process,
+            terminal::terminal_process_tree,
+            stats::get_stats_settings,
+            stats::set_stats_settings,
+            activity::session_unread,
+            terminal::signal_terminal,
+            terminal::kill_foreground_process,
+            terminal::open_terminal,
+            terminal::run_command,
+            serial::list_serial_ports,
+            serial::open_serial_terminal,
+            tcp::open_tcp_terminal,
+            tmux::attach_tmux,
+            tmux::detach_tmux,
+            tmux::tmux_new_window,
+            tmux::tmux_split_pane,
+            tasks::list_tasks,
+            tasks::run_task,
+            terminal::duplicate_terminal,
+            terminal::write_terminal,
+            broadcast::write_terminal_broadcast,
+            broadcast::toggle_broadcast_group,
+            broadcast::broadcast_groups,
+            paste::paste_terminal,
+            paste::get_paste_settings,
+            paste::set_paste_settings,
+            terminal::resize_terminal,
+            terminal::terminal_flow_control,
+            terminal::get_scrollback,
+            terminal::search_terminal,
+            terminal::get_command_history,
+            terminal::copy_last_command_output,
+            screen::get_screen_contents,
+            screen::get_cursor_position,
+            screen::get_text_in_region,
+            links::get_detected_links,
+            hyperlinks::resolve_hyperlink,
+            hyperlinks::open_hyperlink,
+            images::get_terminal_image,
+            editor::open_in_editor,
+            editor::get_editor_settings,
+            editor::set_editor_settings,
+            terminal::clear_scrollback,
+            terminal::get_terminal_title,
+            terminal::set_terminal_title,
+            terminal::set_terminal_restart_on_exit,
+            terminal::close_terminal,
+            detach::get_detach_settings,
+            detach::set_detach_settings,
+            detach::detach_sessions,
+            detach::list_detached_sessions,
+            detach::reattach_sessions,
+            dropdown::toggle_dropdown,
+            dropdown::get_dropdown_settings,
+            dropdown::set_dropdown_settings,
+            layout::create_group,
+            layout::delete_group,
+            layout::create_split,
+            layout::move_pane,
+            layout::resize_split,
+            layout::serialize_layout,
+            recording::start_recording,
+            recording::stop_recording,
+            playback::play_recording,
+            playback::pause_playback,
+            playback::seek_playback,
+            playback::set_playback_speed,
+            playback::stop_playback,
+            triggers::list_triggers,
+            triggers::save_trigger,
+            triggers::delete_trigger,
+            windows::move_session_to_window,
+            windows::window_sessions,
+            workspace::workspace_list,
+            workspace::workspace_add,
+            workspace::workspace_remove,
+            workspace::workspace_refresh
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| match event {
+            // Closing the last window leaves sessions running when detaching is enabled.
+            tauri::RunEvent::ExitRequested { code: None, api, .. } if detach::detach_on_close(app) => {
+                api.prevent_exit();
+            }
+            #[cfg(target_os = "macos")]
+            tauri::RunEvent::Reopen {
+                has_visible_windows: false,
+                ..
+            } => detach::reopen(app),
+            tauri::RunEvent::WindowEvent {
+                label,
+                event: tauri::WindowEvent::Destroyed,
+                ..
+            } => windows::window_destroyed(app, &label),
+            tauri::RunEvent::Exit => {
+                let state = app.state::<TerminalState>();
+                let _ = session_store::save_sessions(app, &state);
+            }
+            _ => {}
+        });
 }
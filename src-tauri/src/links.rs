@@ -0,0 +1,160 @@
+use crate::{error::AppError, scrollback::strip_escapes, terminal::TerminalState};
+use regex::Regex;
+use serde::Serialize;
+use std::sync::OnceLock;
+
+#[derive(Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LinkKind {
+    Url,
+    Path,
+    /// A path followed by `:line` and optionally `:column`, as printed by compilers.
+    FileLocation,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DetectedLink {
+    kind: LinkKind,
+    /// Character offsets into the line with escape sequences removed.
+    start: usize,
+    end: usize,
+    text: String,
+    path: Option<String>,
+    line: Option<u32>,
+    column: Option<u32>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LineLinks {
+    /// Absolute scrollback line index.
+    line: usize,
+    links: Vec<DetectedLink>,
+}
+
+fn url_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r#"\b(?:https?|ftp|file)://[^\s<>"'`]+"#).expect("valid url pattern"))
+}
+
+fn path_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(r"(?P<path>(?:[A-Za-z]:[\\/]|~/|\.{1,2}/|/)?[\w.\-@+]+(?:[\\/][\w.\-@+]+)*)(?::(?P<line>\d+)(?::(?P<column>\d+))?)?")
+            .expect("valid path pattern")
+    })
+}
+
+fn char_offset(text: &str, byte: usize) -> usize {
+    text[..byte].chars().count()
+}
+
+/// Finds URLs, paths and `file:line:col` references in one line of plain text.
+pub fn detect_links(text: &str) -> Vec<DetectedLink> {
+    let mut links = Vec::new();
+    let mut taken = Vec::new();
+
+    for found in url_pattern().find_iter(text) {
+        // Trailing punctuation usually belongs to the sentence, not the URL.
+        let url = found.as_str().trim_end_matches(['.', ',', ';', ':', ')', ']', '}', '!', '?']);
+        let end = found.start() + url.len();
+        taken.push(found.start()..end);
+        links.push(DetectedLink {
+            kind: LinkKind::Url,
+            start: char_offset(text, found.start()),
+            end: char_offset(text, end),
+            text: url.to_string(),
+            path: None,
+            line: None,
+            column: None,
+        });
+    }
+
+    for captures in path_pattern().captures_iter(text) {
+        let Some(found) = captures.get(0) else {
+            continue;
+        };
+        if taken.iter().any(|range| range.start < found.end() && found.start() < range.end) {
+            continue;
+        }
+
+        let line = captures.name("line").and_then(|value| value.as_str().parse::<u32>().ok());
+        let column = captures.name("column").and_then(|value| value.as_str().parse::<u32>().ok());
+        let path = captures
+            .name("path")
+            .map(|value| value.as_str().trim_end_matches('.'))
+            .unwrap_or_default();
+
+        // Bare words need a separator, or an extension plus a line number, to count as a path.
+        let has_separator = path.contains(['/', '\\']);
+        let has_extension = path.rsplit_once('.').is_some_and(|(stem, ext)| !stem.is_empty() && !ext.is_empty());
+        if path.is_empty() || !(has_separator || (has_extension && line.is_some())) {
+            continue;
+        }
+        if path.chars().all(|c| c == '.' || c == '/' || c == '\\') {
+            continue;
+        }
+
+        let end = if line.is_some() {
+            found.end()
+        } else {
+            found.start() + path.len()
+        };
+        links.push(DetectedLink {
+            kind: if line.is_some() {
+                LinkKind::FileLocation
+            } else {
+                LinkKind::Path
+            },
+            start: char_offset(text, found.start()),
+            end: char_offset(text, end),
+            text: text[found.start()..end].to_string(),
+            path: Some(path.to_string()),
+            line,
+            column,
+        });
+    }
+
+    links.sort_by_key(|link| link.start);
+    links
+}
+
+/// Returns the links found in scrollback lines `from..to`, skipping lines without any.
+#[tauri::command]
+pub fn get_detected_links(
+    tab_id: String,
+    from: Option<usize>,
+    to: Option<usize>,
+    state: tauri::State<TerminalState>,
+) -> Result<Vec<LineLinks>, AppError> {
+    let range = {
+        let sessions = state
+            .sessions
+            .lock()
+            .map_err(|_| "failed to lock terminal sessions".to_string())?;
+
+        let session = sessions
+            .get(&tab_id)
+            .ok_or_else(|| AppError::session_not_found(&tab_id))?;
+
+        let scrollback = session
+            .scrollback
+            .lock()
+            .map_err(|_| "failed to lock scrollback".to_string())?;
+        scrollback.range(from, to)
+    };
+
+    Ok(range
+        .lines()
+        .iter()
+        .enumerate()
+        .filter_map(|(offset, line)| {
+            let links = detect_links(&strip_escapes(line));
+            (!links.is_empty()).then(|| LineLinks {
+                line: range.start() + offset,
+                links,
+            })
+        })
+        .collect())
+}
@@ -2,5 +2,9 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 fn main() {
+    if let Some(code) = tauri_app_lib::askpass_client() {
+        std::process::exit(code);
+    }
+
     tauri_app_lib::run()
 }
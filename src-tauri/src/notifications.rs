@@ -0,0 +1,154 @@
+use crate::{activity, error::AppError};
+use serde::{Deserialize, Serialize};
+use std::{sync::Mutex, time::Duration};
+use tauri::Manager;
+use tauri_plugin_notification::NotificationExt;
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandNotificationSettings {
+    enabled: bool,
+    min_duration_secs: u64,
+    /// Raise OSC 9 / OSC 777 notifications from programs as desktop notifications.
+    #[serde(default = "default_terminal_alerts")]
+    terminal_alerts: bool,
+    /// Also raise a desktop notification when a background tab rings the bell.
+    #[serde(default)]
+    bell: bool,
+}
+
+fn default_terminal_alerts() -> bool {
+    true
+}
+
+impl Default for CommandNotificationSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_duration_secs: 10,
+            terminal_alerts: default_terminal_alerts(),
+            bell: false,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct NotificationState {
+    settings: Mutex<CommandNotificationSettings>,
+    active_tab: Mutex<Option<String>>,
+}
+
+/// The tab the frontend last reported as selected.
+pub fn active_tab(app: &tauri::AppHandle) -> Option<String> {
+    app.state::<NotificationState>()
+        .active_tab
+        .lock()
+        .ok()
+        .and_then(|active| active.clone())
+}
+
+pub fn is_active_tab(app: &tauri::AppHandle, tab_id: &str) -> bool {
+    active_tab(app).as_deref() == Some(tab_id)
+}
+
+fn is_backgrounded(app: &tauri::AppHandle, tab_id: &str) -> bool {
+    let window_focused = app
+        .webview_windows()
+        .values()
+        .any(|window| window.is_focused().unwrap_or(false));
+
+    !window_focused || !is_active_tab(app, tab_id)
+}
+
+/// Shows a desktop notification when a long-running command finishes in a tab the user isn't looking at.
+pub fn notify_command_completed(app: &tauri::AppHandle, tab_id: &str, exit_code: Option<i32>, elapsed: Duration) {
+    let state = app.state::<NotificationState>();
+    let settings = match state.settings.lock() {
+        Ok(settings) => *settings,
+        Err(_) => return,
+    };
+
+    if !settings.enabled || elapsed < Duration::from_secs(settings.min_duration_secs) {
+        return;
+    }
+    if !is_backgrounded(app, tab_id) {
+        return;
+    }
+
+    let title = match exit_code {
+        Some(0) | None => "Command finished".to_string(),
+        Some(code) => format!("Command failed with exit code {code}"),
+    };
+    let body = format!("Finished after {}s", elapsed.as_secs());
+
+    let _ = app.notification().builder().title(title).body(body).show();
+}
+
+fn settings(app: &tauri::AppHandle) -> Option<CommandNotificationSettings> {
+    app.state::<NotificationState>()
+        .settings
+        .lock()
+        .ok()
+        .map(|settings| *settings)
+}
+
+/// Forwards a notification a program asked for to the desktop when its tab is in the background.
+pub fn notify_terminal_alert(app: &tauri::AppHandle, tab_id: &str, title: Option<&str>, body: &str) {
+    if !settings(app).is_some_and(|settings| settings.terminal_alerts) || !is_backgrounded(app, tab_id) {
+        return;
+    }
+
+    let title = title.filter(|title| !title.is_empty()).unwrap_or("Terminal");
+    let _ = app.notification().builder().title(title).body(body).show();
+}
+
+pub fn notify_bell(app: &tauri::AppHandle, tab_id: &str, title: Option<String>) {
+    if !settings(app).is_some_and(|settings| settings.bell) || !is_backgrounded(app, tab_id) {
+        return;
+    }
+
+    let body = title.unwrap_or_else(|| "A terminal rang the bell".to_string());
+    let _ = app.notification().builder().title("Bell").body(body).show();
+}
+
+#[tauri::command]
+pub fn set_active_terminal(
+    tab_id: Option<String>,
+    app: tauri::AppHandle,
+    state: tauri::State<NotificationState>,
+) -> Result<(), AppError> {
+    if let Some(tab_id) = &tab_id {
+        activity::mark_read(&app, tab_id);
+    }
+
+    let mut active = state
+        .active_tab
+        .lock()
+        .map_err(|_| "failed to lock active tab".to_string())?;
+    *active = tab_id;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_command_notifications(
+    state: tauri::State<NotificationState>,
+) -> Result<CommandNotificationSettings, AppError> {
+    let settings = state
+        .settings
+        .lock()
+        .map_err(|_| "failed to lock notification settings".to_string())?;
+    Ok(*settings)
+}
+
+#[tauri::command]
+pub fn set_command_notifications(
+    settings: CommandNotificationSettings,
+    state: tauri::State<NotificationState>,
+) -> Result<(), AppError> {
+    let mut current = state
+        .settings
+        .lock()
+        .map_err(|_| "failed to lock notification settings".to_string())?;
+    *current = settings;
+    Ok(())
+}
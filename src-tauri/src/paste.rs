@@ -0,0 +1,238 @@
+use crate::{
+    error::AppError,
+    terminal::{self, TerminalState},
+    windows,
+};
+use serde::{Deserialize, Serialize};
+use std::{sync::Mutex, time::Duration};
+
+const BRACKETED_PASTE_START: &str = "\x1b[200~";
+const BRACKETED_PASTE_END: &str = "\x1b[201~";
+const PREVIEW_CHARS: usize = 1024;
+/// Pause between chunks so the application can drain its input.
+const CHUNK_DELAY: Duration = Duration::from_millis(5);
+
+#[cfg(target_os = "windows")]
+const LINE_ENDING: &str = "\r\n";
+#[cfg(not(target_os = "windows"))]
+const LINE_ENDING: &str = "\r";
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PasteSettings {
+    /// Pastes larger than this are only written once confirmed.
+    confirm_threshold_bytes: usize,
+    chunk_bytes: usize,
+}
+
+impl Default for PasteSettings {
+    fn default() -> Self {
+        Self {
+            confirm_threshold_bytes: 16 * 1024,
+            chunk_bytes: 4096,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct PasteState {
+    settings: Mutex<PasteSettings>,
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PasteWarning {
+    /// Control characters other than tab and newlines were removed.
+    ControlCharacters,
+    /// The paste ends with a newline and would run as soon as it is written.
+    TrailingNewline,
+    /// Several lines would run one after the other.
+    MultiLine,
+    /// A line runs with elevated privileges.
+    Privileged,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PasteResult {
+    written: bool,
+    bytes: usize,
+    needs_confirmation: bool,
+    warnings: Vec<PasteWarning>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PasteWarningEvent {
+    tab_id: String,
+    warnings: Vec<PasteWarning>,
+    preview: String,
+}
+
+/// Drops C0 controls other than tab, CR and LF, DEL and C1 controls, which could otherwise switch
+/// terminal modes or end a bracketed paste early.
+fn sanitize(text: &str) -> (String, bool) {
+    let sanitized = text
+        .chars()
+        .filter(|c| matches!(c, '\t' | '\n' | '\r') || !c.is_control())
+        .collect::<String>();
+    let stripped = sanitized.len() != text.len();
+    (sanitized, stripped)
+}
+
+/// Flags pastes that would execute commands without the user pressing enter.
+fn execution_warnings(text: &str, bracketed: bool) -> Vec<PasteWarning> {
+    let mut warnings = Vec::new();
+    // Bracketed paste makes the shell insert the text instead of running it line by line.
+    if bracketed || !text.contains(['\n', '\r']) {
+        return warnings;
+    }
+
+    if text.ends_with(['\n', '\r']) {
+        warnings.push(PasteWarning::TrailingNewline);
+    }
+    let lines = text.lines().filter(|line| !line.trim().is_empty()).collect::<Vec<_>>();
+    if lines.len() > 1 {
+        warnings.push(PasteWarning::MultiLine);
+    }
+    let privileged = lines.iter().any(|line| {
+        let command = line.split_whitespace().next().unwrap_or_default();
+        matches!(command, "sudo" | "su" | "doas" | "pkexec")
+    });
+    if privileged {
+        warnings.push(PasteWarning::Privileged);
+    }
+    warnings
+}
+
+fn normalize_line_endings(text: &str) -> String {
+    text.replace("\r\n", "\n").replace('\r', "\n").replace('\n', LINE_ENDING)
+}
+
+/// Splits `payload` into pieces of at most `size` bytes without cutting through a UTF-8 character.
+fn chunks(payload: &str, size: usize) -> Vec<&str> {
+    let size = size.max(4);
+    let mut pieces = Vec::new();
+    let mut rest = payload;
+    while rest.len() > size {
+        let mut end = size;
+        while !rest.is_char_boundary(end) {
+            end -= 1;
+        }
+        let (piece, tail) = rest.split_at(end);
+        pieces.push(piece);
+        rest = tail;
+    }
+    if !rest.is_empty() {
+        pieces.push(rest);
+    }
+    pieces
+}
+
+/// Pastes `text` into a session, bracketed when the running application asked for it. Control
+/// characters are stripped first. Large pastes and pastes that would run commands straight away
+/// are refused until `confirmed`, the latter with a `paste-warning` event carrying a preview.
+/// Large pastes are queued on the session's writer as paced chunks, so they never interleave
+/// with each other or with typed input.
+#[tauri::command]
+pub fn paste_terminal(
+    tab_id: String,
+    text: String,
+    confirmed: Option<bool>,
+    app: tauri::AppHandle,
+    state: tauri::State<PasteState>,
+    terminals: tauri::State<TerminalState>,
+) -> Result<PasteResult, AppError> {
+    let settings = *state
+        .settings
+        .lock()
+        .map_err(|_| "failed to lock paste settings".to_string())?;
+
+    let bracketed = {
+        let sessions = terminals
+            .sessions
+            .lock()
+            .map_err(|_| "failed to lock terminal sessions".to_string())?;
+        let session = sessions
+            .get(&tab_id)
+            .ok_or_else(|| AppError::session_not_found(&tab_id))?;
+        session
+            .reported
+            .lock()
+            .map(|reported| reported.bracketed_paste)
+            .unwrap_or(false)
+    };
+
+    let (text, stripped) = sanitize(&text);
+    let mut warnings = execution_warnings(&text, bracketed);
+    let runs_commands = !warnings.is_empty();
+    if stripped {
+        warnings.insert(0, PasteWarning::ControlCharacters);
+    }
+
+    let mut payload = normalize_line_endings(&text);
+    if bracketed {
+        // A pasted end marker would let the rest of the text run as typed input.
+        payload = payload.replace(BRACKETED_PASTE_END, "");
+        payload = format!("{BRACKETED_PASTE_START}{payload}{BRACKETED_PASTE_END}");
+    }
+
+    let bytes = payload.len();
+    let confirmed = confirmed.unwrap_or(false);
+    if !confirmed && runs_commands {
+        windows::emit(
+            &app,
+            &tab_id,
+            "paste-warning",
+            PasteWarningEvent {
+                tab_id: tab_id.clone(),
+                warnings: warnings.clone(),
+                preview: text.chars().take(PREVIEW_CHARS).collect(),
+            },
+        );
+    }
+    if !confirmed && (runs_commands || bytes > settings.confirm_threshold_bytes) {
+        return Ok(PasteResult {
+            written: false,
+            bytes,
+            needs_confirmation: true,
+            warnings,
+        });
+    }
+
+    if bytes <= settings.chunk_bytes {
+        terminal::write_session(&terminals, &tab_id, payload.as_bytes())?;
+    } else {
+        let pieces = chunks(&payload, settings.chunk_bytes)
+            .into_iter()
+            .map(|piece| piece.as_bytes().to_vec())
+            .collect();
+        terminal::write_session_paced(&terminals, &tab_id, pieces, CHUNK_DELAY)?;
+    }
+
+    Ok(PasteResult {
+        written: true,
+        bytes,
+        needs_confirmation: false,
+        warnings,
+    })
+}
+
+#[tauri::command]
+pub fn get_paste_settings(state: tauri::State<PasteState>) -> Result<PasteSettings, AppError> {
+    let settings = state
+        .settings
+        .lock()
+        .map_err(|_| "failed to lock paste settings".to_string())?;
+    Ok(*settings)
+}
+
+#[tauri::command]
+pub fn set_paste_settings(settings: PasteSettings, state: tauri::State<PasteState>) -> Result<(), AppError> {
+    let mut current = state
+        .settings
+        .lock()
+        .map_err(|_| "failed to lock paste settings".to_string())?;
+    *current = settings;
+    Ok(())
+}
@@ -0,0 +1,268 @@
+use crate::{error::AppError, stream::OutputEncoding, terminal, windows};
+use serde::Serialize;
+use serde_json::Value;
+use std::{
+    collections::HashMap,
+    path::Path,
+    sync::{Arc, Condvar, Mutex},
+    time::{Duration, Instant},
+};
+use tauri::Manager;
+
+/// Full reset, sent before replaying from the start when seeking backwards.
+const RESET: &str = "\x1bc";
+
+struct CastEvent {
+    time: f64,
+    data: String,
+}
+
+struct Cast {
+    width: u16,
+    height: u16,
+    events: Vec<CastEvent>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlaybackInfo {
+    width: u16,
+    height: u16,
+    duration: f64,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PlaybackEvent {
+    tab_id: String,
+    position: f64,
+    duration: f64,
+    paused: bool,
+    finished: bool,
+}
+
+struct PlaybackFlags {
+    paused: bool,
+    stopped: bool,
+    speed: f64,
+    seek: Option<f64>,
+}
+
+pub struct PlaybackControl {
+    flags: Mutex<PlaybackFlags>,
+    changed: Condvar,
+}
+
+impl PlaybackControl {
+    fn update(&self, apply: impl FnOnce(&mut PlaybackFlags)) -> Result<(), AppError> {
+        let mut flags = self
+            .flags
+            .lock()
+            .map_err(|_| "failed to lock playback".to_string())?;
+        apply(&mut flags);
+        self.changed.notify_all();
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub struct PlaybackState {
+    players: Mutex<HashMap<String, Arc<PlaybackControl>>>,
+}
+
+/// Reads an asciinema v2 file. Only output events are kept; input and resize events don't
+/// change what the tab shows.
+fn load_cast(path: &Path) -> Result<Cast, String> {
+    let raw = std::fs::read_to_string(path).map_err(|error| format!("failed to read recording: {error}"))?;
+    let mut lines = raw.lines().filter(|line| !line.trim().is_empty());
+
+    let header: Value = serde_json::from_str(lines.next().ok_or_else(|| "recording is empty".to_string())?)
+        .map_err(|error| format!("failed to parse recording header: {error}"))?;
+    if header["version"].as_u64() != Some(2) {
+        return Err("only asciicast v2 recordings are supported".to_string());
+    }
+
+    let events = lines
+        .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+        .filter(|event| event[1].as_str() == Some("o"))
+        .filter_map(|event| {
+            Some(CastEvent {
+                time: event[0].as_f64().filter(|time| time.is_finite() && *time >= 0.0)?,
+                data: event[2].as_str()?.to_string(),
+            })
+        })
+        .collect();
+
+    Ok(Cast {
+        width: header["width"].as_u64().unwrap_or(80) as u16,
+        height: header["height"].as_u64().unwrap_or(24) as u16,
+        events,
+    })
+}
+
+fn emit_state(app: &tauri::AppHandle, tab_id: &str, position: f64, duration: f64, paused: bool, finished: bool) {
+    windows::emit(
+        app,
+        tab_id,
+        "terminal-playback",
+        PlaybackEvent {
+            tab_id: tab_id.to_string(),
+            position,
+            duration,
+            paused,
+            finished,
+        },
+    );
+}
+
+/// Streams the cast into `terminal-data` events, keeping the recorded gaps scaled by the speed.
+/// Pausing, seeking and stopping wake the wait between events.
+fn play(app: tauri::AppHandle, tab_id: String, cast: Cast, control: Arc<PlaybackControl>) {
+    let duration = cast.events.last().map(|event| event.time).unwrap_or_default();
+    let mut index = 0;
+    let mut position = 0.0_f64;
+
+    let Ok(mut flags) = control.flags.lock() else {
+        return;
+    };
+    emit_state(&app, &tab_id, position, duration, flags.paused, false);
+
+    loop {
+        if flags.stopped {
+            break;
+        }
+
+        if let Some(target) = flags.seek.take() {
+            let target = target.clamp(0.0, duration);
+            let mut data = String::new();
+            if target < position {
+                data.push_str(RESET);
+                index = 0;
+            }
+            while let Some(event) = cast.events.get(index).filter(|event| event.time <= target) {
+                data.push_str(&event.data);
+                index += 1;
+            }
+            position = target;
+            terminal::emit_data(&app, &tab_id, OutputEncoding::Text, data, 0);
+            emit_state(&app, &tab_id, position, duration, flags.paused, false);
+            continue;
+        }
+
+        if flags.paused {
+            flags = match control.changed.wait(flags) {
+                Ok(flags) => flags,
+                Err(_) => return,
+            };
+            continue;
+        }
+
+        let Some(event) = cast.events.get(index) else {
+            break;
+        };
+        let speed = if flags.speed > 0.0 { flags.speed } else { 1.0 };
+        // A timestamp too large for a `Duration` plays right away rather than panicking.
+        let delay = Duration::try_from_secs_f64(((event.time - position) / speed).max(0.0)).unwrap_or_default();
+        let waiting_since = Instant::now();
+        let (next, timeout) = match control.changed.wait_timeout(flags, delay) {
+            Ok(result) => result,
+            Err(_) => return,
+        };
+        flags = next;
+        if !timeout.timed_out() {
+            position = (position + waiting_since.elapsed().as_secs_f64() * speed).min(event.time);
+            continue;
+        }
+
+        position = event.time;
+        index += 1;
+        terminal::emit_data(&app, &tab_id, OutputEncoding::Text, event.data.clone(), 0);
+    }
+
+    let stopped = flags.stopped;
+    drop(flags);
+
+    let state = app.state::<PlaybackState>();
+    if let Ok(mut players) = state.players.lock() {
+        if players.get(&tab_id).is_some_and(|current| Arc::ptr_eq(current, &control)) {
+            players.remove(&tab_id);
+        }
+    }
+    emit_state(&app, &tab_id, position, duration, false, !stopped);
+}
+
+fn player(state: &PlaybackState, tab_id: &str) -> Result<Arc<PlaybackControl>, AppError> {
+    state
+        .players
+        .lock()
+        .map_err(|_| "failed to lock playback".to_string())?
+        .get(tab_id)
+        .cloned()
+        .ok_or_else(|| AppError::invalid(format!("no playback running in tab: {tab_id}")))
+}
+
+/// Replays a `.cast` file into a tab. Progress is reported through `terminal-playback` events.
+#[tauri::command]
+pub fn play_recording(
+    path: String,
+    tab_id: String,
+    speed: Option<f64>,
+    app: tauri::AppHandle,
+    state: tauri::State<PlaybackState>,
+) -> Result<PlaybackInfo, AppError> {
+    let cast = load_cast(Path::new(&path))?;
+    let info = PlaybackInfo {
+        width: cast.width,
+        height: cast.height,
+        duration: cast.events.last().map(|event| event.time).unwrap_or_default(),
+    };
+
+    let control = Arc::new(PlaybackControl {
+        flags: Mutex::new(PlaybackFlags {
+            paused: false,
+            stopped: false,
+            speed: speed.unwrap_or(1.0),
+            seek: None,
+        }),
+        changed: Condvar::new(),
+    });
+
+    let previous = state
+        .players
+        .lock()
+        .map_err(|_| "failed to lock playback".to_string())?
+        .insert(tab_id.clone(), Arc::clone(&control));
+    if let Some(previous) = previous {
+        previous.update(|flags| flags.stopped = true)?;
+    }
+
+    std::thread::spawn(move || play(app, tab_id, cast, control));
+    Ok(info)
+}
+
+#[tauri::command]
+pub fn pause_playback(tab_id: String, paused: bool, state: tauri::State<PlaybackState>) -> Result<(), AppError> {
+    player(&state, &tab_id)?.update(|flags| flags.paused = paused)
+}
+
+/// Jumps to `position` seconds into the recording.
+#[tauri::command]
+pub fn seek_playback(tab_id: String, position: f64, state: tauri::State<PlaybackState>) -> Result<(), AppError> {
+    player(&state, &tab_id)?.update(|flags| flags.seek = Some(position))
+}
+
+#[tauri::command]
+pub fn set_playback_speed(tab_id: String, speed: f64, state: tauri::State<PlaybackState>) -> Result<(), AppError> {
+    if !(speed.is_finite() && speed > 0.0) {
+        return Err(AppError::invalid("playback speed must be positive"));
+    }
+    player(&state, &tab_id)?.update(|flags| flags.speed = speed)
+}
+
+#[tauri::command]
+pub fn stop_playback(tab_id: String, state: tauri::State<PlaybackState>) -> Result<(), AppError> {
+    if let Ok(control) = player(&state, &tab_id) {
+        control.update(|flags| flags.stopped = true)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,382 @@
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, sync::Mutex};
+use sysinfo::{Pid, System};
+
+/// Keeps one `System` around so CPU usage is measured between consecutive refreshes.
+#[derive(Default)]
+pub struct ProcessMonitor {
+    system: Mutex<System>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessInfo {
+    pid: u32,
+    parent_pid: Option<u32>,
+    name: String,
+    /// Percent of one core since the previous refresh.
+    cpu: f32,
+    /// Resident memory in bytes.
+    memory: u64,
+}
+
+/// CPU and memory summed over a process and all of its descendants.
+#[derive(Clone, Copy, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TreeUsage {
+    pub cpu: f32,
+    pub memory: u64,
+    pub processes: usize,
+}
+
+fn child_map(system: &System) -> HashMap<Pid, Vec<Pid>> {
+    let mut children: HashMap<Pid, Vec<Pid>> = HashMap::new();
+    for (pid, process) in system.processes() {
+        if let Some(parent) = process.parent() {
+            children.entry(parent).or_default().push(*pid);
+        }
+    }
+    children
+}
+
+impl ProcessMonitor {
+    /// Returns every descendant of `root`, parents before their children.
+    pub fn descendants(&self, root: u32) -> Result<Vec<ProcessInfo>, String> {
+        let mut system = self
+            .system
+            .lock()
+            .map_err(|_| "failed to lock process monitor".to_string())?;
+        system.refresh_processes();
+        let children = child_map(&system);
+
+        let mut tree = Vec::new();
+        let mut pending = vec![Pid::from_u32(root)];
+        while let Some(parent) = pending.pop() {
+            let Some(pids) = children.get(&parent) else {
+                continue;
+            };
+            for pid in pids {
+                let Some(process) = system.process(*pid) else {
+                    continue;
+                };
+                tree.push(ProcessInfo {
+                    pid: pid.as_u32(),
+                    parent_pid: Some(parent.as_u32()),
+                    name: process.name().to_string(),
+                    cpu: process.cpu_usage(),
+                    memory: process.memory(),
+                });
+                pending.push(*pid);
+            }
+        }
+        Ok(tree)
+    }
+
+    /// Usage of each root's process tree, measured with a single refresh. Roots that have
+    /// exited report zero.
+    pub fn tree_usage(&self, roots: &[u32]) -> Result<Vec<TreeUsage>, String> {
+        let mut system = self
+            .system
+            .lock()
+            .map_err(|_| "failed to lock process monitor".to_string())?;
+        system.refresh_processes();
+        let children = child_map(&system);
+
+        Ok(roots
+            .iter()
+            .map(|root| {
+                let mut usage = TreeUsage::default();
+                let mut pending = vec![Pid::from_u32(*root)];
+                while let Some(pid) = pending.pop() {
+                    if let Some(process) = system.process(pid) {
+                        usage.cpu += process.cpu_usage();
+                        usage.memory += process.memory();
+                        usage.processes += 1;
+                    }
+                    pending.extend(children.get(&pid).into_iter().flatten().copied());
+                }
+                usage
+            })
+            .collect())
+    }
+}
+
+#[derive(Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Signal {
+    Interrupt,
+    Terminate,
+    Kill,
+    Hangup,
+}
+
+/// Sends `signal` to `pid`, or to the whole process group `pid` leads when `group` is set.
+#[cfg(unix)]
+pub fn send_signal(pid: u32, group: bool, signal: Signal) -> Result<(), String> {
+    let signal_number = match signal {
+        Signal::Interrupt => libc::SIGINT,
+        Signal::Terminate => libc::SIGTERM,
+        Signal::Kill => libc::SIGKILL,
+        Signal::Hangup => libc::SIGHUP,
+    };
+    let pid = i32::try_from(pid).map_err(|_| format!("invalid pid: {pid}"))?;
+    let target = if group { -pid } else { pid };
+
+    if unsafe { libc::kill(target, signal_number) } != 0 {
+        return Err(format!("failed to signal process {pid}: {}", std::io::Error::last_os_error()));
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+pub fn terminate(pid: u32) -> Result<(), String> {
+    windows::terminate(pid)
+}
+
+#[cfg(target_os = "linux")]
+pub fn process_cwd(pid: u32) -> Result<Option<String>, String> {
+    let link_path = std::path::PathBuf::from(format!("/proc/{pid}/cwd"));
+    let cwd = std::fs::read_link(&link_path).map_err(|error| format!("failed to read cwd link: {error}"))?;
+    Ok(Some(cwd.to_string_lossy().to_string()))
+}
+
+#[cfg(target_os = "macos")]
+pub fn process_cwd(pid: u32) -> Result<Option<String>, String> {
+    let cwd = libproc::proc_pid::pidcwd(pid as i32).map_err(|error| format!("failed to read process cwd: {error}"))?;
+    Ok(Some(cwd.to_string_lossy().to_string()))
+}
+
+#[cfg(target_os = "windows")]
+pub fn process_cwd(pid: u32) -> Result<Option<String>, String> {
+    windows::process_cwd(pid).map(Some)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+pub fn process_cwd(_pid: u32) -> Result<Option<String>, String> {
+    Ok(None)
+}
+
+/// Environment a process was started with. Variables a shell exported since then are not part of
+/// it; they only reach the programs the shell starts.
+#[cfg(target_os = "linux")]
+pub fn process_environ(pid: u32) -> Result<HashMap<String, String>, String> {
+    let raw = std::fs::read(format!("/proc/{pid}/environ"))
+        .map_err(|error| format!("failed to read process environment: {error}"))?;
+    Ok(parse_environ(
+        raw.split(|byte| *byte == 0)
+            .map(|entry| String::from_utf8_lossy(entry).into_owned()),
+    ))
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn process_environ(pid: u32) -> Result<HashMap<String, String>, String> {
+    let mut system = System::new();
+    let pid = Pid::from_u32(pid);
+    if !system.refresh_process(pid) {
+        return Err(format!("process not found: {pid}"));
+    }
+    let process = system
+        .process(pid)
+        .ok_or_else(|| format!("process not found: {pid}"))?;
+    Ok(parse_environ(process.environ().iter().cloned()))
+}
+
+/// Splits `KEY=value` entries. Windows' per-drive entries such as `=C:=C:\` have no name and
+/// are skipped.
+fn parse_environ(entries: impl Iterator<Item = String>) -> HashMap<String, String> {
+    entries
+        .filter_map(|entry| {
+            let (key, value) = entry.split_once('=')?;
+            (!key.is_empty()).then(|| (key.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+#[cfg(target_os = "linux")]
+pub fn process_name(pid: u32) -> Option<String> {
+    let comm = std::fs::read_to_string(format!("/proc/{pid}/comm")).ok()?;
+    Some(comm.trim_end().to_string())
+}
+
+#[cfg(target_os = "macos")]
+pub fn process_name(pid: u32) -> Option<String> {
+    libproc::proc_pid::name(pid as i32).ok()
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+pub fn process_name(_pid: u32) -> Option<String> {
+    None
+}
+
+/// Windows has no terminal process groups, so the most recently started leaf of the shell's
+/// process tree stands in for the foreground job.
+#[cfg(target_os = "windows")]
+pub fn foreground_descendant(pid: u32) -> Option<(u32, String)> {
+    windows::foreground_descendant(pid)
+}
+
+/// Reads the current directory out of the target's PEB. Only the offsets of 64-bit processes are
+/// handled, which matches the shells we spawn on 64-bit builds.
+#[cfg(target_os = "windows")]
+mod windows {
+    use std::ffi::c_void;
+    use windows_sys::{
+        Wdk::System::Threading::{NtQueryInformationProcess, ProcessBasicInformation},
+        Win32::{
+            Foundation::{CloseHandle, HANDLE, INVALID_HANDLE_VALUE},
+            System::{
+                Diagnostics::{
+                    Debug::ReadProcessMemory,
+                    ToolHelp::{CreateToolhelp32Snapshot, Process32FirstW, Process32NextW, PROCESSENTRY32W, TH32CS_SNAPPROCESS},
+                },
+                Threading::{
+                    OpenProcess, TerminateProcess, PROCESS_BASIC_INFORMATION, PROCESS_QUERY_INFORMATION,
+                    PROCESS_TERMINATE, PROCESS_VM_READ,
+                },
+            },
+        },
+    };
+
+    const PEB_PROCESS_PARAMETERS_OFFSET: usize = 0x20;
+    const PARAMETERS_CURRENT_DIRECTORY_OFFSET: usize = 0x38;
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct UnicodeString {
+        length: u16,
+        maximum_length: u16,
+        buffer: usize,
+    }
+
+    struct ProcessHandle(HANDLE);
+
+    impl Drop for ProcessHandle {
+        fn drop(&mut self) {
+            unsafe {
+                CloseHandle(self.0);
+            }
+        }
+    }
+
+    unsafe fn read_memory<T>(handle: HANDLE, address: usize, value: &mut T) -> Result<(), String> {
+        let mut read = 0_usize;
+        let ok = ReadProcessMemory(
+            handle,
+            address as *const c_void,
+            value as *mut T as *mut c_void,
+            std::mem::size_of::<T>(),
+            &mut read,
+        );
+        if ok == 0 || read != std::mem::size_of::<T>() {
+            return Err("failed to read process memory".to_string());
+        }
+        Ok(())
+    }
+
+    pub fn process_cwd(pid: u32) -> Result<String, String> {
+        unsafe {
+            let raw = OpenProcess(PROCESS_QUERY_INFORMATION | PROCESS_VM_READ, 0, pid);
+            if raw.is_null() {
+                return Err(format!("failed to open process {pid}"));
+            }
+            let handle = ProcessHandle(raw);
+
+            let mut info: PROCESS_BASIC_INFORMATION = std::mem::zeroed();
+            let mut returned = 0_u32;
+            let status = NtQueryInformationProcess(
+                handle.0,
+                ProcessBasicInformation,
+                &mut info as *mut PROCESS_BASIC_INFORMATION as *mut c_void,
+                std::mem::size_of::<PROCESS_BASIC_INFORMATION>() as u32,
+                &mut returned,
+            );
+            if status < 0 {
+                return Err(format!("failed to query process information: {status:#x}"));
+            }
+
+            let mut parameters = 0_usize;
+            read_memory(
+                handle.0,
+                info.PebBaseAddress as usize + PEB_PROCESS_PARAMETERS_OFFSET,
+                &mut parameters,
+            )?;
+
+            let mut current_directory = UnicodeString::default();
+            read_memory(
+                handle.0,
+                parameters + PARAMETERS_CURRENT_DIRECTORY_OFFSET,
+                &mut current_directory,
+            )?;
+
+            let mut buffer = vec![0_u16; usize::from(current_directory.length) / 2];
+            let mut read = 0_usize;
+            let ok = ReadProcessMemory(
+                handle.0,
+                current_directory.buffer as *const c_void,
+                buffer.as_mut_ptr() as *mut c_void,
+                usize::from(current_directory.length),
+                &mut read,
+            );
+            if ok == 0 {
+                return Err("failed to read process cwd".to_string());
+            }
+
+            let cwd = String::from_utf16_lossy(&buffer);
+            // The PEB keeps a trailing separator that callers don't expect, except for drive roots.
+            let trimmed = if cwd.len() > 3 { cwd.trim_end_matches('\\') } else { cwd.as_str() };
+            Ok(trimmed.to_string())
+        }
+    }
+
+    /// Lists `(pid, parent pid, exe name)` for every running process.
+    fn process_entries() -> Vec<(u32, u32, String)> {
+        let mut entries = Vec::new();
+        unsafe {
+            let raw = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0);
+            if raw == INVALID_HANDLE_VALUE {
+                return entries;
+            }
+            let snapshot = ProcessHandle(raw);
+
+            let mut entry: PROCESSENTRY32W = std::mem::zeroed();
+            entry.dwSize = std::mem::size_of::<PROCESSENTRY32W>() as u32;
+            let mut ok = Process32FirstW(snapshot.0, &mut entry);
+            while ok != 0 {
+                let len = entry.szExeFile.iter().position(|c| *c == 0).unwrap_or(entry.szExeFile.len());
+                let name = String::from_utf16_lossy(&entry.szExeFile[..len]);
+                entries.push((entry.th32ProcessID, entry.th32ParentProcessID, name));
+                ok = Process32NextW(snapshot.0, &mut entry);
+            }
+        }
+        entries
+    }
+
+    pub fn foreground_descendant(pid: u32) -> Option<(u32, String)> {
+        let entries = process_entries();
+        let mut current = None;
+        let mut parent = pid;
+        // Snapshot order follows creation order, so the last child is the newest one.
+        while let Some((child, _, name)) = entries.iter().rev().find(|(_, ppid, _)| *ppid == parent) {
+            if current.as_ref().is_some_and(|(seen, _)| seen == child) {
+                break;
+            }
+            current = Some((*child, name.clone()));
+            parent = *child;
+        }
+        current
+    }
+
+    pub fn terminate(pid: u32) -> Result<(), String> {
+        unsafe {
+            let raw = OpenProcess(PROCESS_TERMINATE, 0, pid);
+            if raw.is_null() {
+                return Err(format!("failed to open process {pid}"));
+            }
+            let handle = ProcessHandle(raw);
+            if TerminateProcess(handle.0, 1) == 0 {
+                return Err(format!("failed to terminate process {pid}"));
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,113 @@
+use crate::error::AppError;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, path::PathBuf};
+use tauri::Manager;
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShellProfile {
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    pub shell: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    #[serde(default)]
+    pub cwd: Option<String>,
+    /// Start the shell as a login shell (`-l`), so profile files such as `.bash_profile` are read.
+    #[serde(default)]
+    pub login: bool,
+    /// Load the bundled bash, zsh or fish integration scripts that report cwd and prompt marks.
+    #[serde(default)]
+    pub shell_integration: bool,
+}
+
+fn profiles_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_config_dir()
+        .map_err(|error| format!("failed to resolve config dir: {error}"))?;
+    Ok(dir.join("profiles.json"))
+}
+
+pub fn load_profiles(app: &tauri::AppHandle) -> Result<Vec<ShellProfile>, String> {
+    let path = profiles_path(app)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let raw = std::fs::read_to_string(&path).map_err(|error| format!("failed to read profiles: {error}"))?;
+    serde_json::from_str(&raw).map_err(|error| format!("failed to parse profiles: {error}"))
+}
+
+fn store_profiles(app: &tauri::AppHandle, profiles: &[ShellProfile]) -> Result<(), String> {
+    let path = profiles_path(app)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|error| format!("failed to create config dir: {error}"))?;
+    }
+
+    let raw = serde_json::to_string_pretty(profiles).map_err(|error| format!("failed to encode profiles: {error}"))?;
+    std::fs::write(&path, raw).map_err(|error| format!("failed to write profiles: {error}"))?;
+    Ok(())
+}
+
+pub fn find_profile(app: &tauri::AppHandle, id: &str) -> Result<ShellProfile, String> {
+    load_profiles(app)?
+        .into_iter()
+        .find(|profile| profile.id == id)
+        .ok_or_else(|| format!("profile not found: {id}"))
+}
+
+fn generate_profile_id() -> String {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_nanos())
+        .unwrap_or(0);
+    format!("profile-{nanos:x}")
+}
+
+#[tauri::command]
+pub fn list_profiles(app: tauri::AppHandle) -> Result<Vec<ShellProfile>, AppError> {
+    Ok(load_profiles(&app)?)
+}
+
+#[tauri::command]
+pub fn save_profile(profile: ShellProfile, app: tauri::AppHandle) -> Result<ShellProfile, AppError> {
+    let mut profile = profile;
+    profile.name = profile.name.trim().to_string();
+    profile.shell = profile.shell.trim().to_string();
+
+    if profile.name.is_empty() {
+        return Err(AppError::invalid("profile name is empty"));
+    }
+    if profile.shell.is_empty() {
+        return Err(AppError::invalid("profile shell is empty"));
+    }
+    if profile.id.trim().is_empty() {
+        profile.id = generate_profile_id();
+    }
+
+    let mut profiles = load_profiles(&app)?;
+    match profiles.iter_mut().find(|existing| existing.id == profile.id) {
+        Some(existing) => *existing = profile.clone(),
+        None => profiles.push(profile.clone()),
+    }
+    store_profiles(&app, &profiles)?;
+
+    Ok(profile)
+}
+
+#[tauri::command]
+pub fn delete_profile(id: String, app: tauri::AppHandle) -> Result<(), AppError> {
+    let mut profiles = load_profiles(&app)?;
+    let before = profiles.len();
+    profiles.retain(|profile| profile.id != id);
+
+    if profiles.len() == before {
+        return Err(AppError::invalid(format!("profile not found: {id}")));
+    }
+
+    Ok(store_profiles(&app, &profiles)?)
+}
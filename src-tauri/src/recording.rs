@@ -0,0 +1,163 @@
+use crate::{
+    error::AppError,
+    stream::Utf8Decoder,
+    terminal::{OutputObserver, TerminalState},
+};
+use serde_json::json;
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+/// Recorder slot shared between a session and its reader thread.
+pub type RecordingSlot = Arc<Mutex<Option<CastRecorder>>>;
+
+/// Writes an asciinema v2 `.cast` file: a JSON header line followed by one
+/// `[seconds, code, data]` line per event.
+pub struct CastRecorder {
+    file: BufWriter<File>,
+    path: PathBuf,
+    started_at: Instant,
+    record_input: bool,
+    decoder: Utf8Decoder,
+}
+
+impl CastRecorder {
+    pub fn create(path: &Path, cols: u16, rows: u16, shell: &str, record_input: bool) -> Result<Self, String> {
+        if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent).map_err(|error| format!("failed to create recording dir: {error}"))?;
+        }
+        let file = File::create(path).map_err(|error| format!("failed to create recording: {error}"))?;
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or_default();
+
+        let mut recorder = Self {
+            file: BufWriter::new(file),
+            path: path.to_path_buf(),
+            started_at: Instant::now(),
+            record_input,
+            decoder: Utf8Decoder::default(),
+        };
+        let header = json!({
+            "version": 2,
+            "width": cols,
+            "height": rows,
+            "timestamp": timestamp,
+            "env": { "SHELL": shell, "TERM": "xterm-256color" },
+        });
+        writeln!(recorder.file, "{header}").map_err(|error| format!("failed to write recording: {error}"))?;
+        Ok(recorder)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn event(&mut self, code: &str, data: &str) {
+        let time = self.started_at.elapsed().as_secs_f64();
+        let _ = writeln!(self.file, "{}", json!([time, code, data]));
+    }
+
+    pub fn output(&mut self, bytes: &[u8]) {
+        let data = self.decoder.decode(bytes);
+        if !data.is_empty() {
+            self.event("o", &data);
+        }
+    }
+
+    pub fn input(&mut self, bytes: &[u8]) {
+        if self.record_input {
+            self.event("i", &String::from_utf8_lossy(bytes));
+        }
+    }
+
+    pub fn resize(&mut self, cols: u16, rows: u16) {
+        self.event("r", &format!("{cols}x{rows}"));
+    }
+
+    pub fn finish(mut self) -> PathBuf {
+        let rest = self.decoder.finish();
+        if !rest.is_empty() {
+            self.event("o", &rest);
+        }
+        let _ = self.file.flush();
+        self.path
+    }
+}
+
+/// Feeds a session's output into its recorder, if one is running.
+pub struct RecordingObserver(pub RecordingSlot);
+
+impl OutputObserver for RecordingObserver {
+    fn observe(&mut self, _app: &tauri::AppHandle, _tab_id: &str, bytes: &[u8]) {
+        if let Ok(mut slot) = self.0.lock() {
+            if let Some(recorder) = slot.as_mut() {
+                recorder.output(bytes);
+            }
+        }
+    }
+
+    fn finish(&mut self, _app: &tauri::AppHandle, _tab_id: &str) {
+        if let Some(recorder) = self.0.lock().ok().and_then(|mut slot| slot.take()) {
+            recorder.finish();
+        }
+    }
+}
+
+#[tauri::command]
+pub fn start_recording(
+    tab_id: String,
+    path: String,
+    record_input: Option<bool>,
+    state: tauri::State<TerminalState>,
+) -> Result<(), AppError> {
+    let sessions = state
+        .sessions
+        .lock()
+        .map_err(|_| "failed to lock terminal sessions".to_string())?;
+
+    let session = sessions
+        .get(&tab_id)
+        .ok_or_else(|| AppError::session_not_found(&tab_id))?;
+    let mut slot = session
+        .recording
+        .lock()
+        .map_err(|_| "failed to lock recording".to_string())?;
+    if let Some(recorder) = slot.as_ref() {
+        return Err(AppError::invalid(format!("already recording to {}", recorder.path().display())));
+    }
+
+    let (cols, rows) = session.size();
+    *slot = Some(CastRecorder::create(
+        Path::new(&path),
+        cols,
+        rows,
+        &session.shell,
+        record_input.unwrap_or(false),
+    )?);
+    Ok(())
+}
+
+/// Stops recording a session and returns the path of the finished file.
+#[tauri::command]
+pub fn stop_recording(tab_id: String, state: tauri::State<TerminalState>) -> Result<Option<String>, AppError> {
+    let sessions = state
+        .sessions
+        .lock()
+        .map_err(|_| "failed to lock terminal sessions".to_string())?;
+
+    let Some(session) = sessions.get(&tab_id) else {
+        return Ok(None);
+    };
+    let recorder = session
+        .recording
+        .lock()
+        .map_err(|_| "failed to lock recording".to_string())?
+        .take();
+    Ok(recorder.map(|recorder| recorder.finish().to_string_lossy().to_string()))
+}
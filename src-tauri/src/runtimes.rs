@@ -0,0 +1,186 @@
+use crate::{
+    error::AppError,
+    process,
+    terminal::{self, TerminalState},
+};
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Runtime {
+    Python,
+    Node,
+    Ruby,
+}
+
+impl Runtime {
+    const ALL: [Runtime; 3] = [Runtime::Python, Runtime::Node, Runtime::Ruby];
+
+    /// Project files naming the version, as read by pyenv, nvm/fnm/nodenv and rbenv/chruby.
+    fn version_files(self) -> &'static [&'static str] {
+        match self {
+            Runtime::Python => &[".python-version"],
+            Runtime::Node => &[".nvmrc", ".node-version"],
+            Runtime::Ruby => &[".ruby-version"],
+        }
+    }
+
+    /// Plugin name in asdf's `.tool-versions`.
+    fn asdf_name(self) -> &'static str {
+        match self {
+            Runtime::Python => "python",
+            Runtime::Node => "nodejs",
+            Runtime::Ruby => "ruby",
+        }
+    }
+
+    /// Shell-local overrides set by `pyenv shell`, `nodenv shell` and `rbenv shell`.
+    fn manager_env(self) -> &'static str {
+        match self {
+            Runtime::Python => "PYENV_VERSION",
+            Runtime::Node => "NODENV_VERSION",
+            Runtime::Ruby => "RBENV_VERSION",
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuntimeVersion {
+    runtime: Runtime,
+    version: String,
+    /// Where the version came from, e.g. `VIRTUAL_ENV`, `.nvmrc` or `.tool-versions`.
+    source: String,
+}
+
+/// First meaningful line of a version file, with nvm's `v` prefix dropped.
+fn parse_version(content: &str) -> Option<String> {
+    let line = content
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !line.starts_with('#'))?;
+    let version = line.split_whitespace().next()?;
+    let version = match version.strip_prefix('v') {
+        Some(rest) if rest.starts_with(|c: char| c.is_ascii_digit()) => rest,
+        _ => version,
+    };
+    Some(version.to_string())
+}
+
+fn tool_versions_entry(content: &str, name: &str) -> Option<String> {
+    content.lines().find_map(|line| {
+        let mut fields = line.split_whitespace();
+        if fields.next()? != name {
+            return None;
+        }
+        fields.next().map(str::to_string)
+    })
+}
+
+/// Version of an activated environment: a virtualenv, an nvm-managed node on `PATH` or an
+/// rvm/chruby ruby.
+fn activated(runtime: Runtime, env: &HashMap<String, String>) -> Option<RuntimeVersion> {
+    let (version, source) = match runtime {
+        Runtime::Python => {
+            let venv = env.get("VIRTUAL_ENV")?;
+            let config = std::fs::read_to_string(Path::new(venv).join("pyvenv.cfg")).ok()?;
+            let version = config.lines().find_map(|line| {
+                let (key, value) = line.split_once('=')?;
+                matches!(key.trim(), "version" | "version_info").then(|| value.trim().to_string())
+            })?;
+            (version, "VIRTUAL_ENV")
+        }
+        Runtime::Node => {
+            // nvm puts `~/.nvm/versions/node/v20.11.0/bin` first on PATH.
+            let bin = Path::new(env.get("NVM_BIN")?);
+            let version = bin.parent()?.file_name()?.to_string_lossy().to_string();
+            (parse_version(&version)?, "NVM_BIN")
+        }
+        Runtime::Ruby => {
+            let version = env.get("RUBY_VERSION")?;
+            (version.trim_start_matches("ruby-").to_string(), "RUBY_VERSION")
+        }
+    };
+    Some(RuntimeVersion {
+        runtime,
+        version,
+        source: source.to_string(),
+    })
+}
+
+fn from_env(runtime: Runtime, env: &HashMap<String, String>) -> Option<RuntimeVersion> {
+    let asdf_env = format!("ASDF_{}_VERSION", runtime.asdf_name().to_uppercase());
+    let found = [runtime.manager_env(), asdf_env.as_str()].into_iter().find_map(|name| {
+        Some(RuntimeVersion {
+            runtime,
+            version: parse_version(env.get(name)?)?,
+            source: name.to_string(),
+        })
+    });
+    found
+}
+
+/// Looks for version files from `cwd` up to the filesystem root; the nearest one wins.
+fn from_files(runtime: Runtime, cwd: &Path) -> Option<RuntimeVersion> {
+    cwd.ancestors().find_map(|dir| {
+        let named = runtime.version_files().iter().find_map(|name| {
+            let content = std::fs::read_to_string(dir.join(name)).ok()?;
+            Some((parse_version(&content)?, name.to_string()))
+        });
+        let (version, source) = named.or_else(|| {
+            let content = std::fs::read_to_string(dir.join(".tool-versions")).ok()?;
+            Some((tool_versions_entry(&content, runtime.asdf_name())?, ".tool-versions".to_string()))
+        })?;
+        Some(RuntimeVersion {
+            runtime,
+            version,
+            source,
+        })
+    })
+}
+
+/// Active versions in precedence order: activated environments, shell overrides, then project
+/// files. Runtimes with no version configured are left out.
+fn detect(cwd: Option<&Path>, env: &HashMap<String, String>) -> Vec<RuntimeVersion> {
+    Runtime::ALL
+        .into_iter()
+        .filter_map(|runtime| {
+            activated(runtime, env)
+                .or_else(|| from_env(runtime, env))
+                .or_else(|| from_files(runtime, cwd?))
+        })
+        .collect()
+}
+
+/// Python, Node and Ruby versions active in a local session, for the tab's status bar. The
+/// environment is the one the shell started with, so a virtualenv activated later is only found
+/// through project files.
+#[tauri::command]
+pub fn terminal_runtimes(
+    tab_id: String,
+    state: tauri::State<TerminalState>,
+) -> Result<Vec<RuntimeVersion>, AppError> {
+    let (cwd, pid) = {
+        let sessions = state
+            .sessions
+            .lock()
+            .map_err(|_| "failed to lock terminal sessions".to_string())?;
+        let session = sessions
+            .get(&tab_id)
+            .ok_or_else(|| AppError::session_not_found(&tab_id))?;
+        if !session.kind.is_local_process() {
+            return Ok(Vec::new());
+        }
+        (terminal::session_cwd(session)?, session.pid())
+    };
+
+    let env = pid
+        .and_then(|pid| process::process_environ(pid).ok())
+        .unwrap_or_default();
+    let cwd = cwd.map(PathBuf::from);
+    Ok(detect(cwd.as_deref(), &env))
+}
@@ -0,0 +1,186 @@
+use crate::{
+    error::AppError,
+    terminal::{OutputObserver, TerminalState},
+};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+
+/// Grid state of a session, fed by its reader thread. History is left to `Scrollback`, so the
+/// parser only tracks the visible screen.
+pub type ScreenSlot = Arc<Mutex<vt100::Parser>>;
+
+pub fn new_screen(rows: u16, cols: u16) -> ScreenSlot {
+    Arc::new(Mutex::new(vt100::Parser::new(rows.max(1), cols.max(1), 0)))
+}
+
+pub fn resize_screen(screen: &ScreenSlot, rows: u16, cols: u16) {
+    if let Ok(mut parser) = screen.lock() {
+        parser.set_size(rows.max(1), cols.max(1));
+    }
+}
+
+pub struct ScreenObserver(pub ScreenSlot);
+
+impl OutputObserver for ScreenObserver {
+    fn observe(&mut self, _app: &tauri::AppHandle, _tab_id: &str, bytes: &[u8]) {
+        if let Ok(mut parser) = self.0.lock() {
+            parser.process(bytes);
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CursorPosition {
+    row: u16,
+    col: u16,
+    visible: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScreenContents {
+    rows: u16,
+    cols: u16,
+    /// Plain text of each visible row, without trailing blanks.
+    lines: Vec<String>,
+    /// Escape sequences that redraw the screen with its attributes, for reattaching a renderer.
+    formatted: Option<String>,
+    cursor: CursorPosition,
+    alternate_screen: bool,
+    title: String,
+}
+
+/// A cell on the visible screen, zero based.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GridPoint {
+    row: u16,
+    col: u16,
+}
+
+fn row_text(screen: &vt100::Screen, row: u16, cols: std::ops::RangeInclusive<u16>) -> String {
+    let mut text = String::new();
+    for col in cols {
+        let Some(cell) = screen.cell(row, col) else {
+            break;
+        };
+        if cell.is_wide_continuation() {
+            continue;
+        }
+        let contents = cell.contents();
+        if contents.is_empty() {
+            text.push(' ');
+        } else {
+            text.push_str(&contents);
+        }
+    }
+    text
+}
+
+/// Text between two cells, inclusive. Soft-wrapped rows are joined back into one line and
+/// trailing blanks are dropped; rectangular regions take the same columns from every row.
+fn region_text(screen: &vt100::Screen, start: GridPoint, end: GridPoint, rectangular: bool) -> String {
+    let (rows, cols) = screen.size();
+    let last_col = cols.saturating_sub(1);
+    let last_row = rows.saturating_sub(1);
+
+    if rectangular {
+        let first_col = start.col.min(end.col).min(last_col);
+        let end_col = start.col.max(end.col).min(last_col);
+        return (start.row.min(end.row)..=start.row.max(end.row).min(last_row))
+            .map(|row| row_text(screen, row, first_col..=end_col).trim_end().to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+    }
+
+    let (start, end) = if start <= end { (start, end) } else { (end, start) };
+    let end_row = end.row.min(last_row);
+    let mut text = String::new();
+    for row in start.row..=end_row {
+        let first_col = if row == start.row { start.col.min(last_col) } else { 0 };
+        let end_col = if row == end.row {
+            end.col.min(last_col)
+        } else {
+            last_col
+        };
+        let line = row_text(screen, row, first_col..=end_col);
+
+        if row < end_row && screen.row_wrapped(row) {
+            text.push_str(&line);
+        } else {
+            text.push_str(line.trim_end());
+            if row < end_row {
+                text.push('\n');
+            }
+        }
+    }
+    text
+}
+
+fn cursor(screen: &vt100::Screen) -> CursorPosition {
+    let (row, col) = screen.cursor_position();
+    CursorPosition {
+        row,
+        col,
+        visible: !screen.hide_cursor(),
+    }
+}
+
+fn with_screen<T>(state: &TerminalState, tab_id: &str, read: impl FnOnce(&vt100::Screen) -> T) -> Result<T, AppError> {
+    let screen = {
+        let sessions = state
+            .sessions
+            .lock()
+            .map_err(|_| "failed to lock terminal sessions".to_string())?;
+        let session = sessions
+            .get(tab_id)
+            .ok_or_else(|| AppError::session_not_found(tab_id))?;
+        Arc::clone(&session.screen)
+    };
+
+    let parser = screen
+        .lock()
+        .map_err(|_| "failed to lock terminal screen".to_string())?;
+    Ok(read(parser.screen()))
+}
+
+#[tauri::command]
+pub fn get_screen_contents(
+    tab_id: String,
+    formatted: Option<bool>,
+    state: tauri::State<TerminalState>,
+) -> Result<ScreenContents, AppError> {
+    with_screen(&state, &tab_id, |screen| {
+        let (rows, cols) = screen.size();
+        ScreenContents {
+            rows,
+            cols,
+            lines: screen.rows(0, cols).map(|line| line.trim_end().to_string()).collect(),
+            formatted: formatted
+                .unwrap_or(false)
+                .then(|| String::from_utf8_lossy(&screen.contents_formatted()).to_string()),
+            cursor: cursor(screen),
+            alternate_screen: screen.alternate_screen(),
+            title: screen.title().to_string(),
+        }
+    })
+}
+
+#[tauri::command]
+pub fn get_cursor_position(tab_id: String, state: tauri::State<TerminalState>) -> Result<CursorPosition, AppError> {
+    with_screen(&state, &tab_id, cursor)
+}
+
+#[tauri::command]
+pub fn get_text_in_region(
+    tab_id: String,
+    start: GridPoint,
+    end: GridPoint,
+    rectangular: Option<bool>,
+    state: tauri::State<TerminalState>,
+) -> Result<String, AppError> {
+    with_screen(&state, &tab_id, |screen| {
+        region_text(screen, start, end, rectangular.unwrap_or(false))
+    })
+}
@@ -0,0 +1,210 @@
+use crate::stream::Utf8Decoder;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+const DEFAULT_MAX_LINES: usize = 10_000;
+const DEFAULT_MAX_BYTES: usize = 4 * 1024 * 1024;
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScrollbackLimits {
+    #[serde(default = "default_max_lines")]
+    pub max_lines: usize,
+    #[serde(default = "default_max_bytes")]
+    pub max_bytes: usize,
+}
+
+fn default_max_lines() -> usize {
+    DEFAULT_MAX_LINES
+}
+
+fn default_max_bytes() -> usize {
+    DEFAULT_MAX_BYTES
+}
+
+impl Default for ScrollbackLimits {
+    fn default() -> Self {
+        Self {
+            max_lines: DEFAULT_MAX_LINES,
+            max_bytes: DEFAULT_MAX_BYTES,
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScrollbackRange {
+    /// Absolute index of the first returned line since the session started.
+    start: usize,
+    end: usize,
+    total: usize,
+    lines: Vec<String>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScrollbackMatch {
+    /// Absolute line index, as used by `range`.
+    line: usize,
+    /// Character offsets into the line with escape sequences removed.
+    start: usize,
+    end: usize,
+}
+
+/// Removes CSI, OSC and other escape sequences plus carriage returns, leaving the printed text.
+pub fn strip_escapes(line: &str) -> String {
+    let mut text = String::with_capacity(line.len());
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\x1b' => match chars.next() {
+                Some('[') => {
+                    for c in chars.by_ref() {
+                        if ('\x40'..='\x7e').contains(&c) {
+                            break;
+                        }
+                    }
+                }
+                Some(']') => {
+                    while let Some(c) = chars.next() {
+                        if c == '\x07' || (c == '\x1b' && chars.next_if_eq(&'\\').is_some()) {
+                            break;
+                        }
+                    }
+                }
+                _ => {}
+            },
+            '\r' => {}
+            c => text.push(c),
+        }
+    }
+    text
+}
+
+/// Line-oriented ring buffer of PTY output. Line indices are absolute, so they stay stable
+/// while old lines are evicted from the front.
+pub struct Scrollback {
+    limits: ScrollbackLimits,
+    decoder: Utf8Decoder,
+    lines: VecDeque<String>,
+    partial: String,
+    bytes: usize,
+    evicted: usize,
+}
+
+impl ScrollbackRange {
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+}
+
+impl Scrollback {
+    pub fn new(limits: ScrollbackLimits) -> Self {
+        Self {
+            limits,
+            decoder: Utf8Decoder::default(),
+            lines: VecDeque::new(),
+            partial: String::new(),
+            bytes: 0,
+            evicted: 0,
+        }
+    }
+
+    pub fn push(&mut self, bytes: &[u8]) {
+        let text = self.decoder.decode(bytes);
+        let mut segments = text.split('\n').peekable();
+
+        while let Some(segment) = segments.next() {
+            self.partial.push_str(segment);
+            self.bytes += segment.len();
+
+            if segments.peek().is_some() || self.partial.len() > self.limits.max_bytes {
+                let line = std::mem::take(&mut self.partial);
+                self.lines.push_back(line);
+            }
+        }
+
+        self.evict();
+    }
+
+    fn evict(&mut self) {
+        while self.lines.len() > self.limits.max_lines
+            || (self.bytes > self.limits.max_bytes && !self.lines.is_empty())
+        {
+            if let Some(line) = self.lines.pop_front() {
+                self.bytes -= line.len();
+                self.evicted += 1;
+            }
+        }
+    }
+
+    /// Lines seen so far, counting evicted ones and the one still being written.
+    pub fn total(&self) -> usize {
+        self.evicted + self.lines.len() + usize::from(!self.partial.is_empty())
+    }
+
+    pub fn range(&self, from: Option<usize>, to: Option<usize>) -> ScrollbackRange {
+        let total = self.total();
+        let start = from.unwrap_or(self.evicted).clamp(self.evicted, total);
+        let end = to.unwrap_or(total).clamp(start, total);
+
+        let lines = (start..end)
+            .map(|index| {
+                let offset = index - self.evicted;
+                self.lines
+                    .get(offset)
+                    .cloned()
+                    .unwrap_or_else(|| self.partial.clone())
+            })
+            .collect();
+
+        ScrollbackRange {
+            start,
+            end,
+            total,
+            lines,
+        }
+    }
+
+    pub fn tail(&self, count: usize) -> Vec<String> {
+        let total = self.total();
+        self.range(Some(total.saturating_sub(count)), None).lines
+    }
+
+    /// Finds matches of `pattern` across the retained lines, oldest first, stopping after `limit`.
+    pub fn search(&self, pattern: &Regex, limit: usize) -> Vec<ScrollbackMatch> {
+        let partial = Some(&self.partial).filter(|partial| !partial.is_empty());
+        let mut matches = Vec::new();
+
+        for (offset, line) in self.lines.iter().chain(partial).enumerate() {
+            let text = strip_escapes(line);
+            for found in pattern.find_iter(&text) {
+                if matches.len() >= limit {
+                    return matches;
+                }
+                let start = text[..found.start()].chars().count();
+                matches.push(ScrollbackMatch {
+                    line: self.evicted + offset,
+                    start,
+                    end: start + found.as_str().chars().count(),
+                });
+            }
+        }
+        matches
+    }
+
+    /// Drops everything retained. Only complete lines count as evicted, so the line being written
+    /// keeps its index once output continues.
+    pub fn clear(&mut self) {
+        self.evicted += self.lines.len();
+        self.lines.clear();
+        self.partial.clear();
+        self.bytes = 0;
+    }
+}
@@ -0,0 +1,219 @@
+use crate::{
+    error::AppError,
+    scrollback::ScrollbackLimits,
+    session_store,
+    stream::OutputEncoding,
+    terminal::{self, OpenTerminalResponse, SessionIo, SessionKind, SpawnOptions, TerminalSession, TerminalState},
+    windows,
+};
+use portable_pty::ChildKiller;
+use serde::{Deserialize, Serialize};
+use serialport::{DataBits, FlowControl, Parity, SerialPortType, StopBits};
+use std::{
+    io::{ErrorKind, Read},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc,
+    },
+    time::Duration,
+};
+
+/// Read timeout of the port; the reader checks for a close this often.
+const READ_TIMEOUT: Duration = Duration::from_millis(200);
+
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SerialParity {
+    #[default]
+    None,
+    Odd,
+    Even,
+}
+
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SerialFlowControl {
+    #[default]
+    None,
+    Software,
+    Hardware,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SerialTarget {
+    pub port: String,
+    pub baud: u32,
+    #[serde(default)]
+    pub parity: SerialParity,
+    #[serde(default)]
+    pub flow_control: SerialFlowControl,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SerialPortEntry {
+    name: String,
+    kind: String,
+    manufacturer: Option<String>,
+    product: Option<String>,
+    serial_number: Option<String>,
+}
+
+/// Closes a serial session by telling its reader to stop; there is no process to kill.
+#[derive(Clone, Debug)]
+struct SerialKiller(Arc<AtomicBool>);
+
+impl ChildKiller for SerialKiller {
+    fn kill(&mut self) -> std::io::Result<()> {
+        self.0.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+
+    fn clone_killer(&self) -> Box<dyn ChildKiller + Send + Sync> {
+        Box::new(self.clone())
+    }
+}
+
+/// Turns read timeouts into retries and reports end of stream once the session is closed.
+struct SerialReader {
+    port: Box<dyn serialport::SerialPort>,
+    closed: Arc<AtomicBool>,
+}
+
+impl Read for SerialReader {
+    fn read(&mut self, buffer: &mut [u8]) -> std::io::Result<usize> {
+        loop {
+            if self.closed.load(Ordering::SeqCst) {
+                return Ok(0);
+            }
+            match self.port.read(buffer) {
+                Err(error) if error.kind() == ErrorKind::TimedOut => continue,
+                result => return result,
+            }
+        }
+    }
+}
+
+pub fn spawn_serial_session(
+    app: &tauri::AppHandle,
+    tab_id: &str,
+    target: SerialTarget,
+    encoding: OutputEncoding,
+    scrollback: ScrollbackLimits,
+    scrollback_seed: Vec<String>,
+) -> Result<TerminalSession, String> {
+    let port = serialport::new(&target.port, target.baud)
+        .data_bits(DataBits::Eight)
+        .stop_bits(StopBits::One)
+        .parity(match target.parity {
+            SerialParity::None => Parity::None,
+            SerialParity::Odd => Parity::Odd,
+            SerialParity::Even => Parity::Even,
+        })
+        .flow_control(match target.flow_control {
+            SerialFlowControl::None => FlowControl::None,
+            SerialFlowControl::Software => FlowControl::Software,
+            SerialFlowControl::Hardware => FlowControl::Hardware,
+        })
+        .timeout(READ_TIMEOUT)
+        .open()
+        .map_err(|error| format!("failed to open {}: {error}", target.port))?;
+    let reader = port
+        .try_clone()
+        .map_err(|error| format!("failed to clone serial port: {error}"))?;
+
+    let closed = Arc::new(AtomicBool::new(false));
+    // No exit status: dropping the sender lets the emitter finish without waiting.
+    let (_, exited) = mpsc::channel();
+    let io = SessionIo {
+        reader: Box::new(SerialReader {
+            port: reader,
+            closed: Arc::clone(&closed),
+        }),
+        writer: Box::new(port),
+        master: None,
+        killer: Box::new(SerialKiller(closed)),
+        pid: None,
+        exited,
+    };
+
+    let name = target.port.clone();
+    Ok(terminal::start_session(
+        app,
+        tab_id,
+        name,
+        io,
+        SpawnOptions {
+            kind: SessionKind::Serial(target),
+            encoding,
+            scrollback,
+            scrollback_seed,
+            ..SpawnOptions::default()
+        },
+    ))
+}
+
+#[tauri::command]
+pub fn list_serial_ports() -> Result<Vec<SerialPortEntry>, AppError> {
+    let ports = serialport::available_ports().map_err(|error| format!("failed to list serial ports: {error}"))?;
+
+    Ok(ports
+        .into_iter()
+        .map(|port| {
+            let (kind, usb) = match port.port_type {
+                SerialPortType::UsbPort(info) => ("usb", Some(info)),
+                SerialPortType::PciPort => ("pci", None),
+                SerialPortType::BluetoothPort => ("bluetooth", None),
+                SerialPortType::Unknown => ("unknown", None),
+            };
+            SerialPortEntry {
+                name: port.port_name,
+                kind: kind.to_string(),
+                manufacturer: usb.as_ref().and_then(|info| info.manufacturer.clone()),
+                product: usb.as_ref().and_then(|info| info.product.clone()),
+                serial_number: usb.and_then(|info| info.serial_number),
+            }
+        })
+        .collect())
+}
+
+#[tauri::command]
+pub fn open_serial_terminal(
+    tab_id: String,
+    target: SerialTarget,
+    encoding: Option<OutputEncoding>,
+    scrollback: Option<ScrollbackLimits>,
+    app: tauri::AppHandle,
+    webview_window: tauri::WebviewWindow,
+    state: tauri::State<TerminalState>,
+) -> Result<OpenTerminalResponse, AppError> {
+    let mut sessions = state
+        .sessions
+        .lock()
+        .map_err(|_| "failed to lock terminal sessions".to_string())?;
+
+    windows::claim(&app, &tab_id, webview_window.label());
+    if let Some(session) = sessions.get(&tab_id) {
+        return Ok(OpenTerminalResponse {
+            shell: session.shell.clone(),
+        });
+    }
+
+    let session = spawn_serial_session(
+        &app,
+        &tab_id,
+        target,
+        encoding.unwrap_or_default(),
+        scrollback.unwrap_or_default(),
+        Vec::new(),
+    )
+    .map_err(AppError::pty)
+    .inspect_err(|_| windows::forget_tab(&app, &tab_id))?;
+    let shell = session.shell.clone();
+
+    sessions.insert(tab_id, session);
+    let _ = session_store::persist(&app, &sessions);
+
+    Ok(OpenTerminalResponse { shell })
+}
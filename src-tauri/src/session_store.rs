@@ -0,0 +1,203 @@
+use crate::{
+    error::AppError,
+    scrollback::ScrollbackLimits,
+    serial, ssh,
+    stream::OutputEncoding,
+    tcp,
+    terminal::{self, SessionKind, SpawnOptions, TerminalSession, TerminalState},
+    windows,
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, path::PathBuf};
+use tauri::Manager;
+
+const SCROLLBACK_TAIL_LINES: usize = 500;
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionRecord {
+    tab_id: String,
+    shell: String,
+    #[serde(default)]
+    cwd: Option<String>,
+    #[serde(default)]
+    profile_id: Option<String>,
+    #[serde(default)]
+    kind: SessionKind,
+    #[serde(default)]
+    encoding: OutputEncoding,
+    #[serde(default)]
+    scrollback_tail: Vec<String>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoredSession {
+    tab_id: String,
+    shell: String,
+    cwd: Option<String>,
+    scrollback_tail: Vec<String>,
+}
+
+fn sessions_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|error| format!("failed to resolve data dir: {error}"))?;
+    Ok(dir.join("sessions.json"))
+}
+
+fn record_session(tab_id: &str, session: &TerminalSession) -> SessionRecord {
+    let scrollback_tail = session
+        .scrollback
+        .lock()
+        .map(|scrollback| scrollback.tail(SCROLLBACK_TAIL_LINES))
+        .unwrap_or_default();
+
+    SessionRecord {
+        tab_id: tab_id.to_string(),
+        shell: session.shell.clone(),
+        cwd: terminal::session_cwd(session).ok().flatten(),
+        profile_id: session.profile_id.clone(),
+        kind: session.kind.clone(),
+        encoding: session.encoding,
+        scrollback_tail,
+    }
+}
+
+/// Writes the current tab layout to disk. Callers pass the already locked session map.
+pub fn persist(app: &tauri::AppHandle, sessions: &HashMap<String, TerminalSession>) -> Result<(), String> {
+    let mut ordered = sessions.iter().collect::<Vec<_>>();
+    ordered.sort_by_key(|(_, session)| session.opened_at);
+
+    let records = ordered
+        .into_iter()
+        .map(|(tab_id, session)| record_session(tab_id, session))
+        .collect::<Vec<SessionRecord>>();
+
+    let path = sessions_path(app)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|error| format!("failed to create data dir: {error}"))?;
+    }
+
+    let raw = serde_json::to_string(&records).map_err(|error| format!("failed to encode sessions: {error}"))?;
+    std::fs::write(&path, raw).map_err(|error| format!("failed to write sessions: {error}"))
+}
+
+pub fn save_sessions(app: &tauri::AppHandle, state: &TerminalState) -> Result<(), String> {
+    let sessions = state
+        .sessions
+        .lock()
+        .map_err(|_| "failed to lock terminal sessions".to_string())?;
+    persist(app, &sessions)
+}
+
+fn load_records(app: &tauri::AppHandle) -> Result<Vec<SessionRecord>, String> {
+    let path = sessions_path(app)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let raw = std::fs::read_to_string(&path).map_err(|error| format!("failed to read sessions: {error}"))?;
+    serde_json::from_str(&raw).map_err(|error| format!("failed to parse sessions: {error}"))
+}
+
+fn restore_local(app: &tauri::AppHandle, record: &SessionRecord) -> Result<TerminalSession, String> {
+    let options = SpawnOptions {
+        profile_id: record.profile_id.clone(),
+        cwd: record.cwd.clone(),
+        encoding: record.encoding,
+        scrollback_seed: record.scrollback_tail.clone(),
+        ..SpawnOptions::default()
+    };
+
+    // A profile may have been deleted since the layout was saved; fall back to the default shell.
+    match terminal::spawn_session(app, &record.tab_id, options) {
+        Ok(session) => Ok(session),
+        Err(_) => terminal::spawn_session(
+            app,
+            &record.tab_id,
+            SpawnOptions {
+                cwd: record.cwd.clone(),
+                encoding: record.encoding,
+                scrollback_seed: record.scrollback_tail.clone(),
+                ..SpawnOptions::default()
+            },
+        ),
+    }
+}
+
+#[tauri::command]
+pub fn restore_sessions(
+    app: tauri::AppHandle,
+    webview_window: tauri::WebviewWindow,
+    state: tauri::State<TerminalState>,
+) -> Result<Vec<RestoredSession>, AppError> {
+    let records = load_records(&app)?;
+
+    let mut sessions = state
+        .sessions
+        .lock()
+        .map_err(|_| "failed to lock terminal sessions".to_string())?;
+
+    let mut restored = Vec::new();
+
+    for record in records {
+        if sessions.contains_key(&record.tab_id) {
+            continue;
+        }
+
+        // Claimed before spawning so the first output already goes to this window.
+        windows::claim(&app, &record.tab_id, webview_window.label());
+        let spawned = match record.kind.clone() {
+            SessionKind::Ssh(target) => ssh::spawn_ssh_session(
+                &app,
+                &record.tab_id,
+                target,
+                record.encoding,
+                ScrollbackLimits::default(),
+                record.scrollback_tail.clone(),
+            ),
+            SessionKind::Local => restore_local(&app, &record),
+            // One-off commands are not rerun on startup, and tmux panes come back by reattaching.
+            SessionKind::Command(_) | SessionKind::Tmux(_) => {
+                windows::forget_tab(&app, &record.tab_id);
+                continue;
+            }
+            SessionKind::Serial(target) => serial::spawn_serial_session(
+                &app,
+                &record.tab_id,
+                target,
+                record.encoding,
+                ScrollbackLimits::default(),
+                record.scrollback_tail.clone(),
+            ),
+            SessionKind::Tcp(target) => tcp::spawn_tcp_session(
+                &app,
+                &record.tab_id,
+                target,
+                record.encoding,
+                ScrollbackLimits::default(),
+                record.scrollback_tail.clone(),
+            ),
+        };
+        // A host may be unreachable or a device unplugged by now; skip the record rather than
+        // failing the whole restore, which would hide the sessions already started.
+        let Ok(session) = spawned else {
+            windows::forget_tab(&app, &record.tab_id);
+            continue;
+        };
+
+        restored.push(RestoredSession {
+            tab_id: record.tab_id.clone(),
+            shell: session.shell.clone(),
+            cwd: record.cwd,
+            scrollback_tail: record.scrollback_tail,
+        });
+        sessions.insert(record.tab_id, session);
+    }
+
+    persist(&app, &sessions)?;
+
+    Ok(restored)
+}
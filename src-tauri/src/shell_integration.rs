@@ -0,0 +1,665 @@
+use crate::{
+    git::{self, RepoHead},
+    notifications,
+    terminal::OutputObserver,
+    windows,
+};
+use portable_pty::CommandBuilder;
+use serde::Serialize;
+use std::{
+    collections::{HashMap, VecDeque},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+use tauri::Manager;
+
+const MAX_OSC_LEN: usize = 4096;
+/// Bells closer together than this are folded into one event.
+const BELL_INTERVAL: Duration = Duration::from_millis(500);
+
+const BASH_SCRIPT: &str = include_str!("../shell-integration/bash.sh");
+const FISH_SCRIPT: &str = include_str!("../shell-integration/fish.fish");
+const ZSH_FILES: [(&str, &str); 3] = [
+    (".zshenv", include_str!("../shell-integration/zsh/.zshenv")),
+    (".zprofile", include_str!("../shell-integration/zsh/.zprofile")),
+    (".zshrc", include_str!("../shell-integration/zsh/.zshrc")),
+];
+/// Hyperlinks remembered per session before the oldest are forgotten.
+const MAX_HYPERLINKS: usize = 1024;
+/// Commands remembered per session for `get_command_history`.
+const MAX_COMMANDS: usize = 500;
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TerminalBellEvent {
+    tab_id: String,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TerminalNotificationEvent {
+    tab_id: String,
+    title: Option<String>,
+    body: String,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TerminalCwdEvent {
+    tab_id: String,
+    cwd: String,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TerminalRepoEvent {
+    tab_id: String,
+    cwd: String,
+    /// `None` once the shell leaves a repository.
+    repo: Option<RepoHead>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TerminalTitleEvent {
+    tab_id: String,
+    title: String,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TerminalHyperlinkEvent {
+    tab_id: String,
+    id: String,
+    uri: String,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TerminalCommandEvent {
+    tab_id: String,
+    exit_code: Option<i32>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TerminalCommandCompletedEvent {
+    tab_id: String,
+    exit_code: Option<i32>,
+    elapsed_ms: u64,
+}
+
+#[derive(Clone, Copy, Default)]
+enum ScanState {
+    #[default]
+    Ground,
+    Escape,
+    Csi,
+    Osc,
+    OscEscape,
+}
+
+/// What a session's shell has reported about itself, kept after the process is gone.
+#[derive(Default)]
+pub struct ReportedState {
+    pub cwd: Option<String>,
+    /// Repository around `cwd` as last sent in `terminal-repo-changed`.
+    pub repo: Option<RepoHead>,
+    /// Last title set through OSC 0/2.
+    pub title: Option<String>,
+    /// Name given by the user, which takes precedence over the reported title.
+    pub custom_title: Option<String>,
+    /// Whether the running application enabled bracketed paste (`CSI ? 2004 h`).
+    pub bracketed_paste: bool,
+    pub hyperlinks: HyperlinkRegistry,
+    /// Commands delimited by OSC 133 marks, oldest first. The last one may still be running.
+    pub commands: VecDeque<CommandRecord>,
+}
+
+/// A command seen through OSC 133 prompt marks. Lines are absolute scrollback indices.
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandRecord {
+    /// Command line as reported by the shell (`133;C;cmdline=...`), when it does so.
+    pub command: Option<String>,
+    pub prompt_line: Option<usize>,
+    pub output_start: usize,
+    /// Exclusive end of the output; `None` while the command is running.
+    pub output_end: Option<usize>,
+    /// Milliseconds since the Unix epoch.
+    pub started_at: u64,
+    pub duration_ms: Option<u64>,
+    pub exit_code: Option<i32>,
+    pub cwd: Option<String>,
+}
+
+/// OSC 8 hyperlinks seen in a session, keyed by the sequence's `id` parameter or, for links
+/// without one, by a generated `auto-<n>` id.
+#[derive(Default)]
+pub struct HyperlinkRegistry {
+    uris: HashMap<String, String>,
+    order: VecDeque<String>,
+    next_auto_id: u64,
+}
+
+impl HyperlinkRegistry {
+    fn insert(&mut self, id: Option<&str>, uri: &str) -> String {
+        let id = match id {
+            Some(id) => id.to_string(),
+            None => {
+                self.next_auto_id += 1;
+                format!("auto-{}", self.next_auto_id)
+            }
+        };
+
+        if self.uris.insert(id.clone(), uri.to_string()).is_none() {
+            self.order.push_back(id.clone());
+        }
+        while self.order.len() > MAX_HYPERLINKS {
+            if let Some(oldest) = self.order.pop_front() {
+                self.uris.remove(&oldest);
+            }
+        }
+        id
+    }
+
+    pub fn resolve(&self, id: &str) -> Option<String> {
+        self.uris.get(id).cloned()
+    }
+}
+
+impl ReportedState {
+    pub fn title(&self) -> Option<String> {
+        self.custom_title.clone().or_else(|| self.title.clone())
+    }
+}
+
+fn emit_notification(app: &tauri::AppHandle, tab_id: &str, title: Option<&str>, body: &str) {
+    let title = title.filter(|title| !title.is_empty());
+    windows::emit(
+        app,
+        tab_id,
+        "terminal-notification",
+        TerminalNotificationEvent {
+            tab_id: tab_id.to_string(),
+            title: title.map(str::to_string),
+            body: body.to_string(),
+        },
+    );
+    notifications::notify_terminal_alert(app, tab_id, title, body);
+}
+
+pub fn emit_title(app: &tauri::AppHandle, tab_id: &str, title: String) {
+    windows::emit(
+        app,
+        tab_id,
+        "terminal-title-changed",
+        TerminalTitleEvent {
+            tab_id: tab_id.to_string(),
+            title,
+        },
+    );
+}
+
+fn scripts_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|error| format!("failed to resolve data dir: {error}"))?;
+    Ok(dir.join("shell-integration"))
+}
+
+/// Writes a bundled script, leaving it alone when it is already up to date.
+fn write_script(path: &Path, contents: &str) -> Result<(), String> {
+    if std::fs::read_to_string(path).is_ok_and(|existing| existing == contents) {
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|error| format!("failed to create shell integration dir: {error}"))?;
+    }
+    std::fs::write(path, contents).map_err(|error| format!("failed to write shell integration script: {error}"))
+}
+
+/// Arranges for `shell` to load the integration scripts on startup, including the login flag
+/// where the shell needs it. Returns `false` for shells without a script, leaving `builder` untouched.
+pub fn install(app: &tauri::AppHandle, shell: &str, login: bool, builder: &mut CommandBuilder) -> Result<bool, String> {
+    let name = Path::new(shell)
+        .file_stem()
+        .map(|name| name.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default();
+    let dir = scripts_dir(app)?;
+
+    match name.as_str() {
+        "bash" => {
+            // `--rcfile` is ignored by login shells, so the script reads the login files itself.
+            let script = dir.join("bash.sh");
+            write_script(&script, BASH_SCRIPT)?;
+            if login {
+                builder.env("NLK_SHELL_LOGIN", "1");
+            }
+            builder.arg("--rcfile");
+            builder.arg(&script);
+        }
+        "zsh" => {
+            let zdotdir = dir.join("zsh");
+            for (file, contents) in ZSH_FILES {
+                write_script(&zdotdir.join(file), contents)?;
+            }
+            let user_zdotdir = builder
+                .get_env("ZDOTDIR")
+                .map(|value| value.to_os_string())
+                .or_else(|| std::env::var_os("ZDOTDIR"));
+            if let Some(user_zdotdir) = user_zdotdir {
+                builder.env("NLK_USER_ZDOTDIR", user_zdotdir);
+            }
+            builder.env("ZDOTDIR", &zdotdir);
+            if login {
+                builder.arg("-l");
+            }
+        }
+        "fish" => {
+            let script = dir.join("fish.fish");
+            write_script(&script, FISH_SCRIPT)?;
+            if login {
+                builder.arg("-l");
+            }
+            builder.arg("--init-command");
+            builder.arg(format!("source '{}'", script.display().to_string().replace('\'', "\\'")));
+        }
+        _ => return Ok(false),
+    }
+
+    Ok(true)
+}
+
+/// Scans PTY output for OSC 0/2 (title), OSC 7 (cwd), OSC 8 (hyperlinks), OSC 9/777 (notifications)
+/// and OSC 133 (prompt marks) sequences, and for bells and the bracketed paste mode switch.
+/// Sequences may be split across reads, so the parser keeps its state between calls.
+#[derive(Default)]
+pub struct OscScanner {
+    state: ScanState,
+    payload: Vec<u8>,
+    command_started_at: Option<Instant>,
+    reported: Arc<Mutex<ReportedState>>,
+    /// Newlines seen so far, which matches the scrollback's absolute line index.
+    line: usize,
+    /// Whether anything was printed on the current line, so output without a trailing
+    /// newline still counts.
+    line_has_text: bool,
+    prompt_line: Option<usize>,
+    last_bell: Option<Instant>,
+}
+
+impl OscScanner {
+    /// `line` is the scrollback's line count when the scanner starts, past any seeded lines.
+    pub fn new(reported: Arc<Mutex<ReportedState>>, line: usize) -> Self {
+        Self {
+            reported,
+            line,
+            ..Self::default()
+        }
+    }
+
+    fn dispatch(&mut self, app: &tauri::AppHandle, tab_id: &str) {
+        let payload = String::from_utf8_lossy(&self.payload).to_string();
+        self.payload.clear();
+
+        let (code, rest) = payload.split_once(';').unwrap_or((payload.as_str(), ""));
+        match code {
+            "0" | "2" => {
+                let renamed = match self.reported.lock() {
+                    Ok(mut reported) => {
+                        reported.title = Some(rest.to_string());
+                        reported.custom_title.is_some()
+                    }
+                    Err(_) => false,
+                };
+                if !renamed {
+                    emit_title(app, tab_id, rest.to_string());
+                }
+            }
+            "7" => {
+                if let Some(cwd) = parse_file_url(rest) {
+                    if let Ok(mut reported) = self.reported.lock() {
+                        reported.cwd = Some(cwd.clone());
+                    }
+                    windows::emit(
+                        app,
+                        tab_id,
+                        "terminal-cwd-changed",
+                        TerminalCwdEvent {
+                            tab_id: tab_id.to_string(),
+                            cwd: cwd.clone(),
+                        },
+                    );
+                    self.refresh_repo(app, tab_id, cwd);
+                }
+            }
+            "8" => self.dispatch_hyperlink(app, tab_id, rest),
+            // ConEmu reuses OSC 9 with a numeric subcommand (progress, cwd, ...); those aren't messages.
+            "9" if rest.split(';').next().is_none_or(|first| first.parse::<u32>().is_err()) => {
+                emit_notification(app, tab_id, None, rest);
+            }
+            "777" => {
+                if let Some(("notify", message)) = rest.split_once(';') {
+                    let (title, body) = message.split_once(';').unwrap_or(("", message));
+                    emit_notification(app, tab_id, Some(title), body);
+                }
+            }
+            "133" => self.dispatch_prompt_mark(app, tab_id, rest),
+            _ => {}
+        }
+    }
+
+    fn ring_bell(&mut self, app: &tauri::AppHandle, tab_id: &str) {
+        let now = Instant::now();
+        if self
+            .last_bell
+            .is_some_and(|last| now.duration_since(last) < BELL_INTERVAL)
+        {
+            return;
+        }
+        self.last_bell = Some(now);
+
+        windows::emit(
+            app,
+            tab_id,
+            "terminal-bell",
+            TerminalBellEvent {
+                tab_id: tab_id.to_string(),
+            },
+        );
+        let title = self.reported.lock().ok().and_then(|reported| reported.title());
+        notifications::notify_bell(app, tab_id, title);
+    }
+
+    /// Looks up the repository and branch around `cwd` off the reader thread and emits
+    /// `terminal-repo-changed` when they differ from what the tab last saw. Shells report their
+    /// cwd at every prompt, so a checkout in the same directory is picked up as well.
+    fn refresh_repo(&self, app: &tauri::AppHandle, tab_id: &str, cwd: String) {
+        let app = app.clone();
+        let tab_id = tab_id.to_string();
+        let reported = Arc::clone(&self.reported);
+
+        std::thread::spawn(move || {
+            let repo = git::repo_head(Path::new(&cwd));
+            {
+                let Ok(mut reported) = reported.lock() else {
+                    return;
+                };
+                // A later prompt moved on while git ran; its own lookup reports instead.
+                if reported.cwd.as_deref() != Some(cwd.as_str()) || reported.repo == repo {
+                    return;
+                }
+                reported.repo = repo.clone();
+            }
+            windows::emit(
+                &app,
+                &tab_id,
+                "terminal-repo-changed",
+                TerminalRepoEvent {
+                    tab_id: tab_id.clone(),
+                    cwd,
+                    repo,
+                },
+            );
+        });
+    }
+
+    /// Handles `OSC 8 ; params ; uri`. An empty URI closes the link and needs no bookkeeping.
+    fn dispatch_hyperlink(&mut self, app: &tauri::AppHandle, tab_id: &str, rest: &str) {
+        let Some((params, uri)) = rest.split_once(';') else {
+            return;
+        };
+        if uri.is_empty() {
+            return;
+        }
+
+        let link_id = params
+            .split(':')
+            .find_map(|param| param.strip_prefix("id="))
+            .filter(|id| !id.is_empty());
+        let Ok(mut reported) = self.reported.lock() else {
+            return;
+        };
+        let id = reported.hyperlinks.insert(link_id, uri);
+        drop(reported);
+
+        windows::emit(
+            app,
+            tab_id,
+            "terminal-hyperlink",
+            TerminalHyperlinkEvent {
+                tab_id: tab_id.to_string(),
+                id,
+                uri: uri.to_string(),
+            },
+        );
+    }
+
+    fn dispatch_private_mode(&mut self, enabled: bool) {
+        let params = String::from_utf8_lossy(&self.payload).to_string();
+        self.payload.clear();
+
+        let Some(modes) = params.strip_prefix('?') else {
+            return;
+        };
+        if modes.split(';').any(|mode| mode == "2004") {
+            if let Ok(mut reported) = self.reported.lock() {
+                reported.bracketed_paste = enabled;
+            }
+        }
+    }
+
+    fn dispatch_prompt_mark(&mut self, app: &tauri::AppHandle, tab_id: &str, mark: &str) {
+        let (kind, params) = mark.split_once(';').unwrap_or((mark, ""));
+        match kind {
+            "A" => self.prompt_line = Some(self.line),
+            "C" => {
+                self.command_started_at = Some(Instant::now());
+                self.start_command(params);
+                windows::emit(
+                    app,
+                    tab_id,
+                    "terminal-command-started",
+                    TerminalCommandEvent {
+                        tab_id: tab_id.to_string(),
+                        exit_code: None,
+                    },
+                );
+            }
+            "D" => {
+                let exit_code = params.split(';').next().and_then(|code| code.trim().parse::<i32>().ok());
+                windows::emit(
+                    app,
+                    tab_id,
+                    "terminal-command-finished",
+                    TerminalCommandEvent {
+                        tab_id: tab_id.to_string(),
+                        exit_code,
+                    },
+                );
+
+                if let Some(started_at) = self.command_started_at.take() {
+                    let elapsed = started_at.elapsed();
+                    self.finish_command(exit_code, elapsed.as_millis() as u64);
+                    windows::emit(
+                        app,
+                        tab_id,
+                        "terminal-command-completed",
+                        TerminalCommandCompletedEvent {
+                            tab_id: tab_id.to_string(),
+                            exit_code,
+                            elapsed_ms: elapsed.as_millis() as u64,
+                        },
+                    );
+                    notifications::notify_command_completed(app, tab_id, exit_code, elapsed);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Opens a command record at the current line. The command line comes from a `cmdline=`
+    /// (raw) or `cmdline_url=` (percent-encoded) parameter, which runs to the end of the mark.
+    fn start_command(&mut self, params: &str) {
+        let command = params
+            .strip_prefix("cmdline=")
+            .map(str::to_string)
+            .or_else(|| params.strip_prefix("cmdline_url=").map(percent_decode))
+            .map(|command| command.trim().to_string())
+            .filter(|command| !command.is_empty());
+        let started_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_millis() as u64)
+            .unwrap_or_default();
+
+        let Ok(mut reported) = self.reported.lock() else {
+            return;
+        };
+        let record = CommandRecord {
+            command,
+            prompt_line: self.prompt_line.take(),
+            output_start: self.line,
+            output_end: None,
+            started_at,
+            duration_ms: None,
+            exit_code: None,
+            cwd: reported.cwd.clone(),
+        };
+        reported.commands.push_back(record);
+        while reported.commands.len() > MAX_COMMANDS {
+            reported.commands.pop_front();
+        }
+    }
+
+    fn finish_command(&mut self, exit_code: Option<i32>, duration_ms: u64) {
+        let output_end = self.line + usize::from(self.line_has_text);
+        let Ok(mut reported) = self.reported.lock() else {
+            return;
+        };
+        if let Some(record) = reported.commands.back_mut().filter(|record| record.output_end.is_none()) {
+            record.output_end = Some(output_end.max(record.output_start));
+            record.duration_ms = Some(duration_ms);
+            record.exit_code = exit_code;
+        }
+    }
+}
+
+impl OutputObserver for OscScanner {
+    fn observe(&mut self, app: &tauri::AppHandle, tab_id: &str, bytes: &[u8]) {
+        for &byte in bytes {
+            if byte == b'\n' {
+                self.line += 1;
+                self.line_has_text = false;
+            }
+
+            self.state = match (self.state, byte) {
+                (ScanState::Ground, 0x1b) => ScanState::Escape,
+                (ScanState::Ground, 0x07) => {
+                    self.ring_bell(app, tab_id);
+                    ScanState::Ground
+                }
+                (ScanState::Ground, _) => {
+                    if byte >= 0x20 {
+                        self.line_has_text = true;
+                    }
+                    ScanState::Ground
+                }
+                (ScanState::Escape, b']') => {
+                    self.payload.clear();
+                    ScanState::Osc
+                }
+                (ScanState::Escape, b'[') => {
+                    self.payload.clear();
+                    ScanState::Csi
+                }
+                (ScanState::Escape, 0x1b) => ScanState::Escape,
+                (ScanState::Escape, _) => ScanState::Ground,
+                (ScanState::Csi, b'h' | b'l') => {
+                    self.dispatch_private_mode(byte == b'h');
+                    ScanState::Ground
+                }
+                (ScanState::Csi, 0x30..=0x3f) if self.payload.len() < MAX_OSC_LEN => {
+                    self.payload.push(byte);
+                    ScanState::Csi
+                }
+                (ScanState::Csi, 0x1b) => ScanState::Escape,
+                (ScanState::Csi, _) => {
+                    self.payload.clear();
+                    ScanState::Ground
+                }
+                (ScanState::Osc, 0x07) => {
+                    self.dispatch(app, tab_id);
+                    ScanState::Ground
+                }
+                (ScanState::Osc, 0x1b) => ScanState::OscEscape,
+                (ScanState::Osc, _) => {
+                    if self.payload.len() >= MAX_OSC_LEN {
+                        self.payload.clear();
+                        ScanState::Ground
+                    } else {
+                        self.payload.push(byte);
+                        ScanState::Osc
+                    }
+                }
+                (ScanState::OscEscape, b'\\') => {
+                    self.dispatch(app, tab_id);
+                    ScanState::Ground
+                }
+                (ScanState::OscEscape, b']') => {
+                    self.payload.clear();
+                    ScanState::Osc
+                }
+                (ScanState::OscEscape, _) => {
+                    self.payload.clear();
+                    ScanState::Ground
+                }
+            };
+        }
+    }
+}
+
+/// Extracts the path from an OSC 7 `file://host/path` URL.
+fn parse_file_url(url: &str) -> Option<String> {
+    let rest = url.strip_prefix("file://")?;
+    let path = &rest[rest.find('/')?..];
+    let decoded = percent_decode(path);
+
+    #[cfg(target_os = "windows")]
+    {
+        // file://host/C:/Users -> C:/Users
+        let trimmed = decoded.strip_prefix('/').unwrap_or(&decoded);
+        if trimmed.as_bytes().get(1) == Some(&b':') {
+            return Some(trimmed.to_string());
+        }
+    }
+
+    Some(decoded)
+}
+
+fn percent_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut output = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+
+    while index < bytes.len() {
+        if bytes[index] == b'%' && index + 2 < bytes.len() {
+            if let (Some(high), Some(low)) = (hex_value(bytes[index + 1]), hex_value(bytes[index + 2])) {
+                output.push((high << 4) | low);
+                index += 3;
+                continue;
+            }
+        }
+        output.push(bytes[index]);
+        index += 1;
+    }
+
+    String::from_utf8_lossy(&output).to_string()
+}
+
+fn hex_value(byte: u8) -> Option<u8> {
+    (byte as char).to_digit(16).map(|value| value as u8)
+}
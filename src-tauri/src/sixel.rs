@@ -0,0 +1,230 @@
+/// Largest canvas side accepted, in pixels. Larger images are clipped.
+const MAX_DIMENSION: usize = 8192;
+
+/// The VT340 default color registers, as RGB percentages.
+const DEFAULT_PALETTE: [(u32, u32, u32); 16] = [
+    (0, 0, 0),
+    (20, 20, 80),
+    (80, 13, 13),
+    (20, 80, 20),
+    (80, 20, 80),
+    (20, 80, 80),
+    (80, 80, 20),
+    (53, 53, 53),
+    (26, 26, 26),
+    (33, 33, 60),
+    (60, 26, 26),
+    (33, 60, 33),
+    (60, 33, 60),
+    (33, 60, 60),
+    (60, 60, 33),
+    (80, 80, 80),
+];
+
+pub struct SixelImage {
+    pub width: u32,
+    pub height: u32,
+    /// `width * height` RGBA pixels, row by row.
+    pub rgba: Vec<u8>,
+}
+
+struct Canvas {
+    /// Painted extent, which the image is cropped to.
+    width: usize,
+    height: usize,
+    /// Allocated columns and rows. They grow geometrically, so an image without raster
+    /// attributes, which widens by a column or a band at a time, isn't copied on every step.
+    stride: usize,
+    rows: usize,
+    pixels: Vec<u8>,
+    background: [u8; 4],
+}
+
+impl Canvas {
+    /// Grows the canvas to at least `width` x `height`, returning false past `MAX_DIMENSION`.
+    fn ensure(&mut self, width: usize, height: usize) -> bool {
+        if width > MAX_DIMENSION || height > MAX_DIMENSION {
+            return false;
+        }
+
+        if width > self.stride || height > self.rows {
+            let grow = |needed: usize, current: usize| {
+                if needed > current {
+                    needed.max(current * 2).min(MAX_DIMENSION)
+                } else {
+                    current
+                }
+            };
+            let stride = grow(width, self.stride);
+            let rows = grow(height, self.rows);
+            let mut pixels = self.background.repeat(stride * rows);
+            for row in 0..self.height {
+                let source = row * self.stride * 4;
+                let target = row * stride * 4;
+                pixels[target..target + self.width * 4].copy_from_slice(&self.pixels[source..source + self.width * 4]);
+            }
+            self.stride = stride;
+            self.rows = rows;
+            self.pixels = pixels;
+        }
+
+        self.width = self.width.max(width);
+        self.height = self.height.max(height);
+        true
+    }
+
+    fn set(&mut self, x: usize, y: usize, color: [u8; 4]) {
+        let offset = (y * self.stride + x) * 4;
+        self.pixels[offset..offset + 4].copy_from_slice(&color);
+    }
+
+    /// The painted `width * height` pixels, without the spare capacity.
+    fn into_rgba(self) -> Vec<u8> {
+        if self.stride == self.width {
+            let mut pixels = self.pixels;
+            pixels.truncate(self.width * self.height * 4);
+            return pixels;
+        }
+        self.pixels
+            .chunks_exact(self.stride * 4)
+            .take(self.height)
+            .flat_map(|row| &row[..self.width * 4])
+            .copied()
+            .collect()
+    }
+}
+
+fn percent(value: u32) -> u8 {
+    ((value.min(100) * 255 + 50) / 100) as u8
+}
+
+/// Sixel hue starts at blue rather than red, so it is rotated before the usual HSL conversion.
+fn hls_to_rgb(hue: u32, lightness: u32, saturation: u32) -> [u8; 3] {
+    let hue = ((hue + 240) % 360) as f32 / 60.0;
+    let lightness = lightness.min(100) as f32 / 100.0;
+    let saturation = saturation.min(100) as f32 / 100.0;
+
+    let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+    let second = chroma * (1.0 - (hue % 2.0 - 1.0).abs());
+    let (red, green, blue) = match hue as u32 {
+        0 => (chroma, second, 0.0),
+        1 => (second, chroma, 0.0),
+        2 => (0.0, chroma, second),
+        3 => (0.0, second, chroma),
+        4 => (second, 0.0, chroma),
+        _ => (chroma, 0.0, second),
+    };
+    let offset = lightness - chroma / 2.0;
+    let channel = |value: f32| ((value + offset) * 255.0).round().clamp(0.0, 255.0) as u8;
+    [channel(red), channel(green), channel(blue)]
+}
+
+/// Reads `;`-separated decimal parameters starting at `*index`, leaving it on the first other byte.
+fn parameters(data: &[u8], index: &mut usize) -> Vec<u32> {
+    let mut values = vec![0_u32];
+    while let Some(&byte) = data.get(*index) {
+        match byte {
+            b'0'..=b'9' => {
+                let last = values.last_mut().expect("values is never empty");
+                *last = last.saturating_mul(10).saturating_add(u32::from(byte - b'0'));
+            }
+            b';' => values.push(0),
+            _ => break,
+        }
+        *index += 1;
+    }
+    values
+}
+
+/// Decodes the body of a sixel DCS sequence. `params` are the numbers before the `q`; the
+/// second one selects whether unpainted pixels stay transparent.
+pub fn decode(params: &str, data: &[u8]) -> Option<SixelImage> {
+    let transparent = params.split(';').nth(1).map(str::trim) == Some("1");
+
+    let mut palette = [[0_u8, 0, 0, 255]; 256];
+    for (register, &(red, green, blue)) in DEFAULT_PALETTE.iter().enumerate() {
+        palette[register] = [percent(red), percent(green), percent(blue), 255];
+    }
+
+    let mut canvas = Canvas {
+        width: 0,
+        height: 0,
+        stride: 0,
+        rows: 0,
+        pixels: Vec::new(),
+        background: if transparent { [0, 0, 0, 0] } else { palette[0] },
+    };
+    let mut color = 0_usize;
+    let (mut x, mut y) = (0_usize, 0_usize);
+    let mut index = 0;
+
+    while let Some(&byte) = data.get(index) {
+        index += 1;
+        let (bits, count) = match byte {
+            b'"' => {
+                let raster = parameters(data, &mut index);
+                if let [_, _, width, height, ..] = raster[..] {
+                    canvas.ensure(width as usize, height as usize);
+                }
+                continue;
+            }
+            b'#' => {
+                let values = parameters(data, &mut index);
+                color = values[0] as usize % palette.len();
+                if let [_, space, first, second, third, ..] = values[..] {
+                    let [red, green, blue] = match space {
+                        1 => hls_to_rgb(first, second, third),
+                        _ => [percent(first), percent(second), percent(third)],
+                    };
+                    palette[color] = [red, green, blue, 255];
+                }
+                continue;
+            }
+            b'!' => {
+                let count = parameters(data, &mut index)[0].max(1) as usize;
+                match data.get(index) {
+                    Some(&sixel @ 0x3f..=0x7e) => {
+                        index += 1;
+                        (sixel - 0x3f, count.min(MAX_DIMENSION))
+                    }
+                    _ => continue,
+                }
+            }
+            b'$' => {
+                x = 0;
+                continue;
+            }
+            b'-' => {
+                x = 0;
+                y += 6;
+                continue;
+            }
+            0x3f..=0x7e => (byte - 0x3f, 1),
+            _ => continue,
+        };
+
+        if bits != 0 {
+            let rows = 8 - bits.leading_zeros() as usize;
+            if canvas.ensure(x + count, y + rows) {
+                for column in x..x + count {
+                    for row in 0..rows {
+                        if bits & (1 << row) != 0 {
+                            canvas.set(column, y + row, palette[color]);
+                        }
+                    }
+                }
+            }
+        }
+        x += count;
+    }
+
+    if canvas.width == 0 || canvas.height == 0 {
+        return None;
+    }
+
+    Some(SixelImage {
+        width: canvas.width as u32,
+        height: canvas.height as u32,
+        rgba: canvas.into_rgba(),
+    })
+}
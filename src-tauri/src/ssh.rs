@@ -0,0 +1,259 @@
+use crate::{
+    error::AppError,
+    scrollback::ScrollbackLimits,
+    session_store,
+    stream::OutputEncoding,
+    terminal::{self, OpenTerminalResponse, OutputObserver, SessionKind, SpawnOptions, TerminalSession, TerminalState},
+    windows,
+};
+use portable_pty::{CommandBuilder, ExitStatus};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tauri::Manager;
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SshTarget {
+    pub host: String,
+    #[serde(default)]
+    pub port: Option<u16>,
+    #[serde(default)]
+    pub user: Option<String>,
+    #[serde(default)]
+    pub identity_file: Option<String>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum SshStatus {
+    Connecting,
+    Authenticating,
+    Connected,
+    /// ssh gave up: the connection or authentication failed, or the connection was lost.
+    Failed,
+    /// The remote session ended.
+    Closed,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SshStatusEvent {
+    tab_id: String,
+    host: String,
+    status: SshStatus,
+    /// ssh's exit code once it has exited.
+    exit_code: Option<u32>,
+}
+
+/// Printed by ssh's `LocalCommand`, which only runs once the connection is authenticated. An
+/// unknown OSC sequence, so terminals ignore it.
+const CONNECTED_MARKER: &[u8] = b"\x1b]6973;nlk-ssh-connected\x07";
+
+#[cfg(not(target_os = "windows"))]
+const CONNECTED_COMMAND: &str = r"printf '\033]6973;nlk-ssh-connected\007'";
+#[cfg(target_os = "windows")]
+const CONNECTED_COMMAND: &str =
+    "powershell -NoProfile -Command \"[Console]::Write([char]27 + ']6973;nlk-ssh-connected' + [char]7)\"";
+
+/// ssh exits with 255 when it fails itself, as opposed to passing on the remote exit code.
+const SSH_ERROR_EXIT: u32 = 255;
+
+/// Tracks the connection state: credential prompts in the output, the marker printed once
+/// connected, and finally how ssh exited.
+struct SshStatusObserver {
+    host: String,
+    status: SshStatus,
+    /// End of the previous read, in case the marker is split across reads.
+    tail: Vec<u8>,
+}
+
+impl SshStatusObserver {
+    fn set_status(&mut self, app: &tauri::AppHandle, tab_id: &str, status: SshStatus, exit_code: Option<u32>) {
+        if self.status == status {
+            return;
+        }
+
+        self.status = status;
+        windows::emit(
+            app,
+            tab_id,
+            "ssh-status",
+            SshStatusEvent {
+                tab_id: tab_id.to_string(),
+                host: self.host.clone(),
+                status,
+                exit_code,
+            },
+        );
+    }
+}
+
+impl OutputObserver for SshStatusObserver {
+    fn observe(&mut self, app: &tauri::AppHandle, tab_id: &str, bytes: &[u8]) {
+        if self.status == SshStatus::Connected {
+            return;
+        }
+
+        let mut window = std::mem::take(&mut self.tail);
+        window.extend_from_slice(bytes);
+        if window.windows(CONNECTED_MARKER.len()).any(|chunk| chunk == CONNECTED_MARKER) {
+            self.set_status(app, tab_id, SshStatus::Connected, None);
+            return;
+        }
+
+        let text = String::from_utf8_lossy(bytes).to_lowercase();
+        if text.contains("password:") || text.contains("passphrase") {
+            self.set_status(app, tab_id, SshStatus::Authenticating, None);
+        }
+        let keep = window.len().saturating_sub(CONNECTED_MARKER.len() - 1);
+        self.tail = window.split_off(keep);
+    }
+
+    fn exited(&mut self, app: &tauri::AppHandle, tab_id: &str, status: Option<&ExitStatus>) {
+        let exit_code = status.map(ExitStatus::exit_code);
+        let failed = match exit_code {
+            Some(code) => code == SSH_ERROR_EXIT,
+            None => self.status != SshStatus::Connected,
+        };
+        let status = if failed { SshStatus::Failed } else { SshStatus::Closed };
+        self.set_status(app, tab_id, status, exit_code);
+    }
+}
+
+fn known_hosts_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|error| format!("failed to resolve data dir: {error}"))?;
+    std::fs::create_dir_all(&dir).map_err(|error| format!("failed to create data dir: {error}"))?;
+    Ok(dir.join("known_hosts"))
+}
+
+/// `ssh` to `target`, with `options` passed as extra `-o` settings.
+pub fn ssh_command(app: &tauri::AppHandle, target: &SshTarget, options: &[&str]) -> Result<CommandBuilder, String> {
+    let host = target.host.trim();
+    if host.is_empty() {
+        return Err("ssh host is empty".to_string());
+    }
+    if host.starts_with('-') {
+        return Err(format!("invalid ssh host: {host}"));
+    }
+
+    let known_hosts = known_hosts_path(app)?;
+
+    let mut builder = CommandBuilder::new("ssh");
+    builder.arg("-t");
+    builder.arg("-o");
+    builder.arg(format!("UserKnownHostsFile={}", known_hosts.to_string_lossy()));
+    builder.arg("-o");
+    builder.arg("StrictHostKeyChecking=accept-new");
+
+    if let Some(port) = target.port {
+        builder.arg("-p");
+        builder.arg(port.to_string());
+    }
+
+    if let Some(identity) = target.identity_file.as_deref().filter(|path| !path.trim().is_empty()) {
+        builder.arg("-i");
+        builder.arg(identity);
+        builder.arg("-o");
+        builder.arg("IdentitiesOnly=yes");
+    }
+
+    for option in options {
+        builder.arg("-o");
+        builder.arg(option);
+    }
+
+    match target.user.as_deref().map(str::trim).filter(|user| !user.is_empty()) {
+        Some(user) => builder.arg(format!("{user}@{host}")),
+        None => builder.arg(host),
+    }
+
+    terminal::apply_terminal_env(&mut builder);
+    Ok(builder)
+}
+
+pub fn spawn_ssh_session(
+    app: &tauri::AppHandle,
+    tab_id: &str,
+    target: SshTarget,
+    encoding: OutputEncoding,
+    scrollback: ScrollbackLimits,
+    scrollback_seed: Vec<String>,
+) -> Result<TerminalSession, String> {
+    let local_command = format!("LocalCommand={CONNECTED_COMMAND}");
+    let command = ssh_command(app, &target, &["PermitLocalCommand=yes", &local_command])?;
+    let host = target.host.trim().to_string();
+
+    windows::emit(
+        app,
+        tab_id,
+        "ssh-status",
+        SshStatusEvent {
+            tab_id: tab_id.to_string(),
+            host: host.clone(),
+            status: SshStatus::Connecting,
+            exit_code: None,
+        },
+    );
+
+    terminal::spawn_command(
+        app,
+        tab_id,
+        "ssh".to_string(),
+        command,
+        SpawnOptions {
+            kind: SessionKind::Ssh(target),
+            encoding,
+            scrollback,
+            scrollback_seed,
+            observers: vec![Box::new(SshStatusObserver {
+                host,
+                status: SshStatus::Connecting,
+                tail: Vec::new(),
+            })],
+            ..SpawnOptions::default()
+        },
+    )
+}
+
+#[tauri::command]
+pub fn open_ssh_terminal(
+    tab_id: String,
+    target: SshTarget,
+    encoding: Option<OutputEncoding>,
+    scrollback: Option<ScrollbackLimits>,
+    app: tauri::AppHandle,
+    webview_window: tauri::WebviewWindow,
+    state: tauri::State<TerminalState>,
+) -> Result<OpenTerminalResponse, AppError> {
+    let mut sessions = state
+        .sessions
+        .lock()
+        .map_err(|_| "failed to lock terminal sessions".to_string())?;
+
+    windows::claim(&app, &tab_id, webview_window.label());
+    if let Some(session) = sessions.get(&tab_id) {
+        return Ok(OpenTerminalResponse {
+            shell: session.shell.clone(),
+        });
+    }
+
+    let session = spawn_ssh_session(
+        &app,
+        &tab_id,
+        target,
+        encoding.unwrap_or_default(),
+        scrollback.unwrap_or_default(),
+        Vec::new(),
+    )
+    .map_err(AppError::pty)
+    .inspect_err(|_| windows::forget_tab(&app, &tab_id))?;
+    let shell = session.shell.clone();
+
+    sessions.insert(tab_id, session);
+    let _ = session_store::persist(&app, &sessions);
+
+    Ok(OpenTerminalResponse { shell })
+}
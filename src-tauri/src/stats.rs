@@ -0,0 +1,124 @@
+use crate::{
+    error::AppError,
+    process::{ProcessMonitor, TreeUsage},
+    terminal::TerminalState,
+    windows,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+    time::Duration,
+};
+use tauri::Manager;
+
+const MIN_INTERVAL_MS: u64 = 250;
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatsSettings {
+    pub enabled: bool,
+    pub interval_ms: u64,
+}
+
+impl Default for StatsSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_ms: 2000,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct StatsState {
+    settings: Mutex<StatsSettings>,
+    /// The collector thread is started on first enable and idles while disabled.
+    started: AtomicBool,
+}
+
+impl StatsState {
+    fn settings(&self) -> StatsSettings {
+        self.settings.lock().map(|settings| *settings).unwrap_or_default()
+    }
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TerminalStatsEvent {
+    tab_id: String,
+    #[serde(flatten)]
+    usage: TreeUsage,
+}
+
+/// Emits one `terminal-stats` event per session backed by a local process.
+fn collect(app: &tauri::AppHandle) {
+    let sessions = {
+        let terminals = app.state::<TerminalState>();
+        let Ok(sessions) = terminals.sessions.lock() else {
+            return;
+        };
+        sessions
+            .iter()
+            .filter_map(|(tab_id, session)| Some((tab_id.clone(), session.pid()?)))
+            .collect::<Vec<_>>()
+    };
+    if sessions.is_empty() {
+        return;
+    }
+
+    let roots = sessions.iter().map(|(_, pid)| *pid).collect::<Vec<_>>();
+    let Ok(usages) = app.state::<ProcessMonitor>().tree_usage(&roots) else {
+        return;
+    };
+    for ((tab_id, _), usage) in sessions.into_iter().zip(usages) {
+        let event = TerminalStatsEvent {
+            tab_id: tab_id.clone(),
+            usage,
+        };
+        windows::emit(app, &tab_id, "terminal-stats", event);
+    }
+}
+
+fn start_collector(app: &tauri::AppHandle) {
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        let settings = app.state::<StatsState>().settings();
+        std::thread::sleep(Duration::from_millis(settings.interval_ms.max(MIN_INTERVAL_MS)));
+        if settings.enabled {
+            collect(&app);
+        }
+    });
+}
+
+#[tauri::command]
+pub fn get_stats_settings(state: tauri::State<StatsState>) -> Result<StatsSettings, AppError> {
+    let settings = state
+        .settings
+        .lock()
+        .map_err(|_| "failed to lock stats settings".to_string())?;
+    Ok(*settings)
+}
+
+/// Turns the periodic `terminal-stats` events (CPU percent, resident memory and process count of
+/// each session's process tree) on or off.
+#[tauri::command]
+pub fn set_stats_settings(
+    settings: StatsSettings,
+    app: tauri::AppHandle,
+    state: tauri::State<StatsState>,
+) -> Result<(), AppError> {
+    let mut current = state
+        .settings
+        .lock()
+        .map_err(|_| "failed to lock stats settings".to_string())?;
+    *current = settings;
+    drop(current);
+
+    if settings.enabled && !state.started.swap(true, Ordering::SeqCst) {
+        start_collector(&app);
+    }
+    Ok(())
+}
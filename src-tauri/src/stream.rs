@@ -0,0 +1,86 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputEncoding {
+    #[default]
+    Text,
+    Base64,
+}
+
+/// Decodes PTY output as UTF-8, carrying incomplete trailing sequences over to the next read.
+#[derive(Default)]
+pub struct Utf8Decoder {
+    pending: Vec<u8>,
+}
+
+impl Utf8Decoder {
+    pub fn decode(&mut self, bytes: &[u8]) -> String {
+        let mut input = std::mem::take(&mut self.pending);
+        input.extend_from_slice(bytes);
+
+        let mut output = String::with_capacity(input.len());
+        let mut rest = input.as_slice();
+
+        loop {
+            match std::str::from_utf8(rest) {
+                Ok(valid) => {
+                    output.push_str(valid);
+                    break;
+                }
+                Err(error) => {
+                    let (valid, after) = rest.split_at(error.valid_up_to());
+                    output.push_str(std::str::from_utf8(valid).unwrap_or_default());
+
+                    match error.error_len() {
+                        Some(len) => {
+                            output.push(char::REPLACEMENT_CHARACTER);
+                            rest = &after[len..];
+                        }
+                        None => {
+                            self.pending.extend_from_slice(after);
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+
+        output
+    }
+
+    /// Flushes any bytes still buffered when the stream ends.
+    pub fn finish(&mut self) -> String {
+        let pending = std::mem::take(&mut self.pending);
+        String::from_utf8_lossy(&pending).to_string()
+    }
+}
+
+pub struct OutputDecoder {
+    encoding: OutputEncoding,
+    utf8: Utf8Decoder,
+}
+
+impl OutputDecoder {
+    pub fn new(encoding: OutputEncoding) -> Self {
+        Self {
+            encoding,
+            utf8: Utf8Decoder::default(),
+        }
+    }
+
+    pub fn decode(&mut self, bytes: &[u8]) -> String {
+        match self.encoding {
+            OutputEncoding::Text => self.utf8.decode(bytes),
+            OutputEncoding::Base64 => STANDARD.encode(bytes),
+        }
+    }
+
+    pub fn finish(&mut self) -> String {
+        match self.encoding {
+            OutputEncoding::Text => self.utf8.finish(),
+            OutputEncoding::Base64 => String::new(),
+        }
+    }
+}
@@ -0,0 +1,111 @@
+use crate::terminal;
+use portable_pty::{Child, ExitStatus};
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+use tauri::Manager;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+/// How long the emitter gets to report an exited child before the supervisor does it instead.
+const REPORT_GRACE: Duration = Duration::from_secs(5);
+
+struct Supervised {
+    tab_id: String,
+    child: Box<dyn Child + Send + Sync>,
+    exit_sender: mpsc::Sender<Option<ExitStatus>>,
+    exit_reported: Arc<AtomicBool>,
+    close_requested: Arc<AtomicBool>,
+    exited: Option<(Instant, Option<ExitStatus>)>,
+}
+
+/// Children of PTY sessions. They stay here after their tab is closed until they are reaped.
+#[derive(Default)]
+pub struct SupervisorState {
+    children: Mutex<Vec<Supervised>>,
+    /// The supervisor thread is started with the first session.
+    started: AtomicBool,
+}
+
+/// Hands a session's child to the supervisor, which reaps it and sends its status on
+/// `exit_sender`. `exit_reported` is the session's flag for a `terminal-exit` already emitted,
+/// `close_requested` the one for a close the user asked for.
+pub fn watch(
+    app: &tauri::AppHandle,
+    tab_id: &str,
+    child: Box<dyn Child + Send + Sync>,
+    exit_sender: mpsc::Sender<Option<ExitStatus>>,
+    exit_reported: Arc<AtomicBool>,
+    close_requested: Arc<AtomicBool>,
+) {
+    let state = app.state::<SupervisorState>();
+    if let Ok(mut children) = state.children.lock() {
+        children.push(Supervised {
+            tab_id: tab_id.to_string(),
+            child,
+            exit_sender,
+            exit_reported,
+            close_requested,
+            exited: None,
+        });
+    }
+
+    if !state.started.swap(true, Ordering::SeqCst) {
+        let app = app.clone();
+        std::thread::spawn(move || loop {
+            std::thread::sleep(CHECK_INTERVAL);
+            check(&app);
+        });
+    }
+}
+
+/// Polls every child with `try_wait`. Exited children are reaped; when the session's reader
+/// didn't report the exit within `REPORT_GRACE`, e.g. because it died or a leftover process
+/// keeps the PTY open, the session is dropped and `terminal-exit` emitted here.
+fn check(app: &tauri::AppHandle) {
+    let now = Instant::now();
+    let mut unreported = Vec::new();
+    {
+        let state = app.state::<SupervisorState>();
+        let Ok(mut children) = state.children.lock() else {
+            return;
+        };
+        children.retain_mut(|supervised| {
+            if supervised.exited.is_none() {
+                let status = match supervised.child.try_wait() {
+                    Ok(None) => return true,
+                    Ok(Some(status)) => Some(status),
+                    Err(_) => None,
+                };
+                let _ = supervised.exit_sender.send(status.clone());
+                supervised.exited = Some((now, status));
+            }
+
+            let Some((exited_at, status)) = &supervised.exited else {
+                return true;
+            };
+            if supervised.exit_reported.load(Ordering::SeqCst) {
+                return false;
+            }
+            if now.duration_since(*exited_at) < REPORT_GRACE {
+                return true;
+            }
+            if !supervised.exit_reported.swap(true, Ordering::SeqCst) {
+                unreported.push((
+                    supervised.tab_id.clone(),
+                    Arc::clone(&supervised.exit_reported),
+                    supervised.close_requested.load(Ordering::SeqCst),
+                    status.clone(),
+                ));
+            }
+            false
+        });
+    }
+
+    for (tab_id, exit_reported, was_requested, status) in unreported {
+        terminal::drop_dead_session(app, &tab_id, &exit_reported, was_requested, status.as_ref());
+    }
+}
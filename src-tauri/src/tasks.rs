@@ -0,0 +1,292 @@
+use crate::{
+    error::AppError,
+    terminal::{self, CommandSpec, OpenTerminalResponse, TerminalState},
+};
+use serde::Serialize;
+use serde_json::Value;
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+#[derive(Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TaskSource {
+    Npm,
+    Make,
+    Just,
+    Cargo,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Task {
+    /// `<source>:<name>`, as passed to `run_task`.
+    id: String,
+    source: TaskSource,
+    name: String,
+    description: Option<String>,
+    program: String,
+    args: Vec<String>,
+}
+
+fn task(source: TaskSource, name: &str, description: Option<String>, program: &str, args: Vec<String>) -> Task {
+    let prefix = match source {
+        TaskSource::Npm => "npm",
+        TaskSource::Make => "make",
+        TaskSource::Just => "just",
+        TaskSource::Cargo => "cargo",
+    };
+    Task {
+        id: format!("{prefix}:{name}"),
+        source,
+        name: name.to_string(),
+        description,
+        program: program.to_string(),
+        args,
+    }
+}
+
+/// Package manager scripts go through the runner whose lockfile is present.
+fn npm_runner(dir: &Path) -> &'static str {
+    let runner = if dir.join("pnpm-lock.yaml").exists() {
+        "pnpm"
+    } else if dir.join("yarn.lock").exists() {
+        "yarn"
+    } else if dir.join("bun.lockb").exists() || dir.join("bun.lock").exists() {
+        "bun"
+    } else {
+        "npm"
+    };
+    // The Node package managers are batch scripts on Windows.
+    match (cfg!(target_os = "windows"), runner) {
+        (true, "pnpm") => "pnpm.cmd",
+        (true, "yarn") => "yarn.cmd",
+        (true, "npm") => "npm.cmd",
+        _ => runner,
+    }
+}
+
+fn npm_tasks(dir: &Path) -> Vec<Task> {
+    let Ok(raw) = std::fs::read_to_string(dir.join("package.json")) else {
+        return Vec::new();
+    };
+    let Ok(package) = serde_json::from_str::<Value>(&raw) else {
+        return Vec::new();
+    };
+    let Some(scripts) = package["scripts"].as_object() else {
+        return Vec::new();
+    };
+
+    let runner = npm_runner(dir);
+    scripts
+        .iter()
+        .map(|(name, script)| {
+            task(
+                TaskSource::Npm,
+                name,
+                script.as_str().map(ToOwned::to_owned),
+                runner,
+                vec!["run".to_string(), name.clone()],
+            )
+        })
+        .collect()
+}
+
+/// Reads explicit targets from a Makefile. `## text` after the target or on the line above
+/// becomes the description.
+fn make_tasks(dir: &Path) -> Vec<Task> {
+    let Some(raw) = ["GNUmakefile", "makefile", "Makefile"]
+        .iter()
+        .find_map(|name| std::fs::read_to_string(dir.join(name)).ok())
+    else {
+        return Vec::new();
+    };
+
+    let mut tasks: Vec<Task> = Vec::new();
+    let mut comment = None;
+    for line in raw.lines() {
+        if let Some(text) = line.strip_prefix("##") {
+            comment = Some(text.trim().to_string());
+            continue;
+        }
+        if line.starts_with(['\t', ' ', '#', '.']) || line.contains(":=") || line.contains("::=") {
+            comment = None;
+            continue;
+        }
+        let Some((targets, rest)) = line.split_once(':') else {
+            comment = None;
+            continue;
+        };
+        if rest.starts_with('=') || targets.contains(['%', '$', '=']) {
+            comment = None;
+            continue;
+        }
+
+        let description = rest
+            .split_once("##")
+            .map(|(_, text)| text.trim().to_string())
+            .or(comment.take());
+        for target in targets.split_whitespace() {
+            if !tasks.iter().any(|task| task.name == target) {
+                tasks.push(task(
+                    TaskSource::Make,
+                    target,
+                    description.clone(),
+                    "make",
+                    vec![target.to_string()],
+                ));
+            }
+        }
+    }
+    tasks
+}
+
+fn just_tasks(dir: &Path) -> Vec<Task> {
+    let Some(raw) = ["justfile", "Justfile", ".justfile"]
+        .iter()
+        .find_map(|name| std::fs::read_to_string(dir.join(name)).ok())
+    else {
+        return Vec::new();
+    };
+
+    let mut tasks = Vec::new();
+    let mut comment = None;
+    for line in raw.lines() {
+        if let Some(text) = line.strip_prefix('#') {
+            comment = Some(text.trim().to_string()).filter(|text| !text.starts_with('!'));
+            continue;
+        }
+        if line.trim().is_empty() || line.starts_with([' ', '\t', '[']) {
+            if !line.starts_with('[') {
+                comment = None;
+            }
+            continue;
+        }
+
+        let header = line.trim_start_matches('@');
+        let Some((signature, _)) = header.split_once(':') else {
+            comment = None;
+            continue;
+        };
+        let name = signature.split_whitespace().next().unwrap_or_default();
+        let keyword = matches!(name, "set" | "alias" | "export" | "import" | "mod");
+        let valid = !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-');
+        if keyword || !valid || header[signature.len()..].starts_with(":=") || signature.contains(":=") {
+            comment = None;
+            continue;
+        }
+        // Recipes starting with `_` are private.
+        if !name.starts_with('_') {
+            tasks.push(task(TaskSource::Just, name, comment.take(), "just", vec![name.to_string()]));
+        }
+        comment = None;
+    }
+    tasks
+}
+
+/// Reads `[alias]` entries from `.cargo/config.toml` or `.cargo/config`.
+fn cargo_tasks(dir: &Path) -> Vec<Task> {
+    let Some(raw) = ["config.toml", "config"]
+        .iter()
+        .find_map(|name| std::fs::read_to_string(dir.join(".cargo").join(name)).ok())
+    else {
+        return Vec::new();
+    };
+
+    let mut tasks = Vec::new();
+    let mut in_alias = false;
+    for line in raw.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_alias = line == "[alias]";
+            continue;
+        }
+        if !in_alias {
+            continue;
+        }
+        let Some((name, value)) = line.split_once('=') else {
+            continue;
+        };
+        let name = name.trim().trim_matches('"');
+        if name.is_empty() || name.starts_with('#') {
+            continue;
+        }
+        tasks.push(task(
+            TaskSource::Cargo,
+            name,
+            Some(value.trim().to_string()),
+            "cargo",
+            vec![name.to_string()],
+        ));
+    }
+    tasks
+}
+
+fn discover(dir: &Path) -> Vec<Task> {
+    let mut tasks = npm_tasks(dir);
+    tasks.extend(make_tasks(dir));
+    tasks.extend(just_tasks(dir));
+    tasks.extend(cargo_tasks(dir));
+    tasks
+}
+
+/// Picks the directory to scan: `cwd` when given, otherwise the cwd of `tab_id`'s shell.
+fn task_dir(cwd: Option<String>, tab_id: Option<&str>, state: &TerminalState) -> Result<PathBuf, AppError> {
+    if let Some(cwd) = cwd {
+        return Ok(PathBuf::from(cwd));
+    }
+
+    let tab_id = tab_id.ok_or_else(|| AppError::invalid("no directory to scan for tasks"))?;
+    let sessions = state
+        .sessions
+        .lock()
+        .map_err(|_| "failed to lock terminal sessions".to_string())?;
+    let session = sessions
+        .get(tab_id)
+        .ok_or_else(|| AppError::session_not_found(tab_id))?;
+    terminal::session_cwd(session)?
+        .map(PathBuf::from)
+        .ok_or_else(|| AppError::from("session cwd unavailable"))
+}
+
+#[tauri::command]
+pub fn list_tasks(
+    cwd: Option<String>,
+    tab_id: Option<String>,
+    state: tauri::State<TerminalState>,
+) -> Result<Vec<Task>, AppError> {
+    let dir = task_dir(cwd, tab_id.as_deref(), &state)?;
+    Ok(discover(&dir))
+}
+
+/// Runs a discovered task in a new tab `tab_id`.
+#[tauri::command]
+pub fn run_task(
+    tab_id: String,
+    cwd: String,
+    task_id: String,
+    close_on_exit: Option<bool>,
+    app: tauri::AppHandle,
+    webview_window: tauri::WebviewWindow,
+    state: tauri::State<TerminalState>,
+) -> Result<OpenTerminalResponse, AppError> {
+    let task = discover(Path::new(&cwd))
+        .into_iter()
+        .find(|task| task.id == task_id)
+        .ok_or_else(|| AppError::invalid(format!("task not found: {task_id}")))?;
+
+    let spec = CommandSpec {
+        program: task.program,
+        args: task.args,
+        env: HashMap::new(),
+    };
+    terminal::open_program(
+        &app,
+        &state,
+        webview_window.label(),
+        tab_id,
+        spec,
+        Some(cwd),
+        close_on_exit.unwrap_or(false),
+    )
+}
@@ -0,0 +1,318 @@
+use crate::{
+    error::AppError,
+    scrollback::ScrollbackLimits,
+    session_store,
+    stream::OutputEncoding,
+    terminal::{
+        self, OpenTerminalResponse, OutputObserver, SessionIo, SessionKind, SpawnOptions, TerminalSession,
+        TerminalState,
+    },
+    windows,
+};
+use portable_pty::ChildKiller;
+use serde::{Deserialize, Serialize};
+use std::{
+    io::{Read, Write},
+    net::{Shutdown, TcpStream, ToSocketAddrs},
+    sync::{mpsc, Arc},
+    time::Duration,
+};
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Telnet command bytes (RFC 854).
+const IAC: u8 = 255;
+const DONT: u8 = 254;
+const DO: u8 = 253;
+const WONT: u8 = 252;
+const WILL: u8 = 251;
+const SB: u8 = 250;
+const SE: u8 = 240;
+const OPTION_ECHO: u8 = 1;
+const OPTION_SUPPRESS_GO_AHEAD: u8 = 3;
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TcpTarget {
+    pub host: String,
+    pub port: u16,
+    /// Handle telnet option negotiation instead of passing bytes through untouched.
+    #[serde(default)]
+    pub telnet: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum TcpStatus {
+    Connecting,
+    Connected,
+    Failed,
+    Disconnected,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TcpStatusEvent {
+    tab_id: String,
+    host: String,
+    port: u16,
+    status: TcpStatus,
+    error: Option<String>,
+}
+
+fn emit_status(app: &tauri::AppHandle, tab_id: &str, target: &TcpTarget, status: TcpStatus, error: Option<String>) {
+    windows::emit(
+        app,
+        tab_id,
+        "tcp-status",
+        TcpStatusEvent {
+            tab_id: tab_id.to_string(),
+            host: target.host.clone(),
+            port: target.port,
+            status,
+            error,
+        },
+    );
+}
+
+/// Reports the disconnect once the socket is closed from either side.
+struct TcpStatusObserver(TcpTarget);
+
+impl OutputObserver for TcpStatusObserver {
+    fn observe(&mut self, _app: &tauri::AppHandle, _tab_id: &str, _bytes: &[u8]) {}
+
+    fn finish(&mut self, app: &tauri::AppHandle, tab_id: &str) {
+        emit_status(app, tab_id, &self.0, TcpStatus::Disconnected, None);
+    }
+}
+
+/// Closes the connection; the reader then sees end of stream.
+#[derive(Clone, Debug)]
+struct TcpKiller(Arc<TcpStream>);
+
+impl ChildKiller for TcpKiller {
+    fn kill(&mut self) -> std::io::Result<()> {
+        self.0.shutdown(Shutdown::Both)
+    }
+
+    fn clone_killer(&self) -> Box<dyn ChildKiller + Send + Sync> {
+        Box::new(self.clone())
+    }
+}
+
+#[derive(Clone, Copy, Default)]
+enum TelnetState {
+    #[default]
+    Data,
+    Command,
+    Option(u8),
+    Subnegotiation,
+    SubnegotiationCommand,
+}
+
+/// Strips telnet commands from the stream and answers option requests. Only echo and
+/// suppress-go-ahead are accepted from the server; everything else is refused.
+struct TelnetReader {
+    stream: TcpStream,
+    state: TelnetState,
+}
+
+impl TelnetReader {
+    fn reply(&mut self, command: u8, option: u8) {
+        let _ = self.stream.write_all(&[IAC, command, option]);
+    }
+
+    fn negotiate(&mut self, command: u8, option: u8) {
+        match command {
+            WILL if matches!(option, OPTION_ECHO | OPTION_SUPPRESS_GO_AHEAD) => self.reply(DO, option),
+            WILL => self.reply(DONT, option),
+            DO => self.reply(WONT, option),
+            _ => {}
+        }
+    }
+}
+
+impl Read for TelnetReader {
+    fn read(&mut self, buffer: &mut [u8]) -> std::io::Result<usize> {
+        let mut raw = vec![0_u8; buffer.len()];
+        loop {
+            let read = self.stream.read(&mut raw)?;
+            if read == 0 {
+                return Ok(0);
+            }
+
+            let mut written = 0;
+            for &byte in &raw[..read] {
+                self.state = match (self.state, byte) {
+                    (TelnetState::Data, IAC) => TelnetState::Command,
+                    (TelnetState::Data, _) => {
+                        buffer[written] = byte;
+                        written += 1;
+                        TelnetState::Data
+                    }
+                    (TelnetState::Command, IAC) => {
+                        buffer[written] = IAC;
+                        written += 1;
+                        TelnetState::Data
+                    }
+                    (TelnetState::Command, WILL | WONT | DO | DONT) => TelnetState::Option(byte),
+                    (TelnetState::Command, SB) => TelnetState::Subnegotiation,
+                    (TelnetState::Command, _) => TelnetState::Data,
+                    (TelnetState::Option(command), _) => {
+                        self.negotiate(command, byte);
+                        TelnetState::Data
+                    }
+                    (TelnetState::Subnegotiation, IAC) => TelnetState::SubnegotiationCommand,
+                    (TelnetState::Subnegotiation, _) => TelnetState::Subnegotiation,
+                    (TelnetState::SubnegotiationCommand, SE) => TelnetState::Data,
+                    (TelnetState::SubnegotiationCommand, _) => TelnetState::Subnegotiation,
+                };
+            }
+
+            // A read made only of commands isn't end of stream.
+            if written > 0 {
+                return Ok(written);
+            }
+        }
+    }
+}
+
+/// Escapes literal 0xFF bytes, which telnet would otherwise read as a command.
+struct TelnetWriter(TcpStream);
+
+impl Write for TelnetWriter {
+    fn write(&mut self, buffer: &[u8]) -> std::io::Result<usize> {
+        let mut escaped = Vec::with_capacity(buffer.len());
+        for &byte in buffer {
+            escaped.push(byte);
+            if byte == IAC {
+                escaped.push(IAC);
+            }
+        }
+        self.0.write_all(&escaped)?;
+        Ok(buffer.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.0.flush()
+    }
+}
+
+fn connect(target: &TcpTarget) -> Result<TcpStream, String> {
+    let addresses = (target.host.trim(), target.port)
+        .to_socket_addrs()
+        .map_err(|error| format!("failed to resolve {}: {error}", target.host))?;
+
+    let mut last_error = format!("no addresses for {}", target.host);
+    for address in addresses {
+        match TcpStream::connect_timeout(&address, CONNECT_TIMEOUT) {
+            Ok(stream) => return Ok(stream),
+            Err(error) => last_error = format!("failed to connect to {address}: {error}"),
+        }
+    }
+    Err(last_error)
+}
+
+pub fn spawn_tcp_session(
+    app: &tauri::AppHandle,
+    tab_id: &str,
+    target: TcpTarget,
+    encoding: OutputEncoding,
+    scrollback: ScrollbackLimits,
+    scrollback_seed: Vec<String>,
+) -> Result<TerminalSession, String> {
+    emit_status(app, tab_id, &target, TcpStatus::Connecting, None);
+    let stream = match connect(&target) {
+        Ok(stream) => stream,
+        Err(error) => {
+            emit_status(app, tab_id, &target, TcpStatus::Failed, Some(error.clone()));
+            return Err(error);
+        }
+    };
+    let _ = stream.set_nodelay(true);
+
+    let clone = |stream: &TcpStream| {
+        stream
+            .try_clone()
+            .map_err(|error| format!("failed to clone tcp stream: {error}"))
+    };
+    let (reader, writer): (Box<dyn Read + Send>, Box<dyn Write + Send>) = if target.telnet {
+        (
+            Box::new(TelnetReader {
+                stream: clone(&stream)?,
+                state: TelnetState::default(),
+            }),
+            Box::new(TelnetWriter(clone(&stream)?)),
+        )
+    } else {
+        (Box::new(clone(&stream)?), Box::new(clone(&stream)?))
+    };
+
+    // No exit status: dropping the sender lets the emitter finish without waiting.
+    let (_, exited) = mpsc::channel();
+    let io = SessionIo {
+        reader,
+        writer,
+        master: None,
+        killer: Box::new(TcpKiller(Arc::new(stream))),
+        pid: None,
+        exited,
+    };
+
+    emit_status(app, tab_id, &target, TcpStatus::Connected, None);
+    let name = format!("{}:{}", target.host.trim(), target.port);
+    Ok(terminal::start_session(
+        app,
+        tab_id,
+        name,
+        io,
+        SpawnOptions {
+            kind: SessionKind::Tcp(target.clone()),
+            encoding,
+            scrollback,
+            scrollback_seed,
+            observers: vec![Box::new(TcpStatusObserver(target))],
+            ..SpawnOptions::default()
+        },
+    ))
+}
+
+#[tauri::command]
+pub fn open_tcp_terminal(
+    tab_id: String,
+    target: TcpTarget,
+    encoding: Option<OutputEncoding>,
+    scrollback: Option<ScrollbackLimits>,
+    app: tauri::AppHandle,
+    webview_window: tauri::WebviewWindow,
+    state: tauri::State<TerminalState>,
+) -> Result<OpenTerminalResponse, AppError> {
+    let mut sessions = state
+        .sessions
+        .lock()
+        .map_err(|_| "failed to lock terminal sessions".to_string())?;
+
+    windows::claim(&app, &tab_id, webview_window.label());
+    if let Some(session) = sessions.get(&tab_id) {
+        return Ok(OpenTerminalResponse {
+            shell: session.shell.clone(),
+        });
+    }
+
+    let session = spawn_tcp_session(
+        &app,
+        &tab_id,
+        target,
+        encoding.unwrap_or_default(),
+        scrollback.unwrap_or_default(),
+        Vec::new(),
+    )
+    .map_err(AppError::pty)
+    .inspect_err(|_| windows::forget_tab(&app, &tab_id))?;
+    let shell = session.shell.clone();
+
+    sessions.insert(tab_id, session);
+    let _ = session_store::persist(&app, &sessions);
+
+    Ok(OpenTerminalResponse { shell })
+}
@@ -0,0 +1,1474 @@
+use crate::{
+    activity::{self, ActivityObserver},
+    broadcast,
+    error::{AppError, ErrorKind},
+    flow::{
+        self, FloodDetector, FlowControl, FlowHandle, FlowState, FLUSH_INTERVAL, MAX_EVENT_BYTES, QUEUE_CHUNKS,
+        SUMMARY_INTERVAL,
+    },
+    images::{self, ImageScanner},
+    layout,
+    process::{self, ProcessInfo, ProcessMonitor, Signal},
+    profiles::{self, ShellProfile},
+    recording::{RecordingObserver, RecordingSlot},
+    screen::{self, ScreenObserver, ScreenSlot},
+    scrollback::{strip_escapes, Scrollback, ScrollbackLimits, ScrollbackMatch, ScrollbackRange},
+    serial::{self, SerialTarget},
+    session_store,
+    shell_integration::{self, CommandRecord, OscScanner, ReportedState},
+    ssh::{self, SshTarget},
+    stream::{OutputDecoder, OutputEncoding},
+    supervisor,
+    tcp::{self, TcpTarget},
+    tmux::{self, TmuxPane},
+    triggers::TriggerScanner,
+    windows,
+    writer::SessionWriter,
+};
+use portable_pty::{native_pty_system, ChildKiller, CommandBuilder, ExitStatus, MasterPty, PtySize};
+use regex::RegexBuilder;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    io::{Read, Write},
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+use tauri::{Emitter, Manager};
+
+const MAX_SEARCH_RESULTS: usize = 1000;
+
+pub struct TerminalSession {
+    writer: SessionWriter,
+    /// `None` for sessions that aren't backed by a PTY, such as serial ports.
+    master: Option<Box<dyn MasterPty + Send>>,
+    killer: Box<dyn ChildKiller + Send + Sync>,
+    pid: Option<u32>,
+    /// Set by `close_terminal` so the exit event can tell a requested close from a crash.
+    close_requested: Arc<AtomicBool>,
+    /// Set once `terminal-exit` was emitted, by the emitter or by the supervisor.
+    exit_reported: Arc<AtomicBool>,
+    pub shell: String,
+    pub profile_id: Option<String>,
+    pub kind: SessionKind,
+    pub encoding: OutputEncoding,
+    pub opened_at: Instant,
+    pub scrollback: Arc<Mutex<Scrollback>>,
+    pub screen: ScreenSlot,
+    pub reported: Arc<Mutex<ReportedState>>,
+    pub recording: RecordingSlot,
+    /// Respawn the shell in place when it exits on its own.
+    pub restart_on_exit: bool,
+    /// Drop the session once its process exits, used for one-off commands.
+    pub close_on_exit: bool,
+    flow: FlowHandle,
+}
+
+pub struct TerminalState {
+    pub sessions: Mutex<HashMap<String, TerminalSession>>,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum SessionKind {
+    #[default]
+    Local,
+    Ssh(SshTarget),
+    /// A program run directly in the PTY instead of a shell.
+    Command(CommandSpec),
+    Serial(SerialTarget),
+    Tcp(TcpTarget),
+    Tmux(TmuxPane),
+}
+
+impl SessionKind {
+    /// Whether the session's process runs on this machine, so its cwd and children can be read.
+    pub fn is_local_process(&self) -> bool {
+        matches!(self, SessionKind::Local | SessionKind::Command(_))
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandSpec {
+    pub program: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+}
+
+/// Hook into a session's reader thread. Observers see the raw PTY bytes before they are decoded
+/// and forwarded to the frontend, and are finished once the stream ends.
+pub trait OutputObserver: Send {
+    fn observe(&mut self, app: &tauri::AppHandle, tab_id: &str, bytes: &[u8]);
+
+    fn finish(&mut self, _app: &tauri::AppHandle, _tab_id: &str) {}
+
+    /// Called after `finish` with the exit status, `None` when there is none or it didn't arrive.
+    fn exited(&mut self, _app: &tauri::AppHandle, _tab_id: &str, _status: Option<&ExitStatus>) {}
+}
+
+/// The pieces of a running session: PTY or device streams, a way to stop it, and a channel that
+/// yields the exit status once it has ended.
+pub struct SessionIo {
+    pub reader: Box<dyn Read + Send>,
+    pub writer: Box<dyn Write + Send>,
+    pub master: Option<Box<dyn MasterPty + Send>>,
+    pub killer: Box<dyn ChildKiller + Send + Sync>,
+    pub pid: Option<u32>,
+    pub exited: mpsc::Receiver<Option<ExitStatus>>,
+}
+
+#[derive(Default)]
+pub struct SpawnOptions {
+    pub profile_id: Option<String>,
+    pub kind: SessionKind,
+    pub cwd: Option<String>,
+    pub encoding: OutputEncoding,
+    pub scrollback: ScrollbackLimits,
+    pub observers: Vec<Box<dyn OutputObserver>>,
+    /// Lines from an earlier run put in the scrollback before any output, for restored sessions.
+    pub scrollback_seed: Vec<String>,
+    /// Initial PTY size; defaults to 80x24.
+    pub size: PtySize,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenTerminalResponse {
+    pub shell: String,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForegroundProcess {
+    pid: u32,
+    name: String,
+}
+
+#[derive(Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SignalTarget {
+    Shell,
+    #[default]
+    Foreground,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TerminalDataEvent {
+    tab_id: String,
+    data: String,
+    encoding: OutputEncoding,
+    /// Bytes left out before `data` while a flooding session is summarized.
+    #[serde(skip_serializing_if = "is_zero")]
+    skipped: usize,
+}
+
+fn is_zero(value: &usize) -> bool {
+    *value == 0
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TerminalFloodEvent {
+    tab_id: String,
+    flooding: bool,
+    bytes_per_sec: u64,
+    summarized: bool,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TerminalRestartedEvent {
+    tab_id: String,
+    shell: String,
+    window_label: Option<String>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TerminalExitEvent {
+    tab_id: String,
+    exit_code: Option<u32>,
+    signal: Option<String>,
+    was_requested: bool,
+    window_label: Option<String>,
+}
+
+#[cfg(target_os = "windows")]
+pub fn apply_terminal_env(_builder: &mut CommandBuilder) {}
+
+#[cfg(not(target_os = "windows"))]
+pub fn apply_terminal_env(builder: &mut CommandBuilder) {
+    builder.env("TERM", "xterm-256color");
+    builder.env("COLORTERM", "truecolor");
+    builder.env("TERM_PROGRAM", "ghostty-web");
+    builder.env("CLICOLOR", "1");
+}
+
+#[cfg(target_os = "windows")]
+fn shell_details() -> (String, CommandBuilder) {
+    let shell = "cmd.exe".to_string();
+    let builder = CommandBuilder::new(shell.clone());
+    (shell, builder)
+}
+
+#[cfg(not(target_os = "windows"))]
+fn shell_details() -> (String, CommandBuilder) {
+    let shell = std::env::var("SHELL").unwrap_or_else(|_| "/bin/bash".to_string());
+    let mut builder = CommandBuilder::new(shell.clone());
+    apply_terminal_env(&mut builder);
+    (shell, builder)
+}
+
+fn profile_details(app: &tauri::AppHandle, profile: &ShellProfile) -> Result<(String, CommandBuilder), String> {
+    let mut builder = CommandBuilder::new(profile.shell.clone());
+    apply_terminal_env(&mut builder);
+
+    for (key, value) in &profile.env {
+        builder.env(key, value);
+    }
+
+    let integrated =
+        profile.shell_integration && shell_integration::install(app, &profile.shell, profile.login, &mut builder)?;
+    if profile.login && !integrated && cfg!(not(target_os = "windows")) {
+        builder.arg("-l");
+    }
+    builder.args(&profile.args);
+
+    if let Some(cwd) = profile.cwd.as_deref().filter(|cwd| !cwd.trim().is_empty()) {
+        builder.cwd(cwd);
+    }
+
+    Ok((profile.shell.clone(), builder))
+}
+
+impl TerminalSession {
+    pub fn pty_size(&self) -> PtySize {
+        self.master
+            .as_ref()
+            .and_then(|master| master.get_size().ok())
+            .unwrap_or_default()
+    }
+
+    /// Current PTY size as `(cols, rows)`.
+    pub fn size(&self) -> (u16, u16) {
+        let size = self.pty_size();
+        (size.cols, size.rows)
+    }
+
+    /// Local process behind the session, if any: the shell, program or ssh client.
+    pub fn pid(&self) -> Option<u32> {
+        self.pid
+    }
+}
+
+pub fn session_cwd(session: &TerminalSession) -> Result<Option<String>, String> {
+    if !session.kind.is_local_process() {
+        return Ok(None);
+    }
+
+    match session.pid {
+        Some(pid) => process::process_cwd(pid),
+        None => Ok(None),
+    }
+}
+
+/// Returns the job running in the foreground of a local session, or `None` while the shell itself
+/// has the terminal.
+#[cfg(unix)]
+pub fn session_foreground(session: &TerminalSession) -> Option<ForegroundProcess> {
+    if !session.kind.is_local_process() {
+        return None;
+    }
+
+    let shell_pid = session.pid?;
+    let leader = u32::try_from(session.master.as_ref()?.process_group_leader()?).ok()?;
+    if leader == shell_pid {
+        return None;
+    }
+    Some(ForegroundProcess {
+        pid: leader,
+        name: process::process_name(leader).unwrap_or_default(),
+    })
+}
+
+#[cfg(target_os = "windows")]
+pub fn session_foreground(session: &TerminalSession) -> Option<ForegroundProcess> {
+    if !session.kind.is_local_process() {
+        return None;
+    }
+
+    let (pid, name) = process::foreground_descendant(session.pid?)?;
+    Some(ForegroundProcess { pid, name })
+}
+
+/// Runs `spec` directly in a new PTY, with the same terminal environment a shell would get.
+pub fn spawn_program(
+    app: &tauri::AppHandle,
+    tab_id: &str,
+    spec: CommandSpec,
+    cwd: Option<String>,
+    encoding: OutputEncoding,
+) -> Result<TerminalSession, String> {
+    let mut builder = CommandBuilder::new(&spec.program);
+    builder.args(&spec.args);
+    apply_terminal_env(&mut builder);
+    for (key, value) in &spec.env {
+        builder.env(key, value);
+    }
+
+    let program = spec.program.clone();
+    spawn_command(
+        app,
+        tab_id,
+        program,
+        builder,
+        SpawnOptions {
+            kind: SessionKind::Command(spec),
+            cwd,
+            encoding,
+            ..SpawnOptions::default()
+        },
+    )
+}
+
+/// Starts a new session of the same kind as `source`: same profile, SSH target or program.
+fn respawn(
+    app: &tauri::AppHandle,
+    tab_id: &str,
+    source: &TerminalSession,
+    cwd: Option<String>,
+) -> Result<TerminalSession, String> {
+    match source.kind.clone() {
+        SessionKind::Ssh(target) => {
+            ssh::spawn_ssh_session(app, tab_id, target, source.encoding, ScrollbackLimits::default(), Vec::new())
+        }
+        SessionKind::Command(spec) => spawn_program(app, tab_id, spec, cwd, source.encoding),
+        SessionKind::Serial(target) => {
+            serial::spawn_serial_session(
+                app,
+                tab_id,
+                target,
+                source.encoding,
+                ScrollbackLimits::default(),
+                Vec::new(),
+            )
+        }
+        SessionKind::Tcp(target) => {
+            tcp::spawn_tcp_session(app, tab_id, target, source.encoding, ScrollbackLimits::default(), Vec::new())
+        }
+        SessionKind::Tmux(_) => Err("tmux panes can't be restarted or duplicated".to_string()),
+        SessionKind::Local => {
+            let options = SpawnOptions {
+                profile_id: source.profile_id.clone(),
+                cwd,
+                encoding: source.encoding,
+                size: source.pty_size(),
+                ..SpawnOptions::default()
+            };
+            spawn_session(app, tab_id, options)
+        }
+    }
+}
+
+pub fn spawn_session(app: &tauri::AppHandle, tab_id: &str, options: SpawnOptions) -> Result<TerminalSession, String> {
+    let (shell, shell_command) = match options.profile_id.as_deref() {
+        Some(id) => profile_details(app, &profiles::find_profile(app, id)?)?,
+        None => shell_details(),
+    };
+
+    spawn_command(app, tab_id, shell, shell_command, options)
+}
+
+/// Name of the signal that ended the child. portable-pty keeps it private and only exposes it
+/// through `Display` as "Terminated by <signal>".
+fn exit_signal(status: &ExitStatus) -> Option<String> {
+    status.to_string().strip_prefix("Terminated by ").map(ToOwned::to_owned)
+}
+
+pub fn emit_data(app: &tauri::AppHandle, tab_id: &str, encoding: OutputEncoding, data: String, skipped: usize) {
+    if data.is_empty() && skipped == 0 {
+        return;
+    }
+    windows::emit(
+        app,
+        tab_id,
+        "terminal-data",
+        TerminalDataEvent {
+            tab_id: tab_id.to_string(),
+            data,
+            encoding,
+            skipped,
+        },
+    );
+}
+
+/// What the emitter thread shares with the reader thread, the session and the supervisor.
+struct EmitterLinks {
+    queued: mpsc::Receiver<Vec<u8>>,
+    /// The reader's observers, handed over once the stream has ended.
+    observers: mpsc::Receiver<Vec<Box<dyn OutputObserver>>>,
+    flow: Arc<FlowControl>,
+    exited: mpsc::Receiver<Option<ExitStatus>>,
+    close_requested: Arc<AtomicBool>,
+    exit_reported: Arc<AtomicBool>,
+}
+
+/// Coalesces queued PTY reads into `terminal-data` events of at most `MAX_EVENT_BYTES`, flushed
+/// every `FLUSH_INTERVAL`, and emits `terminal-exit` with the child's status once the reader has
+/// finished. Sessions whose output rate crosses the flood threshold get `terminal-output-flood`
+/// events and, if enabled, only the tail of each batch is forwarded.
+fn emit_output(app: tauri::AppHandle, tab_id: String, encoding: OutputEncoding, links: EmitterLinks) {
+    let EmitterLinks {
+        queued,
+        observers,
+        flow,
+        exited,
+        close_requested,
+        exit_reported,
+    } = links;
+    let mut decoder = OutputDecoder::new(encoding);
+    let mut flood = FloodDetector::default();
+
+    while let Ok(mut batch) = queued.recv() {
+        let settings = app.state::<FlowState>().flood_settings();
+        let summarizing = flood.summarizing(settings);
+        let (interval, limit) = if summarizing {
+            (SUMMARY_INTERVAL, usize::MAX)
+        } else {
+            (FLUSH_INTERVAL, MAX_EVENT_BYTES)
+        };
+
+        let deadline = Instant::now() + interval;
+        while batch.len() < limit {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            match queued.recv_timeout(remaining) {
+                Ok(chunk) => batch.extend_from_slice(&chunk),
+                Err(_) => break,
+            }
+        }
+
+        if let Some((flooding, bytes_per_sec)) = flood.record(batch.len(), settings) {
+            windows::emit(
+                &app,
+                &tab_id,
+                "terminal-output-flood",
+                TerminalFloodEvent {
+                    tab_id: tab_id.clone(),
+                    flooding,
+                    bytes_per_sec,
+                    summarized: flood.summarizing(settings),
+                },
+            );
+        }
+
+        flow.wait_while_paused();
+        if summarizing {
+            let (tail, skipped) = flow::summarize(&batch);
+            emit_data(&app, &tab_id, encoding, decoder.decode(tail), skipped);
+            continue;
+        }
+        for slice in batch.chunks(MAX_EVENT_BYTES) {
+            emit_data(&app, &tab_id, encoding, decoder.decode(slice), 0);
+        }
+    }
+
+    emit_data(&app, &tab_id, encoding, decoder.finish(), 0);
+
+    // The PTY can reach EOF slightly before the child is reaped.
+    let status = exited.recv_timeout(Duration::from_secs(2)).ok().flatten();
+    if let Ok(mut observers) = observers.try_recv() {
+        for observer in observers.iter_mut() {
+            observer.exited(&app, &tab_id, status.as_ref());
+        }
+    }
+    if exit_reported.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let was_requested = close_requested.load(Ordering::SeqCst);
+    emit_exit(&app, &tab_id, windows::owner(&app, &tab_id), status.as_ref(), was_requested);
+
+    if !was_requested {
+        let _ = finish_session(&app, &tab_id, &flow);
+    }
+}
+
+/// `window_label` is read by the caller, since closing the session forgets the tab's owner.
+fn emit_exit(
+    app: &tauri::AppHandle,
+    tab_id: &str,
+    window_label: Option<String>,
+    status: Option<&ExitStatus>,
+    was_requested: bool,
+) {
+    let _ = app.emit(
+        "terminal-exit",
+        TerminalExitEvent {
+            tab_id: tab_id.to_string(),
+            exit_code: status.map(ExitStatus::exit_code),
+            signal: status.and_then(exit_signal),
+            was_requested,
+            window_label,
+        },
+    );
+}
+
+/// Drops a session whose child exited without its reader noticing, and emits the `terminal-exit`
+/// the emitter never sent. A session that has since been replaced is left alone. `was_requested`
+/// is the dead session's own close flag.
+pub fn drop_dead_session(
+    app: &tauri::AppHandle,
+    tab_id: &str,
+    exit_reported: &Arc<AtomicBool>,
+    was_requested: bool,
+    status: Option<&ExitStatus>,
+) {
+    let state = app.state::<TerminalState>();
+    let Ok(mut sessions) = state.sessions.lock() else {
+        return;
+    };
+    let current = sessions
+        .get(tab_id)
+        .is_some_and(|session| Arc::ptr_eq(&session.exit_reported, exit_reported));
+    let window_label = windows::owner(app, tab_id);
+    if current {
+        close_session(app, &mut sessions, tab_id);
+        let _ = session_store::persist(app, &sessions);
+    }
+    drop(sessions);
+
+    emit_exit(app, tab_id, window_label, status, was_requested);
+}
+
+/// Handles a session whose process exited on its own: drops it when `close_on_exit` is set, or
+/// replaces it with a fresh one using the same profile, SSH target or program when
+/// `restart_on_exit` is set, in the last directory the shell reported.
+fn finish_session(app: &tauri::AppHandle, tab_id: &str, flow: &Arc<FlowControl>) -> Result<(), String> {
+    let state = app.state::<TerminalState>();
+    let mut sessions = state
+        .sessions
+        .lock()
+        .map_err(|_| "failed to lock terminal sessions".to_string())?;
+
+    // The tab may have been closed, or already reopened with a new session.
+    let Some(previous) = sessions.get(tab_id) else {
+        return Ok(());
+    };
+    if !Arc::ptr_eq(&previous.flow.0, flow) {
+        return Ok(());
+    }
+    if previous.close_on_exit {
+        sessions.remove(tab_id);
+        let _ = session_store::persist(app, &sessions);
+        layout::forget_pane(app, tab_id);
+        images::forget_tab(app, tab_id);
+        activity::forget_tab(app, tab_id);
+        windows::forget_tab(app, tab_id);
+        return Ok(());
+    }
+    if !previous.restart_on_exit {
+        return Ok(());
+    }
+
+    let cwd = previous.reported.lock().ok().and_then(|reported| reported.cwd.clone());
+    let mut session = respawn(app, tab_id, previous, cwd)?;
+    session.restart_on_exit = true;
+    let custom_title = previous.reported.lock().ok().and_then(|reported| reported.custom_title.clone());
+    if let Ok(mut reported) = session.reported.lock() {
+        reported.custom_title = custom_title;
+    }
+    let shell = session.shell.clone();
+
+    sessions.insert(tab_id.to_string(), session);
+    let _ = session_store::persist(app, &sessions);
+    let _ = app.emit(
+        "terminal-restarted",
+        TerminalRestartedEvent {
+            tab_id: tab_id.to_string(),
+            shell,
+            window_label: windows::owner(app, tab_id),
+        },
+    );
+    Ok(())
+}
+
+pub fn spawn_command(
+    app: &tauri::AppHandle,
+    tab_id: &str,
+    shell: String,
+    mut shell_command: CommandBuilder,
+    options: SpawnOptions,
+) -> Result<TerminalSession, String> {
+    let pty_system = native_pty_system();
+    let pair = pty_system
+        .openpty(options.size)
+        .map_err(|error| format!("failed to open pty: {error}"))?;
+
+    if let Some(cwd) = options.cwd.as_deref().filter(|cwd| Path::new(cwd).is_dir()) {
+        shell_command.cwd(cwd);
+    }
+
+    let child = pair
+        .slave
+        .spawn_command(shell_command)
+        .map_err(|error| format!("failed to spawn shell: {error}"))?;
+
+    drop(pair.slave);
+
+    let pid = child.process_id();
+    let killer = child.clone_killer();
+    let (exit_sender, exited) = mpsc::channel();
+
+    let reader = pair
+        .master
+        .try_clone_reader()
+        .map_err(|error| format!("failed to clone pty reader: {error}"))?;
+
+    let writer = pair
+        .master
+        .take_writer()
+        .map_err(|error| format!("failed to get pty writer: {error}"))?;
+
+    let io = SessionIo {
+        reader,
+        writer,
+        master: Some(pair.master),
+        killer,
+        pid,
+        exited,
+    };
+    let session = start_session(app, tab_id, shell, io, options);
+    supervisor::watch(
+        app,
+        tab_id,
+        child,
+        exit_sender,
+        Arc::clone(&session.exit_reported),
+        Arc::clone(&session.close_requested),
+    );
+    Ok(session)
+}
+
+/// Starts the reader and emitter threads for a session's I/O and wraps it up as a session.
+pub fn start_session(
+    app: &tauri::AppHandle,
+    tab_id: &str,
+    shell: String,
+    io: SessionIo,
+    options: SpawnOptions,
+) -> TerminalSession {
+    let SessionIo {
+        mut reader,
+        writer,
+        master,
+        killer,
+        pid,
+        exited,
+    } = io;
+
+    let app_handle = app.clone();
+    let reader_tab_id = tab_id.to_string();
+    let encoding = options.encoding;
+    let mut seeded = Scrollback::new(options.scrollback);
+    if !options.scrollback_seed.is_empty() {
+        let mut seed = options.scrollback_seed.join("\n");
+        seed.push('\n');
+        seeded.push(seed.as_bytes());
+    }
+    let seeded_lines = seeded.total();
+    let scrollback = Arc::new(Mutex::new(seeded));
+    let reader_scrollback = Arc::clone(&scrollback);
+    let mut observers = options.observers;
+    let screen = screen::new_screen(options.size.rows, options.size.cols);
+    observers.push(Box::new(ScreenObserver(Arc::clone(&screen))));
+    let reported = Arc::new(Mutex::new(ReportedState {
+        cwd: options.cwd.clone(),
+        ..ReportedState::default()
+    }));
+    observers.push(Box::new(OscScanner::new(Arc::clone(&reported), seeded_lines)));
+    let recording = RecordingSlot::default();
+    observers.push(Box::new(RecordingObserver(Arc::clone(&recording))));
+    observers.push(Box::new(TriggerScanner::new(options.profile_id.clone())));
+    observers.push(Box::new(ImageScanner::default()));
+    observers.push(Box::new(ActivityObserver));
+
+    let flow = Arc::new(FlowControl::default());
+    let emitter_flow = Arc::clone(&flow);
+    let (chunks, queued) = mpsc::sync_channel::<Vec<u8>>(QUEUE_CHUNKS);
+    let (finished, finished_observers) = mpsc::channel();
+
+    std::thread::spawn(move || {
+        let mut buffer = [0_u8; 8192];
+
+        loop {
+            match reader.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(read) => {
+                    if let Ok(mut scrollback) = reader_scrollback.lock() {
+                        scrollback.push(&buffer[..read]);
+                    }
+
+                    for observer in observers.iter_mut() {
+                        observer.observe(&app_handle, &reader_tab_id, &buffer[..read]);
+                    }
+
+                    // Blocks while the emitter is paused and the queue is full.
+                    if chunks.send(buffer[..read].to_vec()).is_err() {
+                        break;
+                    }
+                }
+                Err(_) => break,
+            }
+        }
+
+        for observer in observers.iter_mut() {
+            observer.finish(&app_handle, &reader_tab_id);
+        }
+        // Sent before `chunks` is dropped, so the emitter has them once the queue closes.
+        let _ = finished.send(observers);
+    });
+
+    let emitter_app = app.clone();
+    let emitter_tab_id = tab_id.to_string();
+    let close_requested = Arc::new(AtomicBool::new(false));
+    let exit_reported = Arc::new(AtomicBool::new(false));
+    let links = EmitterLinks {
+        queued,
+        observers: finished_observers,
+        flow: emitter_flow,
+        exited,
+        close_requested: Arc::clone(&close_requested),
+        exit_reported: Arc::clone(&exit_reported),
+    };
+    std::thread::spawn(move || emit_output(emitter_app, emitter_tab_id, encoding, links));
+
+    TerminalSession {
+        writer: SessionWriter::spawn(app, tab_id, writer),
+        master,
+        killer,
+        pid,
+        close_requested,
+        exit_reported,
+        shell,
+        profile_id: options.profile_id,
+        kind: options.kind,
+        encoding,
+        opened_at: Instant::now(),
+        scrollback,
+        screen,
+        reported,
+        recording,
+        restart_on_exit: false,
+        close_on_exit: false,
+        flow: FlowHandle(flow),
+    }
+}
+
+#[tauri::command]
+pub fn terminal_cwd(tab_id: String, state: tauri::State<TerminalState>) -> Result<Option<String>, AppError> {
+    let sessions = state
+        .sessions
+        .lock()
+        .map_err(|_| "failed to lock terminal sessions".to_string())?;
+
+    match sessions.get(&tab_id) {
+        Some(session) => Ok(session_cwd(session)?),
+        None => Ok(None),
+    }
+}
+
+/// Environment of a local session's shell, e.g. to show the `PATH` or `VIRTUAL_ENV` a tab has.
+/// Empty for sessions without a local process.
+#[tauri::command]
+pub fn terminal_env(tab_id: String, state: tauri::State<TerminalState>) -> Result<HashMap<String, String>, AppError> {
+    let pid = {
+        let sessions = state
+            .sessions
+            .lock()
+            .map_err(|_| "failed to lock terminal sessions".to_string())?;
+        let session = sessions
+            .get(&tab_id)
+            .ok_or_else(|| AppError::session_not_found(&tab_id))?;
+        if !session.kind.is_local_process() {
+            return Ok(HashMap::new());
+        }
+        session.pid
+    };
+
+    match pid {
+        Some(pid) => Ok(process::process_environ(pid)?),
+        None => Ok(HashMap::new()),
+    }
+}
+
+/// Lets the frontend warn before closing a tab that still runs something like `vim`.
+#[tauri::command]
+pub fn terminal_has_foreground_process(
+    tab_id: String,
+    state: tauri::State<TerminalState>,
+) -> Result<Option<ForegroundProcess>, AppError> {
+    let sessions = state
+        .sessions
+        .lock()
+        .map_err(|_| "failed to lock terminal sessions".to_string())?;
+
+    Ok(sessions.get(&tab_id).and_then(session_foreground))
+}
+
+#[cfg(unix)]
+fn signal_session(session: &mut TerminalSession, signal: Signal, target: SignalTarget) -> Result<(), String> {
+    let shell_pid = session
+        .pid
+        .ok_or_else(|| "session has no process id".to_string())?;
+
+    match target {
+        SignalTarget::Shell => process::send_signal(shell_pid, false, signal),
+        SignalTarget::Foreground => {
+            let leader = session
+                .master
+                .as_ref()
+                .and_then(|master| master.process_group_leader())
+                .and_then(|pgid| u32::try_from(pgid).ok())
+                .unwrap_or(shell_pid);
+            process::send_signal(leader, true, signal)
+        }
+    }
+}
+
+/// Windows has no signals: an interrupt goes through the console as Ctrl+C, everything else
+/// terminates the target process.
+#[cfg(target_os = "windows")]
+fn signal_session(session: &mut TerminalSession, signal: Signal, target: SignalTarget) -> Result<(), String> {
+    if matches!(signal, Signal::Interrupt) {
+        session.writer.send(b"\x03")?;
+        return Ok(());
+    }
+
+    let shell_pid = session
+        .pid
+        .ok_or_else(|| "session has no process id".to_string())?;
+    let pid = match target {
+        SignalTarget::Shell => shell_pid,
+        SignalTarget::Foreground => process::foreground_descendant(shell_pid)
+            .map(|(pid, _)| pid)
+            .unwrap_or(shell_pid),
+    };
+    process::terminate(pid)
+}
+
+/// Signals the shell or, by default, the job in the foreground of a session. Unlike writing `^C`
+/// this also reaches programs that put the terminal in raw mode.
+#[tauri::command]
+pub fn signal_terminal(
+    tab_id: String,
+    signal: Signal,
+    target: Option<SignalTarget>,
+    state: tauri::State<TerminalState>,
+) -> Result<(), AppError> {
+    let mut sessions = state
+        .sessions
+        .lock()
+        .map_err(|_| "failed to lock terminal sessions".to_string())?;
+
+    let session = sessions
+        .get_mut(&tab_id)
+        .ok_or_else(|| AppError::session_not_found(&tab_id))?;
+    signal_session(session, signal, target.unwrap_or_default()).map_err(AppError::from)
+}
+
+/// Kills the job in the foreground of a session, such as a program that ignores `^C`, and leaves
+/// the shell running. Returns the killed process, or `None` when the shell itself is in the
+/// foreground.
+#[tauri::command]
+pub fn kill_foreground_process(
+    tab_id: String,
+    state: tauri::State<TerminalState>,
+) -> Result<Option<ForegroundProcess>, AppError> {
+    let sessions = state
+        .sessions
+        .lock()
+        .map_err(|_| "failed to lock terminal sessions".to_string())?;
+
+    let session = sessions
+        .get(&tab_id)
+        .ok_or_else(|| AppError::session_not_found(&tab_id))?;
+    let Some(foreground) = session_foreground(session) else {
+        return Ok(None);
+    };
+
+    #[cfg(unix)]
+    process::send_signal(foreground.pid, true, Signal::Kill)?;
+    #[cfg(target_os = "windows")]
+    process::terminate(foreground.pid)?;
+    Ok(Some(foreground))
+}
+
+/// Lists the processes started from a local session's shell, for activity indicators and the
+/// process list of a tab.
+#[tauri::command]
+pub fn terminal_process_tree(
+    tab_id: String,
+    state: tauri::State<TerminalState>,
+    monitor: tauri::State<ProcessMonitor>,
+) -> Result<Vec<ProcessInfo>, AppError> {
+    let shell_pid = {
+        let sessions = state
+            .sessions
+            .lock()
+            .map_err(|_| "failed to lock terminal sessions".to_string())?;
+        let session = sessions
+            .get(&tab_id)
+            .ok_or_else(|| AppError::session_not_found(&tab_id))?;
+        if !session.kind.is_local_process() {
+            return Ok(Vec::new());
+        }
+        session.pid
+    };
+
+    match shell_pid {
+        Some(pid) => monitor.descendants(pid).map_err(AppError::from),
+        None => Ok(Vec::new()),
+    }
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn open_terminal(
+    tab_id: String,
+    profile_id: Option<String>,
+    cwd: Option<String>,
+    encoding: Option<OutputEncoding>,
+    scrollback: Option<ScrollbackLimits>,
+    cols: Option<u16>,
+    rows: Option<u16>,
+    pixel_width: Option<u16>,
+    pixel_height: Option<u16>,
+    app: tauri::AppHandle,
+    webview_window: tauri::WebviewWindow,
+    state: tauri::State<TerminalState>,
+) -> Result<OpenTerminalResponse, AppError> {
+    let mut sessions = state
+        .sessions
+        .lock()
+        .map_err(|_| "failed to lock terminal sessions".to_string())?;
+
+    // Claimed before spawning so the first output already goes to this window.
+    windows::claim(&app, &tab_id, webview_window.label());
+    if let Some(session) = sessions.get(&tab_id) {
+        return Ok(OpenTerminalResponse {
+            shell: session.shell.clone(),
+        });
+    }
+
+    let session = spawn_session(
+        &app,
+        &tab_id,
+        SpawnOptions {
+            profile_id,
+            cwd,
+            encoding: encoding.unwrap_or_default(),
+            scrollback: scrollback.unwrap_or_default(),
+            size: PtySize {
+                rows: rows.filter(|rows| *rows > 0).unwrap_or(24),
+                cols: cols.filter(|cols| *cols > 0).unwrap_or(80),
+                pixel_width: pixel_width.unwrap_or(0),
+                pixel_height: pixel_height.unwrap_or(0),
+            },
+            ..SpawnOptions::default()
+        },
+    )
+    .map_err(AppError::pty)
+    .inspect_err(|_| windows::forget_tab(&app, &tab_id))?;
+    let shell = session.shell.clone();
+
+    sessions.insert(tab_id, session);
+    let _ = session_store::persist(&app, &sessions);
+
+    Ok(OpenTerminalResponse { shell })
+}
+
+/// Registers a new tab running `spec` in the window labelled `window_label`, see `run_command`.
+pub fn open_program(
+    app: &tauri::AppHandle,
+    state: &TerminalState,
+    window_label: &str,
+    tab_id: String,
+    spec: CommandSpec,
+    cwd: Option<String>,
+    close_on_exit: bool,
+) -> Result<OpenTerminalResponse, AppError> {
+    let mut sessions = state
+        .sessions
+        .lock()
+        .map_err(|_| "failed to lock terminal sessions".to_string())?;
+
+    if sessions.contains_key(&tab_id) {
+        return Err(AppError::new(ErrorKind::SessionExists, format!("terminal session already exists: {tab_id}")));
+    }
+
+    windows::claim(app, &tab_id, window_label);
+    let mut session = spawn_program(app, &tab_id, spec, cwd, OutputEncoding::default())
+        .map_err(AppError::pty)
+        .inspect_err(|_| windows::forget_tab(app, &tab_id))?;
+    session.close_on_exit = close_on_exit;
+    let shell = session.shell.clone();
+
+    sessions.insert(tab_id, session);
+    let _ = session_store::persist(app, &sessions);
+
+    Ok(OpenTerminalResponse { shell })
+}
+
+/// Runs a program (not a shell) in a new tab. Its exit status arrives with `terminal-exit`; with
+/// `close_on_exit` the session is dropped afterwards.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn run_command(
+    tab_id: String,
+    command: String,
+    args: Option<Vec<String>>,
+    cwd: Option<String>,
+    env: Option<HashMap<String, String>>,
+    close_on_exit: Option<bool>,
+    app: tauri::AppHandle,
+    webview_window: tauri::WebviewWindow,
+    state: tauri::State<TerminalState>,
+) -> Result<OpenTerminalResponse, AppError> {
+    if command.trim().is_empty() {
+        return Err(AppError::invalid("command is empty"));
+    }
+
+    let spec = CommandSpec {
+        program: command,
+        args: args.unwrap_or_default(),
+        env: env.unwrap_or_default(),
+    };
+    open_program(
+        &app,
+        &state,
+        webview_window.label(),
+        tab_id,
+        spec,
+        cwd,
+        close_on_exit.unwrap_or(false),
+    )
+}
+
+#[tauri::command]
+pub fn duplicate_terminal(
+    source_tab_id: String,
+    tab_id: String,
+    app: tauri::AppHandle,
+    webview_window: tauri::WebviewWindow,
+    state: tauri::State<TerminalState>,
+) -> Result<OpenTerminalResponse, AppError> {
+    let mut sessions = state
+        .sessions
+        .lock()
+        .map_err(|_| "failed to lock terminal sessions".to_string())?;
+
+    if sessions.contains_key(&tab_id) {
+        return Err(AppError::new(ErrorKind::SessionExists, format!("terminal session already exists: {tab_id}")));
+    }
+
+    let source = sessions
+        .get(&source_tab_id)
+        .ok_or_else(|| AppError::session_not_found(&source_tab_id))?;
+
+    windows::claim(&app, &tab_id, webview_window.label());
+    let session = respawn(&app, &tab_id, source, session_cwd(source)?)
+        .map_err(AppError::pty)
+        .inspect_err(|_| windows::forget_tab(&app, &tab_id))?;
+    let shell = session.shell.clone();
+
+    sessions.insert(tab_id, session);
+    let _ = session_store::persist(&app, &sessions);
+
+    Ok(OpenTerminalResponse { shell })
+}
+
+/// Queues input for a session. Returns once the bytes are queued; write failures arrive later as
+/// `terminal-write-error` events.
+pub fn write_session(state: &TerminalState, tab_id: &str, bytes: &[u8]) -> Result<(), AppError> {
+    let sessions = state
+        .sessions
+        .lock()
+        .map_err(|_| "failed to lock terminal sessions".to_string())?;
+
+    let session = sessions
+        .get(tab_id)
+        .ok_or_else(|| AppError::session_not_found(tab_id))?;
+
+    session.writer.send(bytes)?;
+    record_input(session, bytes);
+    Ok(())
+}
+
+/// Like `write_session`, but the session's writer writes `chunks` one at a time with `delay` in
+/// between so a large paste doesn't overrun the program reading it.
+pub fn write_session_paced(
+    state: &TerminalState,
+    tab_id: &str,
+    chunks: Vec<Vec<u8>>,
+    delay: Duration,
+) -> Result<(), AppError> {
+    let sessions = state
+        .sessions
+        .lock()
+        .map_err(|_| "failed to lock terminal sessions".to_string())?;
+
+    let session = sessions
+        .get(tab_id)
+        .ok_or_else(|| AppError::session_not_found(tab_id))?;
+
+    let bytes = chunks.concat();
+    session.writer.send_paced(chunks, delay)?;
+    record_input(session, &bytes);
+    Ok(())
+}
+
+fn record_input(session: &TerminalSession, bytes: &[u8]) {
+    if let Ok(mut recording) = session.recording.lock() {
+        if let Some(recorder) = recording.as_mut() {
+            recorder.input(bytes);
+        }
+    }
+}
+
+#[tauri::command]
+pub fn write_terminal(
+    tab_id: String,
+    data: String,
+    app: tauri::AppHandle,
+    state: tauri::State<TerminalState>,
+) -> Result<(), AppError> {
+    broadcast::write_with_broadcast(&app, &state, &tab_id, data.as_bytes())
+}
+
+#[tauri::command]
+pub fn resize_terminal(
+    tab_id: String,
+    cols: u16,
+    rows: u16,
+    pixel_width: Option<u16>,
+    pixel_height: Option<u16>,
+    app: tauri::AppHandle,
+    state: tauri::State<TerminalState>,
+) -> Result<(), AppError> {
+    if cols == 0 || rows == 0 {
+        return Ok(());
+    }
+
+    let mut sessions = state
+        .sessions
+        .lock()
+        .map_err(|_| "failed to lock terminal sessions".to_string())?;
+
+    if let Some(session) = sessions.get_mut(&tab_id) {
+        if let Some(master) = session.master.as_ref() {
+            master
+                .resize(PtySize {
+                    rows,
+                    cols,
+                    pixel_width: pixel_width.unwrap_or(0),
+                    pixel_height: pixel_height.unwrap_or(0),
+                })
+                .map_err(|error| AppError::pty(format!("failed to resize pty: {error}")))?;
+        }
+        if let SessionKind::Tmux(pane) = &session.kind {
+            tmux::resize_pane(&app, pane, cols, rows)?;
+        }
+        screen::resize_screen(&session.screen, rows, cols);
+
+        if let Ok(mut recording) = session.recording.lock() {
+            if let Some(recorder) = recording.as_mut() {
+                recorder.resize(cols, rows);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_scrollback(
+    tab_id: String,
+    from: Option<usize>,
+    to: Option<usize>,
+    state: tauri::State<TerminalState>,
+) -> Result<ScrollbackRange, AppError> {
+    let sessions = state
+        .sessions
+        .lock()
+        .map_err(|_| "failed to lock terminal sessions".to_string())?;
+
+    let session = sessions
+        .get(&tab_id)
+        .ok_or_else(|| AppError::session_not_found(&tab_id))?;
+
+    let scrollback = session
+        .scrollback
+        .lock()
+        .map_err(|_| "failed to lock scrollback".to_string())?;
+
+    Ok(scrollback.range(from, to))
+}
+
+/// Searches the whole backend scrollback of a tab, including lines the renderer already dropped.
+#[tauri::command]
+pub fn search_terminal(
+    tab_id: String,
+    query: String,
+    regex: Option<bool>,
+    case_sensitive: Option<bool>,
+    state: tauri::State<TerminalState>,
+) -> Result<Vec<ScrollbackMatch>, AppError> {
+    if query.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let pattern = if regex.unwrap_or(false) {
+        query
+    } else {
+        regex::escape(&query)
+    };
+    let pattern = RegexBuilder::new(&pattern)
+        .case_insensitive(!case_sensitive.unwrap_or(false))
+        .build()
+        .map_err(|error| AppError::invalid(format!("invalid search pattern: {error}")))?;
+
+    let sessions = state
+        .sessions
+        .lock()
+        .map_err(|_| "failed to lock terminal sessions".to_string())?;
+
+    let session = sessions
+        .get(&tab_id)
+        .ok_or_else(|| AppError::session_not_found(&tab_id))?;
+
+    let scrollback = session
+        .scrollback
+        .lock()
+        .map_err(|_| "failed to lock scrollback".to_string())?;
+
+    Ok(scrollback.search(&pattern, MAX_SEARCH_RESULTS))
+}
+
+/// Commands seen through OSC 133 marks, oldest first. When the shell doesn't report the command
+/// line, the line it was typed on (prompt included) is used instead.
+#[tauri::command]
+pub fn get_command_history(tab_id: String, state: tauri::State<TerminalState>) -> Result<Vec<CommandRecord>, AppError> {
+    let sessions = state
+        .sessions
+        .lock()
+        .map_err(|_| "failed to lock terminal sessions".to_string())?;
+
+    let session = sessions
+        .get(&tab_id)
+        .ok_or_else(|| AppError::session_not_found(&tab_id))?;
+
+    let mut commands = session
+        .reported
+        .lock()
+        .map_err(|_| "failed to lock session state".to_string())?
+        .commands
+        .iter()
+        .cloned()
+        .collect::<Vec<_>>();
+
+    let scrollback = session
+        .scrollback
+        .lock()
+        .map_err(|_| "failed to lock scrollback".to_string())?;
+    for record in commands.iter_mut().filter(|record| record.command.is_none()) {
+        let Some(line) = record.output_start.checked_sub(1) else {
+            continue;
+        };
+        let range = scrollback.range(Some(line), Some(line + 1));
+        if range.start() == line {
+            record.command = range
+                .lines()
+                .first()
+                .map(|text| strip_escapes(text).trim().to_string())
+                .filter(|text| !text.is_empty());
+        }
+    }
+
+    Ok(commands)
+}
+
+/// Output of the most recent finished command, without escape sequences.
+#[tauri::command]
+pub fn copy_last_command_output(tab_id: String, state: tauri::State<TerminalState>) -> Result<String, AppError> {
+    let sessions = state
+        .sessions
+        .lock()
+        .map_err(|_| "failed to lock terminal sessions".to_string())?;
+
+    let session = sessions
+        .get(&tab_id)
+        .ok_or_else(|| AppError::session_not_found(&tab_id))?;
+
+    let (start, end) = session
+        .reported
+        .lock()
+        .map_err(|_| "failed to lock session state".to_string())?
+        .commands
+        .iter()
+        .rev()
+        .find_map(|record| record.output_end.map(|end| (record.output_start, end)))
+        .ok_or_else(|| "no finished command in this terminal".to_string())?;
+
+    let scrollback = session
+        .scrollback
+        .lock()
+        .map_err(|_| "failed to lock scrollback".to_string())?;
+    let range = scrollback.range(Some(start), Some(end));
+    if range.start() > start {
+        return Err(AppError::from("command output is no longer in scrollback"));
+    }
+
+    let output = range
+        .lines()
+        .iter()
+        .map(|line| strip_escapes(line))
+        .collect::<Vec<_>>()
+        .join("\n");
+    Ok(output.trim_end().to_string())
+}
+
+#[tauri::command]
+pub fn clear_scrollback(tab_id: String, state: tauri::State<TerminalState>) -> Result<(), AppError> {
+    let sessions = state
+        .sessions
+        .lock()
+        .map_err(|_| "failed to lock terminal sessions".to_string())?;
+
+    if let Some(session) = sessions.get(&tab_id) {
+        session
+            .scrollback
+            .lock()
+            .map_err(|_| "failed to lock scrollback".to_string())?
+            .clear();
+    }
+
+    Ok(())
+}
+
+/// Pauses (`paused: true`) or resumes output events for a tab. While paused the PTY is no longer
+/// drained once the internal queue fills, which stalls the writing process.
+#[tauri::command]
+pub fn terminal_flow_control(tab_id: String, paused: bool, state: tauri::State<TerminalState>) -> Result<(), AppError> {
+    let sessions = state
+        .sessions
+        .lock()
+        .map_err(|_| "failed to lock terminal sessions".to_string())?;
+
+    let session = sessions
+        .get(&tab_id)
+        .ok_or_else(|| AppError::session_not_found(&tab_id))?;
+    session.flow.0.set_paused(paused);
+    Ok(())
+}
+
+/// Returns the user's name for the tab if it was renamed, otherwise the title set by the shell.
+#[tauri::command]
+pub fn get_terminal_title(tab_id: String, state: tauri::State<TerminalState>) -> Result<Option<String>, AppError> {
+    let sessions = state
+        .sessions
+        .lock()
+        .map_err(|_| "failed to lock terminal sessions".to_string())?;
+
+    let session = sessions
+        .get(&tab_id)
+        .ok_or_else(|| AppError::session_not_found(&tab_id))?;
+    let reported = session
+        .reported
+        .lock()
+        .map_err(|_| "failed to lock session state".to_string())?;
+    Ok(reported.title())
+}
+
+/// Renames a tab. Passing `None` drops the custom name and goes back to the shell's title.
+#[tauri::command]
+pub fn set_terminal_title(
+    tab_id: String,
+    title: Option<String>,
+    app: tauri::AppHandle,
+    state: tauri::State<TerminalState>,
+) -> Result<(), AppError> {
+    let sessions = state
+        .sessions
+        .lock()
+        .map_err(|_| "failed to lock terminal sessions".to_string())?;
+
+    let session = sessions
+        .get(&tab_id)
+        .ok_or_else(|| AppError::session_not_found(&tab_id))?;
+    let mut reported = session
+        .reported
+        .lock()
+        .map_err(|_| "failed to lock session state".to_string())?;
+    reported.custom_title = title.filter(|title| !title.trim().is_empty());
+
+    shell_integration::emit_title(&app, &tab_id, reported.title().unwrap_or_else(|| session.shell.clone()));
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_terminal_restart_on_exit(
+    tab_id: String,
+    enabled: bool,
+    state: tauri::State<TerminalState>,
+) -> Result<(), AppError> {
+    let mut sessions = state
+        .sessions
+        .lock()
+        .map_err(|_| "failed to lock terminal sessions".to_string())?;
+
+    let session = sessions
+        .get_mut(&tab_id)
+        .ok_or_else(|| AppError::session_not_found(&tab_id))?;
+    session.restart_on_exit = enabled;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn close_terminal(
+    tab_id: String,
+    app: tauri::AppHandle,
+    state: tauri::State<TerminalState>,
+) -> Result<(), AppError> {
+    let mut sessions = state
+        .sessions
+        .lock()
+        .map_err(|_| "failed to lock terminal sessions".to_string())?;
+
+    if close_session(&app, &mut sessions, &tab_id) {
+        let _ = session_store::persist(&app, &sessions);
+    }
+
+    Ok(())
+}
+
+/// Kills a session on request and drops everything kept for its tab. Returns false when there
+/// was no such session.
+pub fn close_session(app: &tauri::AppHandle, sessions: &mut HashMap<String, TerminalSession>, tab_id: &str) -> bool {
+    let Some(mut session) = sessions.remove(tab_id) else {
+        return false;
+    };
+    session.close_requested.store(true, Ordering::SeqCst);
+    let _ = session.killer.kill();
+    layout::forget_pane(app, tab_id);
+    images::forget_tab(app, tab_id);
+    activity::forget_tab(app, tab_id);
+    windows::forget_tab(app, tab_id);
+    true
+}
@@ -0,0 +1,613 @@
+use crate::{
+    error::AppError,
+    ssh::{self, SshTarget},
+    stream::OutputEncoding,
+    terminal::{self, SessionIo, SessionKind, SpawnOptions, TerminalState},
+    windows,
+};
+use portable_pty::{native_pty_system, ChildKiller, CommandBuilder, PtySize};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    io::{BufRead, BufReader, Read, Write},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc, Arc, Mutex,
+    },
+};
+use tauri::{Emitter, EventTarget, Manager};
+
+/// Prefix of the `list-panes` format, so pane listings can be told apart from other replies.
+const PANE_FORMAT: &str = "nlk-pane #{window_id} #{pane_id} #{pane_width} #{pane_height} #{window_name}";
+/// Input bytes per `send-keys` command.
+const SEND_KEYS_CHUNK: usize = 256;
+
+/// A tmux pane shown as a tab, identified by tmux's `@window` and `%pane` numbers.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TmuxPane {
+    pub connection_id: String,
+    pub window_id: u32,
+    pub pane_id: u32,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TmuxAttachResponse {
+    connection_id: String,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TmuxPaneOpenedEvent {
+    connection_id: String,
+    tab_id: String,
+    window_id: u32,
+    pane_id: u32,
+    window_name: String,
+    cols: u16,
+    rows: u16,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TmuxWindowEvent {
+    connection_id: String,
+    window_id: u32,
+    /// New name for `tmux-window-renamed`, layout string for `tmux-layout-changed`.
+    value: String,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TmuxDetachedEvent {
+    connection_id: String,
+    reason: Option<String>,
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SplitDirection {
+    Horizontal,
+    Vertical,
+}
+
+struct PaneLink {
+    window_id: u32,
+    output: mpsc::Sender<Vec<u8>>,
+}
+
+/// One control mode client. Commands go out through `writer`; the reader thread turns
+/// notifications into pane output and tab changes.
+pub struct TmuxConnection {
+    id: String,
+    /// The window that attached, which shows the pane tabs.
+    window_label: String,
+    writer: Mutex<Box<dyn Write + Send>>,
+    killer: Mutex<Box<dyn ChildKiller + Send + Sync>>,
+    panes: Mutex<HashMap<u32, PaneLink>>,
+}
+
+impl TmuxConnection {
+    fn send(&self, command: &str) -> Result<(), AppError> {
+        let mut writer = self
+            .writer
+            .lock()
+            .map_err(|_| "failed to lock tmux writer".to_string())?;
+        writer
+            .write_all(format!("{command}\n").as_bytes())
+            .and_then(|_| writer.flush())
+            .map_err(|error| AppError::pty(format!("failed to write to tmux: {error}")))
+    }
+
+    fn tab_id(&self, pane_id: u32) -> String {
+        format!("{}-pane-{pane_id}", self.id)
+    }
+
+    /// Sends a connection-wide event to the window that attached.
+    fn emit<S: Serialize + Clone>(&self, app: &tauri::AppHandle, event: &str, payload: S) {
+        let _ = app.emit_to(EventTarget::webview_window(&self.window_label), event, payload);
+    }
+}
+
+impl std::fmt::Debug for TmuxConnection {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        formatter.debug_struct("TmuxConnection").field("id", &self.id).finish()
+    }
+}
+
+#[derive(Default)]
+pub struct TmuxState {
+    connections: Mutex<HashMap<String, Arc<TmuxConnection>>>,
+    next_id: AtomicU64,
+}
+
+fn connection(app: &tauri::AppHandle, connection_id: &str) -> Result<Arc<TmuxConnection>, AppError> {
+    app.state::<TmuxState>()
+        .connections
+        .lock()
+        .map_err(|_| "failed to lock tmux connections".to_string())?
+        .get(connection_id)
+        .cloned()
+        .ok_or_else(|| AppError::invalid(format!("tmux connection not found: {connection_id}")))
+}
+
+/// Feeds `%output` data to a pane session. Ends the stream once the pane is gone.
+struct PaneReader {
+    output: mpsc::Receiver<Vec<u8>>,
+    pending: Vec<u8>,
+    offset: usize,
+}
+
+impl Read for PaneReader {
+    fn read(&mut self, buffer: &mut [u8]) -> std::io::Result<usize> {
+        if self.offset >= self.pending.len() {
+            match self.output.recv() {
+                Ok(chunk) => {
+                    self.pending = chunk;
+                    self.offset = 0;
+                }
+                Err(_) => return Ok(0),
+            }
+        }
+
+        let count = buffer.len().min(self.pending.len() - self.offset);
+        buffer[..count].copy_from_slice(&self.pending[self.offset..self.offset + count]);
+        self.offset += count;
+        Ok(count)
+    }
+}
+
+/// Types into a pane with `send-keys -H`, which takes raw bytes as hex.
+struct PaneWriter {
+    connection: Arc<TmuxConnection>,
+    pane_id: u32,
+}
+
+impl Write for PaneWriter {
+    fn write(&mut self, buffer: &[u8]) -> std::io::Result<usize> {
+        for chunk in buffer.chunks(SEND_KEYS_CHUNK) {
+            let keys = chunk
+                .iter()
+                .map(|byte| format!("{byte:02x}"))
+                .collect::<Vec<_>>()
+                .join(" ");
+            self.connection
+                .send(&format!("send-keys -H -t %{} {keys}", self.pane_id))
+                .map_err(std::io::Error::other)?;
+        }
+        Ok(buffer.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Closing a pane tab kills the pane in tmux; the session ends when tmux reports it gone.
+#[derive(Debug, Clone)]
+struct PaneKiller {
+    connection: Arc<TmuxConnection>,
+    pane_id: u32,
+}
+
+impl ChildKiller for PaneKiller {
+    fn kill(&mut self) -> std::io::Result<()> {
+        self.connection
+            .send(&format!("kill-pane -t %{}", self.pane_id))
+            .map_err(std::io::Error::other)
+    }
+
+    fn clone_killer(&self) -> Box<dyn ChildKiller + Send + Sync> {
+        Box::new(self.clone())
+    }
+}
+
+/// Undoes control mode escaping, where bytes below 32 and backslashes are written as `\ooo`.
+fn unescape(data: &[u8]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(data.len());
+    let mut index = 0;
+    while index < data.len() {
+        let octal = data
+            .get(index + 1..index + 4)
+            .filter(|digits| digits.iter().all(|digit| (b'0'..=b'7').contains(digit)));
+        match (data[index], octal) {
+            (b'\\', Some(digits)) => {
+                bytes.push(digits.iter().fold(0_u8, |value, digit| (value << 3) | (digit - b'0')));
+                index += 4;
+            }
+            (byte, _) => {
+                bytes.push(byte);
+                index += 1;
+            }
+        }
+    }
+    bytes
+}
+
+/// Parses the number out of tmux ids such as `@3` or `%12`.
+fn tmux_id(value: &str, prefix: char) -> Option<u32> {
+    value.strip_prefix(prefix)?.parse().ok()
+}
+
+struct ListedPane {
+    window_id: u32,
+    pane_id: u32,
+    cols: u16,
+    rows: u16,
+    window_name: String,
+}
+
+fn parse_listed_pane(line: &str) -> Option<ListedPane> {
+    let mut fields = line.strip_prefix("nlk-pane ")?.splitn(5, ' ');
+    Some(ListedPane {
+        window_id: tmux_id(fields.next()?, '@')?,
+        pane_id: tmux_id(fields.next()?, '%')?,
+        cols: fields.next()?.parse().ok()?,
+        rows: fields.next()?.parse().ok()?,
+        window_name: fields.next().unwrap_or_default().to_string(),
+    })
+}
+
+/// Registers a tab for a pane that doesn't have one yet.
+fn open_pane(app: &tauri::AppHandle, connection: &Arc<TmuxConnection>, listed: &ListedPane) -> Result<(), String> {
+    let (output, received) = mpsc::channel();
+    {
+        let mut panes = connection
+            .panes
+            .lock()
+            .map_err(|_| "failed to lock tmux panes".to_string())?;
+        if panes.contains_key(&listed.pane_id) {
+            return Ok(());
+        }
+        panes.insert(
+            listed.pane_id,
+            PaneLink {
+                window_id: listed.window_id,
+                output,
+            },
+        );
+    }
+
+    let tab_id = connection.tab_id(listed.pane_id);
+    // No exit status: the session ends when the pane link is dropped.
+    let (_, exited) = mpsc::channel();
+    let io = SessionIo {
+        reader: Box::new(PaneReader {
+            output: received,
+            pending: Vec::new(),
+            offset: 0,
+        }),
+        writer: Box::new(PaneWriter {
+            connection: Arc::clone(connection),
+            pane_id: listed.pane_id,
+        }),
+        master: None,
+        killer: Box::new(PaneKiller {
+            connection: Arc::clone(connection),
+            pane_id: listed.pane_id,
+        }),
+        pid: None,
+        exited,
+    };
+
+    let state = app.state::<TerminalState>();
+    let mut sessions = state
+        .sessions
+        .lock()
+        .map_err(|_| "failed to lock terminal sessions".to_string())?;
+    windows::claim(app, &tab_id, &connection.window_label);
+    let mut session = terminal::start_session(
+        app,
+        &tab_id,
+        "tmux".to_string(),
+        io,
+        SpawnOptions {
+            kind: SessionKind::Tmux(TmuxPane {
+                connection_id: connection.id.clone(),
+                window_id: listed.window_id,
+                pane_id: listed.pane_id,
+            }),
+            encoding: OutputEncoding::default(),
+            size: PtySize {
+                rows: listed.rows,
+                cols: listed.cols,
+                ..PtySize::default()
+            },
+            ..SpawnOptions::default()
+        },
+    );
+    session.close_on_exit = true;
+    sessions.insert(tab_id.clone(), session);
+    drop(sessions);
+
+    windows::emit(
+        app,
+        &tab_id,
+        "tmux-pane-opened",
+        TmuxPaneOpenedEvent {
+            connection_id: connection.id.clone(),
+            tab_id: tab_id.clone(),
+            window_id: listed.window_id,
+            pane_id: listed.pane_id,
+            window_name: listed.window_name.clone(),
+            cols: listed.cols,
+            rows: listed.rows,
+        },
+    );
+    Ok(())
+}
+
+/// Ends the tabs of panes that no longer exist. Dropping the link closes the pane's stream.
+fn close_panes(connection: &TmuxConnection, gone: impl Fn(u32, &PaneLink) -> bool) {
+    if let Ok(mut panes) = connection.panes.lock() {
+        panes.retain(|pane_id, link| !gone(*pane_id, link));
+    }
+}
+
+/// Applies a `list-panes` reply: opens tabs for new panes and, for every window in the
+/// listing, closes tabs whose panes are missing from it.
+fn apply_listing(app: &tauri::AppHandle, connection: &Arc<TmuxConnection>, lines: &[String]) {
+    let listed = lines
+        .iter()
+        .filter_map(|line| parse_listed_pane(line))
+        .collect::<Vec<_>>();
+    if listed.is_empty() {
+        return;
+    }
+
+    let windows = listed.iter().map(|pane| pane.window_id).collect::<HashSet<_>>();
+    let live = listed.iter().map(|pane| pane.pane_id).collect::<HashSet<_>>();
+    close_panes(connection, |pane_id, link| {
+        windows.contains(&link.window_id) && !live.contains(&pane_id)
+    });
+
+    for pane in &listed {
+        let _ = open_pane(app, connection, pane);
+    }
+}
+
+fn emit_window_event(app: &tauri::AppHandle, event: &str, connection: &TmuxConnection, window_id: u32, value: &str) {
+    connection.emit(
+        app,
+        event,
+        TmuxWindowEvent {
+            connection_id: connection.id.clone(),
+            window_id,
+            value: value.to_string(),
+        },
+    );
+}
+
+fn list_panes(connection: &TmuxConnection, target: Option<u32>) {
+    let _ = match target {
+        Some(window_id) => connection.send(&format!("list-panes -t @{window_id} -F '{PANE_FORMAT}'")),
+        None => connection.send(&format!("list-panes -s -F '{PANE_FORMAT}'")),
+    };
+}
+
+/// Reads control mode lines until the client exits, then ends every pane tab.
+fn run_control_reader(app: tauri::AppHandle, connection: Arc<TmuxConnection>, reader: Box<dyn Read + Send>) {
+    let mut reader = BufReader::new(reader);
+    let mut line = Vec::new();
+    let mut reply: Option<Vec<String>> = None;
+    let mut reason = None;
+
+    loop {
+        line.clear();
+        match reader.read_until(b'\n', &mut line) {
+            Ok(0) | Err(_) => break,
+            Ok(_) => {}
+        }
+        while matches!(line.last(), Some(b'\n' | b'\r')) {
+            line.pop();
+        }
+        // `-CC` wraps the whole exchange in a DCS sequence.
+        let mut text = line.as_slice();
+        if let Some(rest) = text.strip_prefix(b"\x1bP1000p") {
+            text = rest;
+        }
+
+        if text.starts_with(b"%end") || text.starts_with(b"%error") {
+            if let Some(lines) = reply.take() {
+                apply_listing(&app, &connection, &lines);
+            }
+            continue;
+        }
+        if let Some(lines) = reply.as_mut() {
+            lines.push(String::from_utf8_lossy(text).to_string());
+            continue;
+        }
+
+        if let Some(rest) = text.strip_prefix(b"%output ") {
+            let split = rest.iter().position(|byte| *byte == b' ').unwrap_or(rest.len());
+            let pane_id = tmux_id(&String::from_utf8_lossy(&rest[..split]), '%');
+            let data = unescape(rest.get(split + 1..).unwrap_or_default());
+            if let (Some(pane_id), Ok(panes)) = (pane_id, connection.panes.lock()) {
+                if let Some(link) = panes.get(&pane_id) {
+                    let _ = link.output.send(data);
+                }
+            }
+            continue;
+        }
+
+        let text = String::from_utf8_lossy(text).to_string();
+        let mut words = text.splitn(3, ' ');
+        let notification = words.next().unwrap_or_default();
+        let window_id = words.next().and_then(|id| tmux_id(id, '@'));
+        let argument = words.next().unwrap_or_default();
+
+        match (notification, window_id) {
+            ("%begin", _) => reply = Some(Vec::new()),
+            ("%window-add", Some(window_id)) => list_panes(&connection, Some(window_id)),
+            ("%window-close" | "%unlinked-window-close", Some(window_id)) => {
+                close_panes(&connection, |_, link| link.window_id == window_id);
+            }
+            ("%window-renamed", Some(window_id)) => {
+                emit_window_event(&app, "tmux-window-renamed", &connection, window_id, argument);
+            }
+            ("%layout-change", Some(window_id)) => {
+                let layout = argument.split(' ').next().unwrap_or_default();
+                emit_window_event(&app, "tmux-layout-changed", &connection, window_id, layout);
+                list_panes(&connection, Some(window_id));
+            }
+            ("%session-changed", _) => list_panes(&connection, None),
+            ("%exit", _) => {
+                reason = Some(text.trim_start_matches("%exit").trim().to_string()).filter(|reason| !reason.is_empty());
+                break;
+            }
+            _ => {}
+        }
+    }
+
+    close_panes(&connection, |_, _| true);
+    if let Ok(mut connections) = app.state::<TmuxState>().connections.lock() {
+        connections.remove(&connection.id);
+    }
+    connection.emit(
+        &app,
+        "tmux-detached",
+        TmuxDetachedEvent {
+            connection_id: connection.id.clone(),
+            reason,
+        },
+    );
+}
+
+/// Keeps a pane's window at the tab's size. Needs tmux 3.2 for per-window client sizes.
+pub fn resize_pane(app: &tauri::AppHandle, pane: &TmuxPane, cols: u16, rows: u16) -> Result<(), AppError> {
+    connection(app, &pane.connection_id)?.send(&format!("refresh-client -C @{}:{cols}x{rows}", pane.window_id))
+}
+
+fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+/// Starts `tmux -CC` locally, or on `ssh` when given, attaching to `session` or creating it.
+/// Each pane becomes a tab with id `<connectionId>-pane-<n>`, announced by `tmux-pane-opened`.
+#[tauri::command]
+pub fn attach_tmux(
+    session: Option<String>,
+    ssh: Option<SshTarget>,
+    app: tauri::AppHandle,
+    webview_window: tauri::WebviewWindow,
+    state: tauri::State<TmuxState>,
+) -> Result<TmuxAttachResponse, AppError> {
+    let session = session
+        .map(|session| session.trim().to_string())
+        .filter(|session| !session.is_empty());
+    let mut tmux_args = vec![
+        "tmux".to_string(),
+        "-CC".to_string(),
+        "new-session".to_string(),
+        "-A".to_string(),
+    ];
+    if let Some(session) = &session {
+        tmux_args.push("-s".to_string());
+        tmux_args.push(session.clone());
+    }
+
+    let command = match &ssh {
+        Some(target) => {
+            let mut builder = ssh::ssh_command(&app, target, &[])?;
+            // ssh joins remote arguments with spaces, so they are quoted for the remote shell.
+            builder.args(tmux_args.iter().map(|arg| quote(arg)));
+            builder
+        }
+        None => {
+            let mut builder = CommandBuilder::new(&tmux_args[0]);
+            builder.args(&tmux_args[1..]);
+            terminal::apply_terminal_env(&mut builder);
+            builder
+        }
+    };
+
+    let pair = native_pty_system()
+        .openpty(PtySize::default())
+        .map_err(|error| AppError::pty(format!("failed to open pty: {error}")))?;
+    let mut child = pair
+        .slave
+        .spawn_command(command)
+        .map_err(|error| AppError::pty(format!("failed to start tmux: {error}")))?;
+    drop(pair.slave);
+
+    let reader = pair
+        .master
+        .try_clone_reader()
+        .map_err(|error| AppError::pty(format!("failed to read tmux output: {error}")))?;
+    let writer = pair
+        .master
+        .take_writer()
+        .map_err(|error| AppError::pty(format!("failed to write to tmux: {error}")))?;
+
+    let id = format!("tmux-{}", state.next_id.fetch_add(1, Ordering::Relaxed) + 1);
+    let connection = Arc::new(TmuxConnection {
+        id: id.clone(),
+        window_label: webview_window.label().to_string(),
+        writer: Mutex::new(writer),
+        killer: Mutex::new(child.clone_killer()),
+        panes: Mutex::new(HashMap::new()),
+    });
+    state
+        .connections
+        .lock()
+        .map_err(|_| "failed to lock tmux connections".to_string())?
+        .insert(id.clone(), Arc::clone(&connection));
+
+    std::thread::spawn(move || {
+        // Keeps the PTY open for as long as the client runs.
+        let _master = pair.master;
+        let _ = child.wait();
+    });
+
+    list_panes(&connection, None);
+    std::thread::spawn(move || run_control_reader(app, connection, reader));
+
+    Ok(TmuxAttachResponse { connection_id: id })
+}
+
+#[tauri::command]
+pub fn detach_tmux(connection_id: String, app: tauri::AppHandle) -> Result<(), AppError> {
+    let connection = connection(&app, &connection_id)?;
+    if connection.send("detach-client").is_err() {
+        if let Ok(mut killer) = connection.killer.lock() {
+            let _ = killer.kill();
+        }
+    }
+    Ok(())
+}
+
+/// Opens a tmux window; its tab appears once tmux reports it.
+#[tauri::command]
+pub fn tmux_new_window(connection_id: String, app: tauri::AppHandle) -> Result<(), AppError> {
+    connection(&app, &connection_id)?.send("new-window")
+}
+
+/// Splits the pane behind `tab_id`; the new pane's tab appears once tmux reports the layout change.
+#[tauri::command]
+pub fn tmux_split_pane(
+    tab_id: String,
+    direction: SplitDirection,
+    app: tauri::AppHandle,
+    state: tauri::State<TerminalState>,
+) -> Result<(), AppError> {
+    let pane = {
+        let sessions = state
+            .sessions
+            .lock()
+            .map_err(|_| "failed to lock terminal sessions".to_string())?;
+        let session = sessions
+            .get(&tab_id)
+            .ok_or_else(|| AppError::session_not_found(&tab_id))?;
+        match &session.kind {
+            SessionKind::Tmux(pane) => pane.clone(),
+            _ => return Err(AppError::invalid(format!("not a tmux pane: {tab_id}"))),
+        }
+    };
+
+    let flag = match direction {
+        SplitDirection::Horizontal => "-h",
+        SplitDirection::Vertical => "-v",
+    };
+    connection(&app, &pane.connection_id)?.send(&format!("split-window {flag} -t %{}", pane.pane_id))
+}
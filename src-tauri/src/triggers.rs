@@ -0,0 +1,295 @@
+use crate::{error::AppError, scrollback::strip_escapes, stream::Utf8Decoder, terminal::OutputObserver, windows};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::{
+    path::PathBuf,
+    process::{Command, Stdio},
+    sync::{Arc, Mutex},
+};
+use tauri::Manager;
+use tauri_plugin_notification::NotificationExt;
+
+/// Longest line kept while waiting for its newline; longer output is matched in pieces.
+const MAX_LINE_LEN: usize = 4096;
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum TriggerAction {
+    /// Only emit the `terminal-trigger` event.
+    Event,
+    Highlight {
+        #[serde(default)]
+        color: Option<String>,
+    },
+    Notify {
+        #[serde(default)]
+        title: Option<String>,
+    },
+    /// Runs `command` through the platform shell with the match in `NLK_TRIGGER_MATCH`.
+    Command { command: String },
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Trigger {
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    pub pattern: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Limits the trigger to sessions started from this profile.
+    #[serde(default)]
+    pub profile_id: Option<String>,
+    /// Limits the trigger to one tab.
+    #[serde(default)]
+    pub tab_id: Option<String>,
+    pub action: TriggerAction,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+struct CompiledTrigger {
+    trigger: Trigger,
+    pattern: Regex,
+}
+
+/// Caches the compiled trigger list; saving or deleting a trigger drops the cache.
+#[derive(Default)]
+pub struct TriggerState {
+    compiled: Mutex<Option<Arc<Vec<CompiledTrigger>>>>,
+}
+
+impl TriggerState {
+    fn invalidate(&self) {
+        if let Ok(mut compiled) = self.compiled.lock() {
+            *compiled = None;
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TerminalTriggerEvent {
+    tab_id: String,
+    trigger_id: String,
+    name: String,
+    action: TriggerAction,
+    text: String,
+    line: String,
+    captures: Vec<Option<String>>,
+}
+
+fn triggers_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_config_dir()
+        .map_err(|error| format!("failed to resolve config dir: {error}"))?;
+    Ok(dir.join("triggers.json"))
+}
+
+fn load_triggers(app: &tauri::AppHandle) -> Result<Vec<Trigger>, String> {
+    let path = triggers_path(app)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let raw = std::fs::read_to_string(&path).map_err(|error| format!("failed to read triggers: {error}"))?;
+    serde_json::from_str(&raw).map_err(|error| format!("failed to parse triggers: {error}"))
+}
+
+fn store_triggers(app: &tauri::AppHandle, triggers: &[Trigger]) -> Result<(), String> {
+    let path = triggers_path(app)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|error| format!("failed to create config dir: {error}"))?;
+    }
+
+    let raw = serde_json::to_string_pretty(triggers).map_err(|error| format!("failed to encode triggers: {error}"))?;
+    std::fs::write(&path, raw).map_err(|error| format!("failed to write triggers: {error}"))?;
+    app.state::<TriggerState>().invalidate();
+    Ok(())
+}
+
+fn compiled_triggers(app: &tauri::AppHandle) -> Arc<Vec<CompiledTrigger>> {
+    let state = app.state::<TriggerState>();
+    let Ok(mut compiled) = state.compiled.lock() else {
+        return Arc::default();
+    };
+
+    let triggers = compiled.get_or_insert_with(|| {
+        let triggers = load_triggers(app)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|trigger| trigger.enabled)
+            .filter_map(|trigger| {
+                let pattern = Regex::new(&trigger.pattern).ok()?;
+                Some(CompiledTrigger { trigger, pattern })
+            })
+            .collect();
+        Arc::new(triggers)
+    });
+    Arc::clone(triggers)
+}
+
+fn generate_trigger_id() -> String {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_nanos())
+        .unwrap_or(0);
+    format!("trigger-{nanos:x}")
+}
+
+fn run_action(app: &tauri::AppHandle, tab_id: &str, trigger: &Trigger, text: &str, line: &str) {
+    match &trigger.action {
+        TriggerAction::Notify { title } => {
+            let title = title.clone().unwrap_or_else(|| trigger.name.clone());
+            let _ = app.notification().builder().title(title).body(line).show();
+        }
+        TriggerAction::Command { command } => {
+            #[cfg(target_os = "windows")]
+            let mut process = {
+                let mut process = Command::new("cmd");
+                process.args(["/C", command]);
+                process
+            };
+            #[cfg(not(target_os = "windows"))]
+            let mut process = {
+                let mut process = Command::new("sh");
+                process.args(["-c", command]);
+                process
+            };
+            let spawned = process
+                .env("NLK_TRIGGER_MATCH", text)
+                .env("NLK_TRIGGER_LINE", line)
+                .env("NLK_TAB_ID", tab_id)
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn();
+            // Reap the child without blocking the reader thread.
+            if let Ok(mut child) = spawned {
+                std::thread::spawn(move || child.wait());
+            }
+        }
+        TriggerAction::Event | TriggerAction::Highlight { .. } => {}
+    }
+}
+
+/// Matches completed output lines against the configured triggers.
+pub struct TriggerScanner {
+    profile_id: Option<String>,
+    decoder: Utf8Decoder,
+    line: String,
+}
+
+impl TriggerScanner {
+    pub fn new(profile_id: Option<String>) -> Self {
+        Self {
+            profile_id,
+            decoder: Utf8Decoder::default(),
+            line: String::new(),
+        }
+    }
+
+    fn scan_line(&mut self, app: &tauri::AppHandle, tab_id: &str) {
+        let line = strip_escapes(&std::mem::take(&mut self.line));
+        if line.trim().is_empty() {
+            return;
+        }
+
+        for compiled in compiled_triggers(app).iter() {
+            let trigger = &compiled.trigger;
+            if trigger.tab_id.as_deref().is_some_and(|id| id != tab_id) {
+                continue;
+            }
+            if trigger.profile_id.is_some() && trigger.profile_id != self.profile_id {
+                continue;
+            }
+            let Some(captures) = compiled.pattern.captures(&line) else {
+                continue;
+            };
+
+            let text = captures.get(0).map(|found| found.as_str()).unwrap_or_default().to_string();
+            windows::emit(
+                app,
+                tab_id,
+                "terminal-trigger",
+                TerminalTriggerEvent {
+                    tab_id: tab_id.to_string(),
+                    trigger_id: trigger.id.clone(),
+                    name: trigger.name.clone(),
+                    action: trigger.action.clone(),
+                    text: text.clone(),
+                    line: line.clone(),
+                    captures: captures
+                        .iter()
+                        .skip(1)
+                        .map(|group| group.map(|group| group.as_str().to_string()))
+                        .collect(),
+                },
+            );
+            run_action(app, tab_id, trigger, &text, &line);
+        }
+    }
+}
+
+impl OutputObserver for TriggerScanner {
+    fn observe(&mut self, app: &tauri::AppHandle, tab_id: &str, bytes: &[u8]) {
+        let text = self.decoder.decode(bytes);
+        for c in text.chars() {
+            if c == '\n' || self.line.len() >= MAX_LINE_LEN {
+                self.scan_line(app, tab_id);
+            }
+            if c != '\n' {
+                self.line.push(c);
+            }
+        }
+    }
+
+    fn finish(&mut self, app: &tauri::AppHandle, tab_id: &str) {
+        self.scan_line(app, tab_id);
+    }
+}
+
+#[tauri::command]
+pub fn list_triggers(app: tauri::AppHandle) -> Result<Vec<Trigger>, AppError> {
+    Ok(load_triggers(&app)?)
+}
+
+#[tauri::command]
+pub fn save_trigger(trigger: Trigger, app: tauri::AppHandle) -> Result<Trigger, AppError> {
+    let mut trigger = trigger;
+    trigger.name = trigger.name.trim().to_string();
+
+    if trigger.name.is_empty() {
+        return Err(AppError::invalid("trigger name is empty"));
+    }
+    Regex::new(&trigger.pattern).map_err(|error| AppError::invalid(format!("invalid trigger pattern: {error}")))?;
+    if trigger.id.trim().is_empty() {
+        trigger.id = generate_trigger_id();
+    }
+
+    let mut triggers = load_triggers(&app)?;
+    match triggers.iter_mut().find(|existing| existing.id == trigger.id) {
+        Some(existing) => *existing = trigger.clone(),
+        None => triggers.push(trigger.clone()),
+    }
+    store_triggers(&app, &triggers)?;
+
+    Ok(trigger)
+}
+
+#[tauri::command]
+pub fn delete_trigger(id: String, app: tauri::AppHandle) -> Result<(), AppError> {
+    let mut triggers = load_triggers(&app)?;
+    let before = triggers.len();
+    triggers.retain(|trigger| trigger.id != id);
+
+    if triggers.len() == before {
+        return Err(AppError::invalid(format!("trigger not found: {id}")));
+    }
+
+    Ok(store_triggers(&app, &triggers)?)
+}
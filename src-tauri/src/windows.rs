@@ -0,0 +1,176 @@
+use crate::{
+    dropdown,
+    error::AppError,
+    layout,
+    terminal::{self, TerminalState},
+};
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+};
+use tauri::{Emitter, EventTarget, Manager, WebviewUrl, WebviewWindowBuilder};
+
+/// Which window shows each tab. Tabs without an owner, such as those opened before a window
+/// claimed them, get their events broadcast to every window.
+#[derive(Default)]
+pub struct WindowState {
+    owners: Mutex<HashMap<String, String>>,
+    next_window: AtomicUsize,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SessionMovedEvent {
+    tab_id: String,
+    from: Option<String>,
+    to: String,
+}
+
+pub fn owner(app: &tauri::AppHandle, tab_id: &str) -> Option<String> {
+    app.state::<WindowState>()
+        .owners
+        .lock()
+        .ok()
+        .and_then(|owners| owners.get(tab_id).cloned())
+}
+
+/// Records `label` as the window showing `tab_id`, returning the previous owner.
+pub fn claim(app: &tauri::AppHandle, tab_id: &str, label: &str) -> Option<String> {
+    app.state::<WindowState>()
+        .owners
+        .lock()
+        .ok()
+        .and_then(|mut owners| owners.insert(tab_id.to_string(), label.to_string()))
+}
+
+pub fn forget_tab(app: &tauri::AppHandle, tab_id: &str) {
+    if let Ok(mut owners) = app.state::<WindowState>().owners.lock() {
+        owners.remove(tab_id);
+    }
+}
+
+/// Sends a per-tab event only to the window that owns the tab.
+pub fn emit<S: Serialize + Clone>(app: &tauri::AppHandle, tab_id: &str, event: &str, payload: S) {
+    let _ = match owner(app, tab_id) {
+        Some(label) => app.emit_to(EventTarget::webview_window(label), event, payload),
+        None => app.emit(event, payload),
+    };
+}
+
+/// Closes the sessions of a window that was closed while others stay open. When it was the last
+/// terminal window, its sessions are left for the exit or detach handling and only disowned.
+pub fn window_destroyed(app: &tauri::AppHandle, label: &str) {
+    let tab_ids = match app.state::<WindowState>().owners.lock() {
+        Ok(mut owners) => {
+            let tab_ids = owners
+                .iter()
+                .filter(|(_, owner)| owner.as_str() == label)
+                .map(|(tab_id, _)| tab_id.clone())
+                .collect::<Vec<_>>();
+            for tab_id in &tab_ids {
+                owners.remove(tab_id);
+            }
+            tab_ids
+        }
+        Err(_) => return,
+    };
+
+    let remaining = app
+        .webview_windows()
+        .into_keys()
+        .filter(|other| other != label && other != dropdown::LABEL)
+        .count();
+    if remaining == 0 {
+        // A hidden dropdown would otherwise keep the app alive with no way back in.
+        if let Some(window) = app.get_webview_window(dropdown::LABEL) {
+            let _ = window.destroy();
+        }
+        return;
+    }
+
+    let state = app.state::<TerminalState>();
+    let Ok(mut sessions) = state.sessions.lock() else {
+        return;
+    };
+    for tab_id in &tab_ids {
+        terminal::close_session(app, &mut sessions, tab_id);
+    }
+    let _ = crate::session_store::persist(app, &sessions);
+}
+
+/// Hands a tab to another window, opening a new one when `window_label` is omitted. The session
+/// keeps running; the receiving window picks it up from `window_sessions` or the
+/// `session-moved` event. Returns the label of the receiving window.
+// Async so a new window is not created on the main thread, which deadlocks on Windows.
+#[tauri::command]
+pub async fn move_session_to_window(
+    tab_id: String,
+    window_label: Option<String>,
+    app: tauri::AppHandle,
+    state: tauri::State<'_, TerminalState>,
+    windows: tauri::State<'_, WindowState>,
+) -> Result<String, AppError> {
+    if !state
+        .sessions
+        .lock()
+        .map_err(|_| "failed to lock terminal sessions".to_string())?
+        .contains_key(&tab_id)
+    {
+        return Err(AppError::session_not_found(&tab_id));
+    }
+
+    let label = match window_label {
+        Some(label) => {
+            let window = app
+                .get_webview_window(&label)
+                .ok_or_else(|| AppError::invalid(format!("window not found: {label}")))?;
+            let _ = window.set_focus();
+            label
+        }
+        None => {
+            let label = format!("window-{}", windows.next_window.fetch_add(1, Ordering::SeqCst) + 1);
+            WebviewWindowBuilder::new(&app, &label, WebviewUrl::default())
+                .title("Terminal")
+                .decorations(false)
+                .transparent(true)
+                .inner_size(1200.0, 760.0)
+                .min_inner_size(920.0, 620.0)
+                .build()
+                .map_err(|error| format!("failed to open window: {error}"))?;
+            label
+        }
+    };
+
+    layout::forget_pane(&app, &tab_id);
+    let from = claim(&app, &tab_id, &label);
+    let _ = app.emit(
+        "session-moved",
+        SessionMovedEvent {
+            tab_id,
+            from,
+            to: label.clone(),
+        },
+    );
+    Ok(label)
+}
+
+/// Tabs owned by the calling window, for a window that was just opened to restore its tabs.
+#[tauri::command]
+pub fn window_sessions(
+    webview_window: tauri::WebviewWindow,
+    windows: tauri::State<WindowState>,
+) -> Result<Vec<String>, AppError> {
+    let owners = windows
+        .owners
+        .lock()
+        .map_err(|_| "failed to lock window owners".to_string())?;
+    Ok(owners
+        .iter()
+        .filter(|(_, owner)| owner.as_str() == webview_window.label())
+        .map(|(tab_id, _)| tab_id.clone())
+        .collect())
+}
@@ -0,0 +1,218 @@
+use crate::{
+    error::AppError,
+    git::{self, watcher, GitStatusResponse},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+use tauri::{Emitter, Manager};
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WorkspaceRecord {
+    path: String,
+    name: String,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceRepo {
+    path: String,
+    name: String,
+    status: Option<GitStatusResponse>,
+    error: Option<String>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct WorkspaceStatusEvent {
+    repo_path: String,
+    status: Option<GitStatusResponse>,
+    error: Option<String>,
+}
+
+/// Open repositories with their last known status. `None` until loaded from disk.
+#[derive(Default)]
+pub struct WorkspaceState {
+    repos: Mutex<Option<Vec<WorkspaceRepo>>>,
+}
+
+fn workspace_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_config_dir()
+        .map_err(|error| format!("failed to resolve config dir: {error}"))?;
+    Ok(dir.join("workspace.json"))
+}
+
+fn load_records(app: &tauri::AppHandle) -> Result<Vec<WorkspaceRecord>, String> {
+    let path = workspace_path(app)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let raw = std::fs::read_to_string(&path).map_err(|error| format!("failed to read workspace: {error}"))?;
+    serde_json::from_str(&raw).map_err(|error| format!("failed to parse workspace: {error}"))
+}
+
+fn store_records(app: &tauri::AppHandle, repos: &[WorkspaceRepo]) -> Result<(), String> {
+    let path = workspace_path(app)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|error| format!("failed to create config dir: {error}"))?;
+    }
+
+    let records = repos
+        .iter()
+        .map(|repo| WorkspaceRecord {
+            path: repo.path.clone(),
+            name: repo.name.clone(),
+        })
+        .collect::<Vec<WorkspaceRecord>>();
+    let raw =
+        serde_json::to_string_pretty(&records).map_err(|error| format!("failed to encode workspace: {error}"))?;
+    std::fs::write(&path, raw).map_err(|error| format!("failed to write workspace: {error}"))?;
+    Ok(())
+}
+
+fn repo_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string_lossy().to_string())
+}
+
+fn load_status(app: &tauri::AppHandle, path: &str) -> (Option<GitStatusResponse>, Option<String>) {
+    match git::git_status(Some(path.to_string()), None, None, None, app.clone()) {
+        Ok(status) => (Some(status), None),
+        Err(error) => (None, Some(error.into())),
+    }
+}
+
+/// Runs `update` on the repository list, loading it from disk and starting the watchers on
+/// first use.
+fn with_repos<T>(
+    app: &tauri::AppHandle,
+    update: impl FnOnce(&mut Vec<WorkspaceRepo>) -> Result<T, AppError>,
+) -> Result<T, AppError> {
+    let state = app.state::<WorkspaceState>();
+    let mut repos = state
+        .repos
+        .lock()
+        .map_err(|_| "failed to lock workspace".to_string())?;
+
+    if repos.is_none() {
+        let loaded = load_records(app)?
+            .into_iter()
+            .map(|record| {
+                let _ = watcher::watch(app, Path::new(&record.path));
+                let (status, error) = load_status(app, &record.path);
+                WorkspaceRepo {
+                    path: record.path,
+                    name: record.name,
+                    status,
+                    error,
+                }
+            })
+            .collect();
+        *repos = Some(loaded);
+    }
+
+    match repos.as_mut() {
+        Some(repos) => update(repos),
+        None => Err(AppError::from("workspace unavailable")),
+    }
+}
+
+/// Refreshes the cached status of a workspace repository after its watcher fired and emits
+/// `workspace-status` for it. Repositories outside the workspace are ignored.
+pub fn invalidate(app: &tauri::AppHandle, repo: &Path) {
+    let repo_path = repo.to_string_lossy().to_string();
+    let Some(state) = app.try_state::<WorkspaceState>() else {
+        return;
+    };
+    let Ok(mut repos) = state.repos.lock() else {
+        return;
+    };
+    let Some(entry) = repos
+        .as_mut()
+        .and_then(|repos| repos.iter_mut().find(|entry| entry.path == repo_path))
+    else {
+        return;
+    };
+
+    let (status, error) = load_status(app, &entry.path);
+    entry.status = status.clone();
+    entry.error = error.clone();
+    let _ = app.emit(
+        "workspace-status",
+        WorkspaceStatusEvent {
+            repo_path,
+            status,
+            error,
+        },
+    );
+}
+
+#[tauri::command]
+pub fn workspace_list(app: tauri::AppHandle) -> Result<Vec<WorkspaceRepo>, AppError> {
+    with_repos(&app, |repos| Ok(repos.clone()))
+}
+
+#[tauri::command]
+pub fn workspace_add(path: String, name: Option<String>, app: tauri::AppHandle) -> Result<WorkspaceRepo, AppError> {
+    let root = watcher::watch(&app, Path::new(&path))?;
+    let root_path = root.to_string_lossy().to_string();
+
+    with_repos(&app, |repos| {
+        if let Some(existing) = repos.iter().find(|repo| repo.path == root_path) {
+            return Ok(existing.clone());
+        }
+
+        let (status, error) = load_status(&app, &root_path);
+        let repo = WorkspaceRepo {
+            name: name
+                .map(|name| name.trim().to_string())
+                .filter(|name| !name.is_empty())
+                .unwrap_or_else(|| repo_name(&root)),
+            path: root_path.clone(),
+            status,
+            error,
+        };
+        repos.push(repo.clone());
+        store_records(&app, repos)?;
+        Ok(repo)
+    })
+}
+
+#[tauri::command]
+pub fn workspace_remove(path: String, app: tauri::AppHandle) -> Result<(), AppError> {
+    with_repos(&app, |repos| {
+        let before = repos.len();
+        repos.retain(|repo| repo.path != path);
+        if repos.len() == before {
+            return Err(AppError::invalid(format!("repository not in workspace: {path}")));
+        }
+        Ok(store_records(&app, repos)?)
+    })?;
+
+    watcher::unwatch(&app, Path::new(&path));
+    Ok(())
+}
+
+/// Reloads the status of one repository, bypassing the cache.
+#[tauri::command]
+pub fn workspace_refresh(path: String, app: tauri::AppHandle) -> Result<WorkspaceRepo, AppError> {
+    with_repos(&app, |repos| {
+        let repo = repos
+            .iter_mut()
+            .find(|repo| repo.path == path)
+            .ok_or_else(|| AppError::invalid(format!("repository not in workspace: {path}")))?;
+
+        git::status_cache::invalidate(Path::new(&repo.path));
+        let (status, error) = load_status(&app, &repo.path);
+        repo.status = status;
+        repo.error = error;
+        Ok(repo.clone())
+    })
+}
@@ -0,0 +1,94 @@
+use crate::{error::AppError, windows};
+use serde::Serialize;
+use std::{
+    io::Write,
+    sync::mpsc::{self, SyncSender, TrySendError},
+    time::Duration,
+};
+
+/// Writes queued for a session before `write_terminal` starts rejecting input. Keeps a stalled
+/// PTY, e.g. one whose program stopped reading stdin, from tying up memory or the caller.
+pub const WRITE_QUEUE_CHUNKS: usize = 256;
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TerminalWriteErrorEvent {
+    tab_id: String,
+    error: String,
+}
+
+enum QueuedInput {
+    Bytes(Vec<u8>),
+    /// Written one chunk at a time with a pause in between, as a single entry so nothing else
+    /// queued for the session lands in the middle.
+    Paced(Vec<Vec<u8>>, Duration),
+}
+
+/// Input side of a session. Bytes are queued and written by a dedicated thread, so a write that
+/// blocks on a full PTY buffer never holds up the command handler or the sessions lock.
+pub struct SessionWriter {
+    queue: SyncSender<QueuedInput>,
+}
+
+impl SessionWriter {
+    pub fn spawn(app: &tauri::AppHandle, tab_id: &str, mut writer: Box<dyn Write + Send>) -> Self {
+        let (queue, queued) = mpsc::sync_channel::<QueuedInput>(WRITE_QUEUE_CHUNKS);
+        let app = app.clone();
+        let tab_id = tab_id.to_string();
+
+        // Ends once the session drops its sender, or on the first failed write.
+        std::thread::spawn(move || {
+            while let Ok(input) = queued.recv() {
+                let written = match input {
+                    QueuedInput::Bytes(bytes) => writer.write_all(&bytes).and_then(|_| writer.flush()),
+                    QueuedInput::Paced(chunks, delay) => chunks.iter().enumerate().try_for_each(|(index, chunk)| {
+                        if index > 0 {
+                            std::thread::sleep(delay);
+                        }
+                        writer.write_all(chunk).and_then(|_| writer.flush())
+                    }),
+                };
+                if let Err(error) = written {
+                    windows::emit(
+                        &app,
+                        &tab_id,
+                        "terminal-write-error",
+                        TerminalWriteErrorEvent {
+                            tab_id: tab_id.clone(),
+                            error: format!("failed to write to pty: {error}"),
+                        },
+                    );
+                    break;
+                }
+            }
+        });
+
+        Self { queue }
+    }
+
+    /// Queues `bytes` without waiting for the write.
+    pub fn send(&self, bytes: &[u8]) -> Result<(), AppError> {
+        if bytes.is_empty() {
+            return Ok(());
+        }
+        self.enqueue(QueuedInput::Bytes(bytes.to_vec()))
+    }
+
+    /// Queues `chunks` to be written in order with `delay` between them.
+    pub fn send_paced(&self, chunks: Vec<Vec<u8>>, delay: Duration) -> Result<(), AppError> {
+        if chunks.is_empty() {
+            return Ok(());
+        }
+        self.enqueue(QueuedInput::Paced(chunks, delay))
+    }
+
+    fn enqueue(&self, input: QueuedInput) -> Result<(), AppError> {
+        self.queue.try_send(input).map_err(|error| match error {
+            TrySendError::Full(_) => AppError {
+                recoverable: true,
+                ..AppError::pty("terminal input queue is full")
+            },
+            TrySendError::Disconnected(_) => AppError::pty("terminal input is closed"),
+        })
+    }
+}